@@ -0,0 +1,50 @@
+//! Compares building a mipmap of narrow integers with a naive pairwise loop.
+//! Run with `cargo bench --bench build`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use mipmap_1d::MipMap1D;
+
+const LEN: usize = 10_000_000;
+
+/// Returns the fastest of several runs of `f`.
+fn time<R>(mut f: impl FnMut() -> R) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+/// Averages pairs level by level, truncating toward zero, without any bookkeeping.
+fn naive(source: &[i16]) -> Vec<Vec<i16>> {
+    let mut levels = vec![source.to_vec()];
+    while levels[levels.len() - 1].len() > 1 {
+        let below = &levels[levels.len() - 1];
+        let level = below
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => ((i32::from(*a) + i32::from(*b)) / 2) as i16,
+                _ => pair[0],
+            })
+            .collect();
+        levels.push(level);
+    }
+    levels
+}
+
+fn main() {
+    let source: Vec<i16> = (0..LEN).map(|x| ((x * 7919) % 65_536) as i16).collect();
+    let naive = time(|| naive(&source));
+    let mipmap = time(|| MipMap1D::new(source.clone()));
+    let floats: Vec<f32> = source.iter().map(|&x| f32::from(x)).collect();
+    let float_mipmap = time(|| MipMap1D::new(floats.clone()));
+
+    println!("naive pairwise loop, {LEN} i16: {naive:?}");
+    println!("MipMap1D::new, {LEN} i16: {mipmap:?}");
+    println!("MipMap1D::new, {LEN} f32: {float_mipmap:?}");
+}
@@ -0,0 +1,178 @@
+//! Derive macro for `mipmap_1d::Downsample`.
+//! Use it through the `derive` feature of the `mipmap-1d` crate.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Strategies which can be selected with `#[downsample(...)]`.
+/// Each of them maps to a function with the same name in `mipmap_1d::record`.
+const STRATEGIES: [&str; 6] = ["mean", "min", "max", "first", "last", "sum"];
+
+/// Derives `mipmap_1d::Downsample` for a struct with named fields.
+/// Every field is downsampled on its own, using the strategy given in `#[downsample(...)]`
+/// (one of `mean`, `min`, `max`, `first`, `last` or `sum`). Fields without the attribute are averaged.
+#[proc_macro_derive(Downsample, attributes(downsample))]
+pub fn derive_downsample(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err(message) => format!("compile_error!({message:?});").parse().unwrap(),
+    }
+}
+
+/// A struct field together with the strategy used to downsample it.
+struct Field {
+    name: String,
+    strategy: String,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let mut tokens = input.into_iter();
+    let mut name = None;
+
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ident) = token {
+            match ident.to_string().as_str() {
+                "struct" => {
+                    name = tokens.next();
+                    break;
+                }
+                "enum" | "union" => {
+                    return Err("`Downsample` can only be derived for structs".to_string())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let name = match name {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => return Err("Expected a struct name".to_string()),
+    };
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("`Downsample` can not be derived for generic structs".to_string())
+        }
+        _ => {
+            return Err(
+                "`Downsample` can only be derived for structs with named fields".to_string(),
+            )
+        }
+    };
+
+    let fields = split_fields(body)
+        .into_iter()
+        .map(parse_field)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let initializers: String = fields
+        .iter()
+        .map(|field| {
+            format!(
+                "{name}: ::mipmap_1d::record::{strategy}(bucket.iter().map(|sample| sample.{name})),",
+                name = field.name,
+                strategy = field.strategy,
+            )
+        })
+        .collect();
+
+    format!(
+        "impl ::mipmap_1d::Downsample for {name} {{
+            fn downsample(bucket: &[Self]) -> Self {{
+                Self {{ {initializers} }}
+            }}
+        }}"
+    )
+    .parse()
+    .map_err(|_| "Failed to generate the `Downsample` implementation".to_string())
+}
+
+/// Splits the body of a struct into the tokens of every field.
+/// Commas inside generic arguments of field types are not treated as separators.
+fn split_fields(body: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut fields = vec![];
+    let mut current = vec![];
+    let mut depth = 0usize;
+
+    for token in body {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    fields.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        current.push(token);
+    }
+
+    if !current.is_empty() {
+        fields.push(current);
+    }
+
+    fields
+}
+
+/// Extracts the name of a field and its `#[downsample(...)]` strategy.
+fn parse_field(tokens: Vec<TokenTree>) -> Result<Field, String> {
+    let mut strategy = None;
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(attribute)) = tokens.next() {
+                    if let Some(selected) = parse_attribute(attribute.stream())? {
+                        strategy = Some(selected);
+                    }
+                }
+            }
+            TokenTree::Ident(ident) if ident.to_string() == "pub" => {
+                if let Some(TokenTree::Group(_)) = tokens.peek() {
+                    tokens.next();
+                }
+            }
+            TokenTree::Ident(ident) => match tokens.next() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {
+                    return Ok(Field {
+                        name: ident.to_string(),
+                        strategy: strategy.unwrap_or_else(|| "mean".to_string()),
+                    })
+                }
+                _ => break,
+            },
+            _ => break,
+        }
+    }
+
+    Err("`Downsample` can only be derived for structs with named fields".to_string())
+}
+
+/// Returns the strategy of a `downsample(...)` attribute, or `None` for any other attribute.
+fn parse_attribute(attribute: TokenStream) -> Result<Option<String>, String> {
+    let mut tokens = attribute.into_iter();
+
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "downsample" => {}
+        _ => return Ok(None),
+    }
+
+    let arguments = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            group.stream().into_iter().collect::<Vec<_>>()
+        }
+        _ => return Err("Expected `#[downsample(<strategy>)]`".to_string()),
+    };
+
+    match arguments.as_slice() {
+        [TokenTree::Ident(ident)] if STRATEGIES.contains(&ident.to_string().as_str()) => {
+            Ok(Some(ident.to_string()))
+        }
+        _ => Err(format!(
+            "Unknown downsampling strategy, expected one of: {}",
+            STRATEGIES.join(", ")
+        )),
+    }
+}
@@ -0,0 +1,49 @@
+/* C API of mipmap-1d, available with the `ffi` (or `python`) feature. Kept in sync with src/ffi.rs. */
+
+#ifndef MIPMAP_1D_H
+#define MIPMAP_1D_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque handle to a mipmap of double samples. */
+typedef struct MipMapF64 MipMapF64;
+
+/* The part of a level which covers a viewport. */
+typedef struct MipMapSlice {
+    /* The level the data comes from. */
+    size_t level;
+    /* Index of the first element of `data` within the level. */
+    size_t start;
+    const double *data;
+    size_t len;
+} MipMapSlice;
+
+/* Creates a mipmap of `len` samples starting at `data`, which are copied. */
+MipMapF64 *mipmap_f64_new(const double *data, size_t len);
+
+/* Releases a mipmap. Null handles are ignored. */
+void mipmap_f64_free(MipMapF64 *mipmap);
+
+/* Appends a sample, updating all levels. Pointers to level data become invalid. */
+void mipmap_f64_push(MipMapF64 *mipmap, double value);
+
+/* Returns the total number of downsampled levels. */
+size_t mipmap_f64_num_levels(const MipMapF64 *mipmap);
+
+/* Returns the data on given level and writes its length to `len`.
+ * If the level is out of bounds, returns NULL and writes 0. */
+const double *mipmap_f64_get_level(const MipMapF64 *mipmap, size_t level, size_t *len);
+
+/* Returns the data of the finest level which displays the source samples `start..end`
+ * with at most `max_points` points. */
+MipMapSlice mipmap_f64_query(const MipMapF64 *mipmap, size_t start, size_t end, size_t max_points);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* MIPMAP_1D_H */
@@ -0,0 +1,150 @@
+"""Python bindings of mipmap-1d for `float` samples, available with the `python` feature.
+
+The module wraps the C API of `include/mipmap_1d.h` with ctypes, so it needs no compiler
+or Python dependencies. The library is built with
+`cargo rustc --release --features python --crate-type cdylib` and found through the
+`MIPMAP_1D_LIB` environment variable, or else on the system library path.
+
+Levels are returned as memoryviews of the data owned by the mipmap, without copying,
+so NumPy can wrap them with `numpy.asarray`. A view stays valid until the mipmap is
+modified or closed. Example:
+
+>>> from mipmap_1d import MipMap
+>>> mipmap = MipMap([2.0, 4.0, 6.0, 8.0])
+>>> mipmap.push(9.0)
+>>> mipmap.num_levels
+4
+>>> mipmap.level(1).tolist()
+[3.0, 7.0, 9.0]
+>>> level, start, data = mipmap.query(1, 5, 2)
+>>> (level, start, data.tolist())
+(2, 0, [5.0, 9.0])
+>>> mipmap.close()
+"""
+
+import array
+import ctypes
+import ctypes.util
+import os
+
+__all__ = ["MipMap"]
+
+
+class _Slice(ctypes.Structure):
+    _fields_ = [
+        ("level", ctypes.c_size_t),
+        ("start", ctypes.c_size_t),
+        ("data", ctypes.POINTER(ctypes.c_double)),
+        ("len", ctypes.c_size_t),
+    ]
+
+
+def _load():
+    path = os.environ.get("MIPMAP_1D_LIB") or ctypes.util.find_library("mipmap_1d")
+    if path is None:
+        raise ImportError("the mipmap-1d library was not found, set MIPMAP_1D_LIB to its path")
+    lib = ctypes.CDLL(path)
+    handle = ctypes.c_void_p
+    lib.mipmap_f64_new.argtypes = [ctypes.POINTER(ctypes.c_double), ctypes.c_size_t]
+    lib.mipmap_f64_new.restype = handle
+    lib.mipmap_f64_free.argtypes = [handle]
+    lib.mipmap_f64_free.restype = None
+    lib.mipmap_f64_push.argtypes = [handle, ctypes.c_double]
+    lib.mipmap_f64_push.restype = None
+    lib.mipmap_f64_num_levels.argtypes = [handle]
+    lib.mipmap_f64_num_levels.restype = ctypes.c_size_t
+    lib.mipmap_f64_get_level.argtypes = [handle, ctypes.c_size_t, ctypes.POINTER(ctypes.c_size_t)]
+    lib.mipmap_f64_get_level.restype = ctypes.POINTER(ctypes.c_double)
+    lib.mipmap_f64_query.argtypes = [handle, ctypes.c_size_t, ctypes.c_size_t, ctypes.c_size_t]
+    lib.mipmap_f64_query.restype = _Slice
+    return lib
+
+
+_lib = _load()
+
+
+def _samples(data):
+    """Returns the samples as a contiguous buffer of doubles, copying only if needed."""
+    try:
+        view = memoryview(data)
+    except TypeError:
+        view = None
+    if view is None or view.format != "d" or not view.c_contiguous:
+        view = memoryview(array.array("d", data))
+    return view
+
+
+class MipMap:
+    """A mipmap of `float` samples, see `MipMap1D` of the Rust crate.
+
+    The samples can be any buffer of doubles, e.g. a NumPy `float64` array, which is copied
+    without a Python loop, or any iterable of numbers.
+
+    >>> mipmap = MipMap([1.0, 2.0, 3.0])
+    >>> mipmap.level(3)
+    Traceback (most recent call last):
+    ...
+    IndexError: level 3 is out of bounds
+    >>> with MipMap(array.array("d", [])) as empty:
+    ...     empty.level(0).tolist()
+    []
+    """
+
+    def __init__(self, data=()):
+        view = _samples(data)
+        count = view.nbytes // 8
+        buffer = (ctypes.c_double * count).from_buffer_copy(view)
+        self._handle = _lib.mipmap_f64_new(buffer, count)
+
+    def push(self, value):
+        """Appends a sample, updating all levels. Views of the levels become invalid."""
+        _lib.mipmap_f64_push(self._valid(), value)
+
+    @property
+    def num_levels(self):
+        """The total number of levels, including the source."""
+        return _lib.mipmap_f64_num_levels(self._valid())
+
+    def level(self, level):
+        """Returns a view of the data on given level. Raises IndexError if it is out of bounds."""
+        count = ctypes.c_size_t()
+        data = _lib.mipmap_f64_get_level(self._valid(), level, ctypes.byref(count))
+        if not data:
+            raise IndexError(f"level {level} is out of bounds")
+        return self._view(data, count.value)
+
+    def query(self, start, end, max_points):
+        """Returns `(level, start, data)` of the finest level which displays the source samples
+        `start..end` with at most `max_points` points, where `start` is the index of the first
+        element of `data` within the level."""
+        result = _lib.mipmap_f64_query(self._valid(), start, end, max_points)
+        return result.level, result.start, self._view(result.data, result.len)
+
+    def close(self):
+        """Releases the mipmap. Views of the levels become invalid."""
+        if self._handle is not None:
+            _lib.mipmap_f64_free(self._handle)
+            self._handle = None
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+    def __del__(self):
+        if getattr(self, "_handle", None) is not None:
+            self.close()
+
+    def _valid(self):
+        if self._handle is None:
+            raise ValueError("the mipmap is closed")
+        return self._handle
+
+    def _view(self, data, count):
+        if count == 0:
+            return memoryview(array.array("d"))
+        elements = (ctypes.c_double * count).from_address(ctypes.addressof(data.contents))
+        # The view keeps the mipmap alive, so it is not released by the garbage collector.
+        elements._mipmap = self
+        return memoryview(elements).cast("B").cast("d")
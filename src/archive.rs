@@ -0,0 +1,509 @@
+use std::any::TypeId;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Range;
+use std::path::Path;
+use std::{fmt, fs};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::bytes::{as_bytes, as_bytes_mut, from_bytes, Pod};
+use crate::{MipMap1D, MipMapConfig, NanPolicy, Reduction, Rounding};
+
+const MAGIC: [u8; 8] = *b"MIPMAP1D";
+const VERSION: u64 = 3;
+/// Written in native byte order, so that archives from a machine with another byte order are rejected.
+const BYTE_ORDER: u64 = 0x0102_0304_0506_0708;
+/// Alignment of every level within the archive, which suffices for all primitive types.
+const ALIGNMENT: usize = 16;
+/// Number of `u64` fields after the magic bytes.
+const FIELDS: usize = 10;
+/// Every level has `ceil(len / FACTOR)` elements of the level below.
+const FACTOR: u64 = 2;
+/// Configuration values in the order of their codes in the header.
+const NAN_POLICIES: [NanPolicy; 3] = [NanPolicy::Propagate, NanPolicy::Skip, NanPolicy::Missing];
+const ROUNDINGS: [Rounding; 5] = [
+    Rounding::TowardZero,
+    Rounding::Floor,
+    Rounding::Ceil,
+    Rounding::HalfEven,
+    Rounding::HalfAway,
+];
+const REDUCTIONS: [Reduction; 5] = [
+    Reduction::Mean,
+    Reduction::Min,
+    Reduction::Max,
+    Reduction::First,
+    Reduction::Last,
+];
+
+/// Defines the element types in the order of their codes in the header, starting from `1`.
+/// Unlike `std::any::type_name`, the codes do not depend on the compiler.
+macro_rules! element_types {
+    ($($t:ty),*) => {
+        const TYPE_NAMES: &[&str] = &[$(stringify!($t)),*];
+
+        fn type_ids() -> Vec<TypeId> {
+            vec![$(TypeId::of::<$t>()),*]
+        }
+    };
+}
+
+element_types!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// Returns the code of an element type, or None if it is not a primitive integer or float type.
+/// `usize` and `isize` have the code of the fixed-size integer of the same width.
+fn type_code<T: 'static>() -> Option<u64> {
+    let id = TypeId::of::<T>();
+    let id = match size_of::<usize>() {
+        _ if id != TypeId::of::<usize>() && id != TypeId::of::<isize>() => id,
+        4 if id == TypeId::of::<usize>() => TypeId::of::<u32>(),
+        4 => TypeId::of::<i32>(),
+        _ if id == TypeId::of::<usize>() => TypeId::of::<u64>(),
+        _ => TypeId::of::<i64>(),
+    };
+    let position = type_ids().iter().position(|&t| t == id)?;
+    Some(position as u64 + 1)
+}
+
+/// Returns the name of the element type with given code.
+fn element_name(code: u64) -> String {
+    usize::try_from(code)
+        .ok()
+        .and_then(|code| TYPE_NAMES.get(code.checked_sub(1)?))
+        .map_or_else(|| format!("type code {code}"), |name| name.to_string())
+}
+
+/// Describes why bytes could not be opened as an [`ArchivedMipMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The bytes do not start with the archive header.
+    NotAnArchive,
+    /// The archive was written by an incompatible version of the format.
+    UnsupportedVersion(u64),
+    /// The archive was written on a machine with another byte order.
+    ByteOrder,
+    /// The archive stores elements of another type.
+    TypeMismatch { expected: String, found: String },
+    /// The bytes end before the data described by the header.
+    Truncated,
+    /// The bytes are not aligned for the element type, e.g. because they were read into an unaligned buffer.
+    Misaligned,
+    /// The header describes an unknown configuration, or the levels do not fit together.
+    Corrupted,
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnArchive => write!(f, "the bytes are not a mipmap archive"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "archive version {version} is not supported")
+            }
+            Self::ByteOrder => write!(f, "the archive was written with another byte order"),
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "the archive stores {found}, expected {expected}")
+            }
+            Self::Truncated => write!(f, "the archive is truncated"),
+            Self::Misaligned => write!(f, "the archive is not aligned for its element type"),
+            Self::Corrupted => write!(f, "the archive is corrupted"),
+        }
+    }
+}
+
+impl Error for ArchiveError {}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Pod> MipMap1D<T> {
+    /// Writes all levels into an archive which can be opened with [`ArchivedMipMap::from_bytes`] without copying,
+    /// e.g. after mapping the file into memory.
+    /// Elements are stored in native byte order, and every level is aligned to 16 bytes.
+    /// The header also stores the configuration, so that [`MipMap1D::load`] restores an equivalent mipmap.
+    ///
+    /// # Panics
+    /// Panics if `T` is not a primitive integer or float type.
+    pub fn to_archive(&self) -> Vec<u8> {
+        let element =
+            type_code::<T>().expect("Only primitive integer and float types can be archived");
+        let config = self.config();
+        let mut bytes = MAGIC.to_vec();
+        for field in [
+            VERSION,
+            BYTE_ORDER,
+            FACTOR,
+            code(&NAN_POLICIES, config.nan_policy),
+            code(&ROUNDINGS, config.rounding),
+            code(&REDUCTIONS, config.reduction),
+            config.max_levels.map_or(u64::MAX, |max| max as u64),
+            element,
+            self.num_levels() as u64,
+            self.min_level() as u64,
+        ] {
+            bytes.extend(field.to_ne_bytes());
+        }
+
+        let table = bytes.len().next_multiple_of(ALIGNMENT);
+        bytes.resize(table + 16 * self.num_levels(), 0);
+        for level in 0..self.num_levels() {
+            let data = as_bytes(self.get_level(level).unwrap_or_default());
+            let start = bytes.len().next_multiple_of(ALIGNMENT);
+            let entry = table + 16 * level;
+            bytes[entry..entry + 8].copy_from_slice(&(start as u64).to_ne_bytes());
+            bytes[entry + 8..entry + 16].copy_from_slice(&(data.len() as u64).to_ne_bytes());
+            bytes.resize(start, 0);
+            bytes.extend_from_slice(data);
+        }
+        bytes
+    }
+
+    /// Writes the archive of [`MipMap1D::to_archive`] to a file, so that the levels need not be computed again.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, MipMapConfig, Reduction};
+    ///
+    /// let config = MipMapConfig { reduction: Reduction::Max, ..Default::default() };
+    /// let mipmap = MipMap1D::from_config(vec![2.0, 4.0, 6.0, 8.0, 9.0], config);
+    /// let path = std::env::temp_dir().join("mipmap-1d-save-doctest.bin");
+    /// mipmap.save(&path).unwrap();
+    ///
+    /// let loaded = MipMap1D::<f64>::load(&path).unwrap();
+    /// assert_eq!(loaded.reduction(), Reduction::Max);
+    /// assert_eq!(*loaded.get_level(1).unwrap(), [4.0, 8.0, 9.0]);
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_archive())
+    }
+
+    /// Reads a file written by [`MipMap1D::save`], restoring the levels and the configuration.
+    /// Pruned levels stay pruned.
+    /// An archive which is invalid or stores another element type is reported
+    /// as [`io::ErrorKind::InvalidData`] wrapping an [`ArchiveError`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, ArchiveError::Truncated))?;
+        // Reads into a buffer aligned for all primitive types.
+        let mut buffer = vec![0u128; len.div_ceil(ALIGNMENT)];
+        let bytes = &mut as_bytes_mut(&mut buffer)[..len];
+        file.read_exact(bytes)?;
+
+        let invalid = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+        let archived = ArchivedMipMap::<T>::from_bytes(bytes).map_err(invalid)?;
+        let levels = (0..archived.num_levels())
+            .map(|level| archived.get_level(level).unwrap_or_default().to_vec())
+            .collect();
+        Self::from_levels(levels, archived.config(), archived.min_level())
+            .ok_or_else(|| invalid(ArchiveError::Corrupted))
+    }
+}
+
+/// Returns the position of a configuration value in the header.
+fn code<V: PartialEq>(values: &[V], value: V) -> u64 {
+    values.iter().position(|v| *v == value).unwrap() as u64
+}
+
+/// Returns the configuration value at a position read from the header.
+fn decode<V: Copy>(values: &[V], code: u64) -> Result<V, ArchiveError> {
+    usize::try_from(code)
+        .ok()
+        .and_then(|code| values.get(code).copied())
+        .ok_or(ArchiveError::Corrupted)
+}
+
+/// The header of an archive, which describes where the levels are without reading them.
+pub(crate) struct Header {
+    pub(crate) config: MipMapConfig,
+    pub(crate) min_level: usize,
+    /// Byte range of every level within the archive.
+    pub(crate) levels: Vec<Range<usize>>,
+}
+
+impl Header {
+    /// Validates the header at the start of `bytes`, which need not contain the levels.
+    pub(crate) fn parse<T: 'static>(bytes: &[u8]) -> Result<Self, ArchiveError> {
+        if bytes.get(..8) != Some(&MAGIC[..]) {
+            return Err(ArchiveError::NotAnArchive);
+        }
+        let field = |index: usize| -> Result<u64, ArchiveError> {
+            let start = 8 * index;
+            let field = bytes.get(start..start + 8).ok_or(ArchiveError::Truncated)?;
+            Ok(u64::from_ne_bytes(field.try_into().unwrap()))
+        };
+        let version = field(1)?;
+        if version != VERSION && version.swap_bytes() != VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version));
+        }
+        if field(2)? != BYTE_ORDER {
+            return Err(ArchiveError::ByteOrder);
+        }
+        if field(3)? != FACTOR {
+            return Err(ArchiveError::Corrupted);
+        }
+        let config = MipMapConfig {
+            nan_policy: decode(&NAN_POLICIES, field(4)?)?,
+            rounding: decode(&ROUNDINGS, field(5)?)?,
+            reduction: decode(&REDUCTIONS, field(6)?)?,
+            max_levels: match field(7)? {
+                u64::MAX => None,
+                max => Some(max as usize),
+            },
+        };
+        let found = field(8)?;
+        if Some(found) != type_code::<T>() {
+            return Err(ArchiveError::TypeMismatch {
+                expected: std::any::type_name::<T>().to_string(),
+                found: element_name(found),
+            });
+        }
+        let size = |field: u64| usize::try_from(field).map_err(|_| ArchiveError::Corrupted);
+        let (num_levels, min_level) = (size(field(9)?)?, size(field(10)?)?);
+        // A mipmap of at most `usize::MAX` samples has fewer levels than `usize` has bits.
+        if min_level >= num_levels || num_levels > usize::BITS as usize {
+            return Err(ArchiveError::Corrupted);
+        }
+
+        let table = (8 * (FIELDS + 1)).next_multiple_of(ALIGNMENT) / 8;
+        let levels: Vec<Range<usize>> = (0..num_levels)
+            .map(|level| {
+                let (start, len) = (field(table + 2 * level)?, field(table + 2 * level + 1)?);
+                usize::try_from(start)
+                    .ok()
+                    .zip(usize::try_from(len).ok())
+                    .and_then(|(start, len)| Some(start..start.checked_add(len)?))
+                    .ok_or(ArchiveError::Truncated)
+            })
+            .collect::<Result<_, _>>()?;
+
+        // Every level above the finest kept one halves the level below, until a single element is left.
+        let lens: Vec<_> = levels[min_level..]
+            .iter()
+            .map(|range| range.len() / size_of::<T>().max(1))
+            .collect();
+        let fits = lens
+            .windows(2)
+            .all(|pair| pair[0] > 1 && pair[1] == pair[0].div_ceil(2));
+        if !fits || (min_level > 0 && lens[0].leading_zeros() < min_level as u32) {
+            return Err(ArchiveError::Corrupted);
+        }
+
+        Ok(Self {
+            config,
+            min_level,
+            levels,
+        })
+    }
+}
+
+/// Levels of a mipmap read directly from the bytes of an archive written by [`MipMap1D::to_archive`].
+/// Only the header is parsed, the levels are borrowed from the bytes, so opening is independent of their size.
+/// The archive format is specific to this crate and not compatible with `rkyv`.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{ArchivedMipMap, MipMap1D};
+///
+/// let archive = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]).to_archive();
+/// // The bytes must be aligned for the element type, e.g. when they are memory-mapped.
+/// let mut aligned = vec![0u128; archive.len().div_ceil(16)];
+/// let bytes = &mut mipmap_1d::bytes::as_bytes_mut(&mut aligned)[..archive.len()];
+/// bytes.copy_from_slice(&archive);
+///
+/// let archived = ArchivedMipMap::<f64>::from_bytes(bytes).unwrap();
+/// assert_eq!(archived.num_levels(), 4);
+/// assert_eq!(*archived.get_level(1).unwrap(), [3.0, 7.0, 9.0]);
+/// ```
+pub struct ArchivedMipMap<'a, T> {
+    levels: Vec<&'a [T]>,
+    min_level: usize,
+    config: MipMapConfig,
+}
+
+impl<'a, T: Pod> ArchivedMipMap<'a, T> {
+    /// Validates the header and locates the levels.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        let header = Header::parse::<T>(bytes)?;
+        let levels = header
+            .levels
+            .into_iter()
+            .map(|range| {
+                let data = bytes.get(range).ok_or(ArchiveError::Truncated)?;
+                from_bytes(data).ok_or(ArchiveError::Misaligned)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            levels,
+            min_level: header.min_level,
+            config: header.config,
+        })
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the finest level which was not pruned before archiving.
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Returns the configuration of the archived mipmap.
+    pub fn config(&self) -> MipMapConfig {
+        self.config
+    }
+
+    /// Returns the data on given level.
+    /// If the level is out of bounds or was pruned, returns None
+    pub fn get_level(&self, level: usize) -> Option<&'a [T]> {
+        if level < self.min_level {
+            return None;
+        }
+        self.levels.get(level).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies bytes into a buffer aligned to 16 bytes.
+    fn aligned(bytes: &[u8]) -> Vec<u128> {
+        let mut buffer = vec![0u128; bytes.len().div_ceil(16)];
+        as_bytes_mut(&mut buffer)[..bytes.len()].copy_from_slice(bytes);
+        buffer
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let mut mipmap = MipMap1D::new((0..99).map(|x| x * 3 % 7).collect::<Vec<i16>>());
+        mipmap.prune_below(1);
+        let archive = mipmap.to_archive();
+        let buffer = aligned(&archive);
+        let archived =
+            ArchivedMipMap::<i16>::from_bytes(&as_bytes(&buffer)[..archive.len()]).unwrap();
+
+        assert_eq!(archived.num_levels(), mipmap.num_levels());
+        assert_eq!(archived.min_level(), 1);
+        for level in 0..=mipmap.num_levels() {
+            assert_eq!(archived.get_level(level), mipmap.get_level(level));
+        }
+    }
+
+    #[test]
+    fn test_invalid_archives() {
+        let archive = MipMap1D::new(vec![1u32, 2, 3]).to_archive();
+        let buffer = aligned(&archive);
+        let bytes = &as_bytes(&buffer)[..archive.len()];
+
+        assert_eq!(
+            ArchivedMipMap::<u32>::from_bytes(&bytes[1..]).err(),
+            Some(ArchiveError::NotAnArchive)
+        );
+        assert_eq!(
+            ArchivedMipMap::<u32>::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(ArchiveError::Truncated)
+        );
+        let error = ArchivedMipMap::<f32>::from_bytes(bytes).err().unwrap();
+        assert_eq!(error.to_string(), "the archive stores u32, expected f32");
+
+        let mut swapped = bytes.to_vec();
+        swapped[16..24].reverse();
+        let swapped = aligned(&swapped);
+        assert_eq!(
+            ArchivedMipMap::<u32>::from_bytes(&as_bytes(&swapped)[..archive.len()]).err(),
+            Some(ArchiveError::ByteOrder)
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let config = MipMapConfig {
+            nan_policy: NanPolicy::Missing,
+            rounding: Rounding::HalfEven,
+            reduction: Reduction::Mean,
+            max_levels: Some(4),
+        };
+        let source: Vec<f32> = (0..40)
+            .map(|x| if x % 3 == 0 { f32::NAN } else { x as f32 })
+            .collect();
+        let mipmap = MipMap1D::from_config(source, config);
+        let path = std::env::temp_dir().join(format!("mipmap-1d-test-{}.bin", std::process::id()));
+        mipmap.save(&path).unwrap();
+        let mut loaded = MipMap1D::<f32>::load(&path).unwrap();
+        let error = MipMap1D::<f64>::load(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.config(), config);
+        assert_eq!(loaded.num_levels(), 4);
+        assert_eq!(loaded.verify(), Ok(()));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "the archive stores f32, expected f64");
+
+        // Sample counts are restored, so pushing continues the same means.
+        let mut original = mipmap.clone();
+        for x in [1.0, f32::NAN, 3.0] {
+            original.push(x);
+            loaded.push(x);
+        }
+        for level in 0..original.num_levels() {
+            let (a, b) = (
+                original.get_level(level).unwrap(),
+                loaded.get_level(level).unwrap(),
+            );
+            assert!(a
+                .iter()
+                .zip(b)
+                .all(|(a, b)| a == b || (a.is_nan() && b.is_nan())));
+        }
+    }
+
+    #[test]
+    fn test_corrupted_header() {
+        let mut archive = MipMap1D::new(vec![1u8, 2, 3]).to_archive();
+        archive[8..16].copy_from_slice(&1u64.to_ne_bytes());
+        assert_eq!(
+            ArchivedMipMap::<u8>::from_bytes(&archive).err(),
+            Some(ArchiveError::UnsupportedVersion(1))
+        );
+        archive[8..16].copy_from_slice(&VERSION.to_ne_bytes());
+        let original = archive.clone();
+
+        // Unknown reduction, number of levels, finest kept level and length of level 1.
+        for (range, value) in [(40..48, 7), (72..80, u64::MAX), (80..88, 3), (120..128, 5)] {
+            archive.copy_from_slice(&original);
+            archive[range].copy_from_slice(&value.to_ne_bytes());
+            assert_eq!(
+                ArchivedMipMap::<u8>::from_bytes(&archive).err(),
+                Some(ArchiveError::Corrupted)
+            );
+        }
+
+        archive.copy_from_slice(&original);
+        archive[64..72].copy_from_slice(&99u64.to_ne_bytes());
+        let error = ArchivedMipMap::<u8>::from_bytes(&archive).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "the archive stores type code 99, expected u8"
+        );
+    }
+
+    #[test]
+    fn test_pointer_sized_integers() {
+        let archive = MipMap1D::new(vec![1usize, 2, 3]).to_archive();
+        let buffer = aligned(&archive);
+        let bytes = &as_bytes(&buffer)[..archive.len()];
+
+        // `usize` is stored as the fixed-size integer of the same width, so both types open the archive.
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            ArchivedMipMap::<u64>::from_bytes(bytes)
+                .unwrap()
+                .get_level(1),
+            Some(&[1, 3][..])
+        );
+        let archived = ArchivedMipMap::<usize>::from_bytes(bytes).unwrap();
+        assert_eq!(archived.get_level(2), Some(&[2][..]));
+        assert!(ArchivedMipMap::<isize>::from_bytes(bytes).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::vec;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::storage::{LevelStorage, VecStorage};
+use crate::{MipMap1D, NanPolicy, Reduction};
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Adds the samples of two mipmaps of the same length, e.g. to overlay two channels.
+    /// If both mipmaps have the same configuration, propagate NaNs and keep means, first or last elements,
+    /// the sum is computed level by level. Otherwise its levels are rebuilt from the summed sources
+    /// with the configuration of this mipmap.
+    /// Integer means computed level by level may differ by the rounding from rebuilt ones.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let a = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+    /// let b = MipMap1D::new(vec![1.0, 1.0, 2.0, 2.0, 3.0]);
+    /// assert_eq!(*a.add(&b).get_level(1).unwrap(), [4.0, 9.0, 12.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the mipmaps have different lengths, or if the levels must be rebuilt
+    /// and level `0` of either mipmap was dropped by [`MipMap1D::prune_below`].
+    pub fn add(&self, other: &Self) -> Self {
+        self.combine(other, true, |a, b| a.clone() + b.clone())
+    }
+
+    /// Same as [`MipMap1D::add`], but subtracts the samples of `other`, e.g. a baseline.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let signal = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+    /// let baseline = MipMap1D::new(vec![1.0, 1.0, 2.0, 2.0, 3.0]);
+    /// assert_eq!(*signal.sub(&baseline).get_level(2).unwrap(), [3.5, 6.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`MipMap1D::add`].
+    pub fn sub(&self, other: &Self) -> Self {
+        self.combine(other, true, |a, b| a.clone() - b.clone())
+    }
+
+    /// Divides the samples of this mipmap by those of `other` as `f64`, e.g. for a gain between two signals.
+    /// Division by zero gives infinities or NaN.
+    /// A mean of ratios is not the ratio of means, so the levels are rebuilt unless both mipmaps
+    /// keep first or last elements.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let a = MipMap1D::new(vec![2, 4, 6, 8]);
+    /// let b = MipMap1D::new(vec![1, 2, 2, 2]);
+    /// assert_eq!(*a.ratio(&b).get_level(1).unwrap(), [2.0, 3.5]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`MipMap1D::add`].
+    pub fn ratio(&self, other: &Self) -> MipMap1D<f64> {
+        self.combine(other, false, |a, b| {
+            a.to_f64().unwrap() / b.to_f64().unwrap()
+        })
+    }
+
+    /// Combines the samples of two mipmaps with `f`, which is `linear` if it commutes with means.
+    fn combine<U: Num + ToPrimitive + FromPrimitive + Clone>(
+        &self,
+        other: &Self,
+        linear: bool,
+        f: impl Fn(&T, &T) -> U,
+    ) -> MipMap1D<U> {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "The mipmaps must have the same length"
+        );
+        let commutes = match self.reduction() {
+            Reduction::First | Reduction::Last => true,
+            Reduction::Mean => linear,
+            Reduction::Min | Reduction::Max => false,
+        };
+        let level_wise = commutes
+            && self.config() == other.config()
+            && self.nan_policy() == NanPolicy::Propagate
+            && self.min_level() == other.min_level();
+        let zip = |level: usize| match (self.get_level(level), other.get_level(level)) {
+            (Some(a), Some(b)) => a.iter().zip(b).map(|(a, b)| f(a, b)).collect(),
+            _ => panic!("Can not rebuild after the source was pruned"),
+        };
+
+        if !level_wise {
+            return MipMap1D::from_config(zip(0), self.config());
+        }
+        let mut data = VecStorage::default();
+        for level in 0..self.num_levels() {
+            match level < self.min_level() {
+                true => data.push_level(vec![]),
+                false => data.push_level(zip(level)),
+            }
+        }
+        self.with_levels(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_rebuilt() {
+        let a: Vec<f64> = (0..37).map(|x| ((x * 7919) % 101) as f64).collect();
+        let b: Vec<f64> = (0..37).map(|x| ((x * 104_729) % 37) as f64 + 1.0).collect();
+        for reduction in [Reduction::Mean, Reduction::Max, Reduction::Last] {
+            let (mut x, mut y) = (MipMap1D::new(a.clone()), MipMap1D::new(b.clone()));
+            x.rebuild_levels_with(reduction);
+            y.rebuild_levels_with(reduction);
+
+            type Op = fn(f64, f64) -> f64;
+            let cases: [(MipMap1D<f64>, Op); 3] = [
+                (x.add(&y), |a, b| a + b),
+                (x.sub(&y), |a, b| a - b),
+                (x.ratio(&y), |a, b| a / b),
+            ];
+            for (combined, f) in cases {
+                let source = a.iter().zip(&b).map(|(&a, &b)| f(a, b)).collect();
+                let mut rebuilt = MipMap1D::new(source);
+                rebuilt.rebuild_levels_with(reduction);
+                for level in 0..rebuilt.num_levels() {
+                    let pairs = combined.get_level(level).unwrap().iter();
+                    let mut pairs = pairs.zip(rebuilt.get_level(level).unwrap());
+                    assert!(pairs.all(|(x, y)| (x - y).abs() < 1e-9));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pruned_and_mixed_mipmaps() {
+        let (mut a, mut b) = (
+            MipMap1D::new(vec![1, 2, 3, 4, 5]),
+            MipMap1D::new(vec![5; 5]),
+        );
+        a.prune_below(1);
+        b.prune_below(1);
+        let sum = a.add(&b);
+        assert_eq!(sum.min_level(), 1);
+        assert_eq!(*sum.get_level(1).unwrap(), [6, 8, 10]);
+
+        // Different reductions are rebuilt with the reduction of the first mipmap.
+        let a = MipMap1D::new(vec![1, 2, 3, 4]);
+        let mut b = MipMap1D::new(vec![4, 3, 2, 1]);
+        b.rebuild_levels_with(Reduction::Max);
+        assert_eq!(*a.sub(&b).get_level(1).unwrap(), [-2, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_different_lengths_panic() {
+        MipMap1D::new(vec![1, 2, 3]).add(&MipMap1D::new(vec![1, 2]));
+    }
+}
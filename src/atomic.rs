@@ -0,0 +1,193 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+/// Levels shared by the writer and the readers.
+struct AtomicLevels {
+    /// Bit patterns of the samples of every level, allocated for the full capacity.
+    levels: Vec<Vec<AtomicU32>>,
+    /// Number of published source samples.
+    len: AtomicUsize,
+}
+
+/// The appending side of an atomic mipmap, created by [`atomic_mipmap`]. There is only one writer.
+pub struct AtomicWriter {
+    levels: Arc<AtomicLevels>,
+    len: usize,
+}
+
+/// The reading side of an atomic mipmap, created by [`atomic_mipmap`]. Clones read the same levels.
+#[derive(Clone)]
+pub struct AtomicReader {
+    levels: Arc<AtomicLevels>,
+}
+
+/// Creates the writer and reader of levels of up to `capacity` `f32` samples, which one thread appends to
+/// without blocking or allocating, e.g. an audio capture thread, while other threads read them without locks.
+/// The levels are the same as those of [`MipMap1D::new`](crate::MipMap1D::new).
+/// Every append takes `O(log capacity)` steps, and readers see it once it updated all levels.
+/// Example:
+/// ```rust
+/// use mipmap_1d::atomic::atomic_mipmap;
+///
+/// let (mut writer, reader) = atomic_mipmap(1 << 20);
+/// std::thread::spawn(move || {
+///     for sample in [2.0, 4.0, 6.0, 8.0, 9.0] {
+///         writer.push(sample).unwrap();
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// assert_eq!(reader.len(), 5);
+/// assert_eq!(reader.level(1).unwrap(), [3.0, 7.0, 9.0]);
+/// ```
+pub fn atomic_mipmap(capacity: usize) -> (AtomicWriter, AtomicReader) {
+    let levels = (0..num_levels(capacity))
+        .map(|level| {
+            (0..level_len(capacity, level))
+                .map(|_| AtomicU32::new(0))
+                .collect()
+        })
+        .collect();
+    let levels = Arc::new(AtomicLevels {
+        levels,
+        len: AtomicUsize::new(0),
+    });
+
+    (
+        AtomicWriter {
+            levels: Arc::clone(&levels),
+            len: 0,
+        },
+        AtomicReader { levels },
+    )
+}
+
+impl AtomicWriter {
+    /// Appends a sample and publishes it to the readers.
+    /// If the capacity is exhausted, returns the sample as an error.
+    pub fn push(&mut self, value: f32) -> Result<(), f32> {
+        let levels = &self.levels.levels;
+        let index = self.len;
+        if index >= levels[0].len() {
+            return Err(value);
+        }
+
+        levels[0][index].store(value.to_bits(), Ordering::Relaxed);
+        for level in 1..levels.len() {
+            // The buckets below were written by this thread, so they are read back relaxed.
+            let (below, bucket) = (&levels[level - 1], index >> level);
+            let first = f32::from_bits(below[2 * bucket].load(Ordering::Relaxed));
+            let mean = match 2 * bucket < index >> (level - 1) {
+                true => {
+                    (first + f32::from_bits(below[2 * bucket + 1].load(Ordering::Relaxed))) / 2.0
+                }
+                false => first,
+            };
+            levels[level][bucket].store(mean.to_bits(), Ordering::Relaxed);
+        }
+
+        self.len += 1;
+        self.levels.len.store(self.len, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the number of appended samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no samples were appended.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl AtomicReader {
+    /// Returns the number of published source samples.
+    pub fn len(&self) -> usize {
+        self.levels.len.load(Ordering::Acquire)
+    }
+
+    /// Returns true if no samples were published.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of levels of the published samples, like [`MipMap1D::num_levels`](crate::MipMap1D::num_levels).
+    pub fn num_levels(&self) -> usize {
+        num_levels(self.len())
+    }
+
+    /// Copies the published data on given level.
+    /// While the writer appends, the last element may already include samples published afterwards.
+    /// If the level is out of bounds, returns None
+    pub fn level(&self, level: usize) -> Option<Vec<f32>> {
+        let len = self.len();
+        if level >= num_levels(len) {
+            return None;
+        }
+
+        let data = &self.levels.levels[level][..level_len(len, level)];
+        Some(
+            data.iter()
+                .map(|bits| f32::from_bits(bits.load(Ordering::Relaxed)))
+                .collect(),
+        )
+    }
+}
+
+/// Returns the number of levels of `len` source samples.
+fn num_levels(len: usize) -> usize {
+    1 + len.max(1).next_power_of_two().trailing_zeros() as usize
+}
+
+/// Returns the length of a level of `len` source samples.
+fn level_len(len: usize, level: usize) -> usize {
+    len.div_ceil(1 << level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_levels_match_mipmap() {
+        let source: Vec<f32> = (0..1000).map(|x| (x as f32 * 0.1).sin()).collect();
+        let (mut writer, reader) = atomic_mipmap(1024);
+        for &sample in &source {
+            writer.push(sample).unwrap();
+        }
+
+        let mipmap = MipMap1D::new(source);
+        assert_eq!(reader.num_levels(), mipmap.num_levels());
+        for level in 0..mipmap.num_levels() {
+            assert_eq!(
+                reader.level(level).unwrap(),
+                mipmap.get_level(level).unwrap()
+            );
+        }
+        assert_eq!(reader.level(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_concurrent_reads_and_capacity() {
+        let (mut writer, reader) = atomic_mipmap(10_000);
+        let handle = std::thread::spawn(move || {
+            for x in 0..10_000 {
+                writer.push(1.0 + (x % 2) as f32).unwrap();
+            }
+            writer.push(0.0)
+        });
+
+        while !handle.is_finished() {
+            let top = reader.level(reader.num_levels() - 1).unwrap_or_default();
+            // Every bucket averages samples of 1.0 and 2.0, even while it is updated.
+            assert!(top.iter().all(|&x| (1.0..=2.0).contains(&x)));
+        }
+        assert_eq!(handle.join().unwrap(), Err(0.0));
+        assert_eq!(reader.level(13).unwrap(), [1.5, 1.5]);
+        assert!(atomic_mipmap(0).1.is_empty());
+    }
+}
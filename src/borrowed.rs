@@ -0,0 +1,85 @@
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::OverviewMipMap;
+
+/// Creates several downsampled versions of a borrowed slice, keeping only a reference to it as level `0`,
+/// e.g. when the source lives in a memory-mapped file or a page cache.
+/// The downsampled levels are owned.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1DRef;
+///
+/// let data = vec![2, 4, 6, 8, 9];
+/// let mipmap = MipMap1DRef::new(&data);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// assert_eq!(*mipmap.get_level(0).unwrap(), [2, 4, 6, 8, 9]);
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 7, 9]);
+/// assert_eq!(mipmap.get_level(4), None);
+/// ```
+pub struct MipMap1DRef<'a, T: Num + ToPrimitive + FromPrimitive> {
+    source: &'a [T],
+    overview: OverviewMipMap<T>,
+}
+
+impl<'a, T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1DRef<'a, T> {
+    pub fn new(source: &'a [T]) -> Self {
+        Self {
+            source,
+            overview: OverviewMipMap::new(source),
+        }
+    }
+
+    /// Returns the borrowed source data.
+    pub fn source(&self) -> &'a [T] {
+        self.source
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.overview.num_levels()
+    }
+
+    /// Returns the data on given level.
+    /// Level `0` returns the borrowed source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[T]> {
+        self.overview.get_level_or(level, self.source)
+    }
+
+    /// Returns the finest level which has at most `max_points` elements.
+    /// If even the coarsest level is larger, returns the coarsest level.
+    pub fn level_within(&self, max_points: usize) -> usize {
+        (0..self.num_levels())
+            .find(|&level| self.get_level(level).unwrap().len() <= max_points)
+            .unwrap_or(self.num_levels() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_matches_owned_mipmap() {
+        let source: Vec<u16> = (0..51).map(|x| x * 7 % 13).collect();
+        let owned = MipMap1D::new(source.clone());
+        let borrowed = MipMap1DRef::new(&source);
+
+        assert_eq!(borrowed.num_levels(), owned.num_levels());
+        for level in 0..=owned.num_levels() {
+            assert_eq!(borrowed.get_level(level), owned.get_level(level));
+        }
+        assert_eq!(borrowed.level_within(10), owned.level_within(10));
+        assert!(std::ptr::eq(borrowed.source(), source.as_slice()));
+    }
+
+    #[test]
+    fn test_empty_source() {
+        let mipmap = MipMap1DRef::<f32>::new(&[]);
+        assert_eq!(mipmap.num_levels(), 1);
+        assert_eq!(mipmap.get_level(0), Some(&[][..]));
+        assert_eq!(mipmap.level_within(0), 0);
+    }
+}
@@ -0,0 +1,190 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::resample::reduce;
+use crate::{MipMapConfig, Reduction};
+
+/// One of the views built together by [`MipMapBundle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Levels combining buckets like a [`MipMap1D`](crate::MipMap1D) rebuilt with the reduction.
+    Reduction(Reduction),
+    /// Root mean square of every bucket as `f64`, e.g. the loudness of an audio signal.
+    Rms,
+}
+
+/// Levels of one source combined with several strategies at once, e.g. the mean, an envelope
+/// and the RMS for an audio overview. Every level is traversed once for all strategies,
+/// and the source is stored only once.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMapBundle, Reduction, Strategy};
+///
+/// let strategies = [
+///     Strategy::Reduction(Reduction::Min),
+///     Strategy::Reduction(Reduction::Max),
+///     Strategy::Rms,
+/// ];
+/// let bundle = MipMapBundle::new(vec![3.0, -4.0, 1.0, 7.0, 5.0], &strategies);
+/// assert_eq!(bundle.num_levels(), 4);
+/// assert_eq!(*bundle.get_level(Reduction::Min, 1).unwrap(), [-4.0, 1.0, 5.0]);
+/// assert_eq!(*bundle.get_level(Reduction::Max, 2).unwrap(), [7.0, 5.0]);
+/// assert_eq!(bundle.get_level(Reduction::Mean, 1), None);
+/// assert_eq!(*bundle.rms_level(1).unwrap(), [12.5f64.sqrt(), 5.0, 5.0]);
+/// ```
+pub struct MipMapBundle<T> {
+    source: Vec<T>,
+    /// Levels starting from `1` of every requested reduction.
+    reductions: Vec<(Reduction, Vec<Vec<T>>)>,
+    /// RMS levels starting from `1`, if requested.
+    rms: Option<Vec<Vec<f64>>>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMapBundle<T> {
+    /// Constructs the levels of all `strategies`. Repeated strategies are built once.
+    pub fn new(source: Vec<T>, strategies: &[Strategy]) -> Self {
+        let mut reductions: Vec<(Reduction, Vec<Vec<T>>)> = vec![];
+        let mut rms: Option<Vec<Vec<f64>>> = None;
+        for &strategy in strategies {
+            match strategy {
+                Strategy::Reduction(reduction)
+                    if reductions.iter().all(|(r, _)| *r != reduction) =>
+                {
+                    reductions.push((reduction, vec![]));
+                }
+                Strategy::Reduction(_) => {}
+                Strategy::Rms => rms = Some(vec![]),
+            }
+        }
+        let configs: Vec<_> = reductions
+            .iter()
+            .map(|(reduction, _)| MipMapConfig {
+                reduction: *reduction,
+                ..Default::default()
+            })
+            .collect();
+
+        let mut len = source.len();
+        while len > 1 {
+            let next = len.div_ceil(2);
+            for ((_, levels), config) in reductions.iter_mut().zip(&configs) {
+                let below = levels.last().unwrap_or(&source);
+                let level = (0..next)
+                    .map(|index| {
+                        let bucket = &below[2 * index..(2 * index + 2).min(len)];
+                        let elements: Vec<_> = bucket.iter().map(|x| (x, 1)).collect();
+                        reduce(&elements, config)
+                    })
+                    .collect();
+                levels.push(level);
+            }
+            if let Some(levels) = &mut rms {
+                let level = (0..next)
+                    .map(|index| {
+                        let range = 2 * index..(2 * index + 2).min(len);
+                        let squares: f64 = match levels.last() {
+                            Some(below) => below[range.clone()].iter().map(|x: &f64| x * x).sum(),
+                            None => source[range.clone()]
+                                .iter()
+                                .map(|x| x.to_f64().unwrap())
+                                .map(|x| x * x)
+                                .sum(),
+                        };
+                        (squares / range.len() as f64).sqrt()
+                    })
+                    .collect();
+                levels.push(level);
+            }
+            len = next;
+        }
+
+        Self {
+            source,
+            reductions,
+            rms,
+        }
+    }
+}
+
+impl<T> MipMapBundle<T> {
+    /// Returns the total number of levels, including the source, like [`MipMap1D::num_levels`](crate::MipMap1D::num_levels).
+    pub fn num_levels(&self) -> usize {
+        1 + self
+            .source
+            .len()
+            .max(1)
+            .next_power_of_two()
+            .trailing_zeros() as usize
+    }
+
+    /// Returns the source data.
+    pub fn source(&self) -> &[T] {
+        &self.source
+    }
+
+    /// Returns the data on given level combined with `reduction`. Level `0` is the source for every reduction.
+    /// If the reduction was not requested or the level is out of bounds, returns None
+    pub fn get_level(&self, reduction: Reduction, level: usize) -> Option<&[T]> {
+        let (_, levels) = self.reductions.iter().find(|(r, _)| *r == reduction)?;
+        match level {
+            0 => Some(&self.source),
+            _ => levels.get(level - 1).map(Vec::as_slice),
+        }
+    }
+
+    /// Returns the RMS of the buckets on given level.
+    /// If the RMS was not requested, or the level is `0` (which is the source) or out of bounds, returns None
+    pub fn rms_level(&self, level: usize) -> Option<&[f64]> {
+        self.rms
+            .as_ref()?
+            .get(level.checked_sub(1)?)
+            .map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_matches_separate_mipmaps() {
+        let source: Vec<i32> = (0..77).map(|x| (x * 7919) % 101 - 50).collect();
+        let reductions = [
+            Reduction::Mean,
+            Reduction::Min,
+            Reduction::Max,
+            Reduction::Last,
+        ];
+        let mut strategies: Vec<_> = reductions.iter().map(|&r| Strategy::Reduction(r)).collect();
+        strategies.push(Strategy::Reduction(Reduction::Max));
+        let bundle = MipMapBundle::new(source.clone(), &strategies);
+
+        for reduction in reductions {
+            let mut mipmap = MipMap1D::new(source.clone());
+            mipmap.rebuild_levels_with(reduction);
+            assert_eq!(bundle.num_levels(), mipmap.num_levels());
+            for level in 0..=mipmap.num_levels() {
+                assert_eq!(bundle.get_level(reduction, level), mipmap.get_level(level));
+            }
+        }
+        assert_eq!(bundle.get_level(Reduction::First, 0), None);
+        assert_eq!(bundle.rms_level(1), None);
+    }
+
+    #[test]
+    fn test_rms_of_full_buckets() {
+        let source: Vec<f64> = (0..64).map(|x| [3.0, -3.0, 1.0, -1.0][x % 4]).collect();
+        let bundle = MipMapBundle::new(source, &[Strategy::Rms]);
+
+        assert_eq!(*bundle.rms_level(1).unwrap(), [[3.0, 1.0]; 16].concat());
+        let top = bundle.rms_level(bundle.num_levels() - 1).unwrap();
+        assert!((top[0] - 5.0f64.sqrt()).abs() < 1e-12);
+        assert_eq!(bundle.rms_level(0), None);
+        assert_eq!(
+            MipMapBundle::<f32>::new(vec![], &[Strategy::Rms]).num_levels(),
+            1
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, PackedLevels};
+
+/// Element types which can be viewed as raw bytes: they have no padding and every bit pattern is valid.
+/// Implemented for all primitive integer and float types.
+///
+/// # Safety
+/// Implementors must not contain padding bytes, pointers or interior mutability.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Returns the bytes of given elements in native byte order.
+pub fn as_bytes<T: Pod>(values: &[T]) -> &[u8] {
+    // SAFETY: `T: Pod` has no padding, so every byte of the slice is initialized,
+    // and `u8` has no alignment requirement.
+    unsafe { core::slice::from_raw_parts(values.as_ptr().cast(), size_of_val(values)) }
+}
+
+/// Returns the bytes of given elements in native byte order for modification.
+pub fn as_bytes_mut<T: Pod>(values: &mut [T]) -> &mut [u8] {
+    // SAFETY: as in `as_bytes`, and any bytes written are a valid `T: Pod`.
+    unsafe { core::slice::from_raw_parts_mut(values.as_mut_ptr().cast(), size_of_val(values)) }
+}
+
+/// Reinterprets bytes in native byte order as elements without copying.
+/// If the bytes are not aligned for `T` or their length is not a multiple of its size, returns None
+pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Option<&[T]> {
+    if bytes.as_ptr().align_offset(align_of::<T>()) != 0
+        || !bytes.len().is_multiple_of(size_of::<T>())
+    {
+        return None;
+    }
+    // SAFETY: the pointer is aligned and the length fits, and every bit pattern is a valid `T: Pod`.
+    Some(unsafe {
+        core::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len() / size_of::<T>())
+    })
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Pod> MipMap1D<T> {
+    /// Returns the data on given level as bytes in native byte order, e.g. to write it to a socket or upload it to a GPU.
+    /// If the level is out of bounds, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![1u16, 3, 0x100]);
+    /// assert_eq!(mipmap.level_bytes(1).unwrap(), [2u16, 0x100].map(u16::to_ne_bytes).concat());
+    /// ```
+    pub fn level_bytes(&self, level: usize) -> Option<&[u8]> {
+        self.get_level(level).map(as_bytes)
+    }
+
+    /// Same as [`MipMap1D::pack_f32`], but the elements keep their type and are stored in native byte order.
+    ///
+    /// # Panics
+    /// Panics if `alignment` is zero.
+    pub fn pack(&self, alignment: usize) -> PackedLevels {
+        let levels = (0..self.num_levels()).filter_map(|level| self.get_level(level));
+        PackedLevels::pack(levels, size_of::<T>(), alignment, |bytes, level| {
+            bytes.extend_from_slice(as_bytes(level));
+        })
+    }
+
+    /// Returns [`MipMap1D::buffer`] as bytes in native byte order.
+    /// Every level starts at `size_of::<T>()` times the start of its [`MipMap1D::level_range`].
+    pub fn buffer_bytes(&self) -> &[u8] {
+        as_bytes(self.buffer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_match_elements() {
+        let mipmap = MipMap1D::new(vec![1.5f64, -2.0, 8.25]);
+
+        for level in 0..mipmap.num_levels() {
+            let expected: Vec<u8> = mipmap
+                .get_level(level)
+                .unwrap()
+                .iter()
+                .flat_map(|x| x.to_ne_bytes())
+                .collect();
+            assert_eq!(mipmap.level_bytes(level).unwrap(), expected);
+        }
+        assert_eq!(mipmap.level_bytes(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let values = [1u32, 2, 3];
+        let bytes = as_bytes(&values);
+
+        assert_eq!(from_bytes::<u32>(bytes), Some(&values[..]));
+        assert_eq!(from_bytes::<u32>(&bytes[..5]), None);
+        assert_eq!(from_bytes::<u32>(&bytes[1..5]), None);
+        assert_eq!(from_bytes::<u8>(&bytes[1..2]), Some(&[bytes[1]][..]));
+    }
+
+    #[test]
+    fn test_buffer_bytes() {
+        let mipmap = MipMap1D::new(vec![1i32, 2, 3, 4]);
+        let bytes = mipmap.buffer_bytes();
+        let range = mipmap.level_range(2).unwrap();
+
+        assert_eq!(bytes.len(), 7 * 4);
+        assert_eq!(bytes[range.start * 4..range.end * 4], 2i32.to_ne_bytes());
+
+        let packed = mipmap.pack(8);
+        assert_eq!(packed.levels, [0..16, 16..24, 24..28]);
+        assert_eq!(packed.bytes[16..24], *mipmap.level_bytes(1).unwrap());
+    }
+}
@@ -0,0 +1,106 @@
+use num_traits::{Float, FromPrimitive};
+
+use crate::{MipMap1D, MultiMipMap};
+
+/// Creates several downsampled versions of complex samples, e.g. IQ captures of a software-defined radio.
+/// Samples are given as `(re, im)` pairs, and both components are averaged on their own.
+/// Since the mean of a rotating phasor cancels out, the mean magnitude of every bucket can be kept as well.
+/// Example:
+/// ```rust
+/// use mipmap_1d::ComplexMipMap;
+///
+/// let samples = vec![(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+/// let mipmap = ComplexMipMap::with_magnitudes(&samples);
+/// assert_eq!(mipmap.num_levels(), 3);
+/// assert_eq!(mipmap.get_level(1).unwrap(), [(0.5, 0.5), (-0.5, -0.5)]);
+/// assert_eq!(mipmap.get_level(2).unwrap(), [(0.0, 0.0)]);
+/// assert_eq!(*mipmap.get_magnitudes(2).unwrap(), [1.0]);
+/// ```
+pub struct ComplexMipMap<T: Float + FromPrimitive> {
+    /// Levels of the real and the imaginary components.
+    components: MultiMipMap<T>,
+    magnitudes: Option<MipMap1D<T>>,
+}
+
+impl<T: Float + FromPrimitive> ComplexMipMap<T> {
+    /// Constructs the levels of the components only.
+    pub fn new(samples: &[(T, T)]) -> Self {
+        let (re, im) = samples.iter().copied().unzip();
+        Self {
+            components: MultiMipMap::new(vec![re, im]),
+            magnitudes: None,
+        }
+    }
+
+    /// Constructs the levels from interleaved components, i.e. `[re0, im0, re1, im1, ...]`.
+    ///
+    /// # Panics
+    /// Panics if the number of components is odd.
+    pub fn from_interleaved(components: &[T]) -> Self {
+        Self {
+            components: MultiMipMap::from_interleaved(components, 2),
+            magnitudes: None,
+        }
+    }
+
+    /// Same as [`ComplexMipMap::new`], but also keeps the mean magnitude of every bucket.
+    pub fn with_magnitudes(samples: &[(T, T)]) -> Self {
+        let magnitudes = samples.iter().map(|&(re, im)| re.hypot(im)).collect();
+        Self {
+            magnitudes: Some(MipMap1D::new(magnitudes)),
+            ..Self::new(samples)
+        }
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.components.num_levels()
+    }
+
+    /// Returns the `(re, im)` means on given level.
+    /// Level `0` returns the source samples.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Vec<(T, T)>> {
+        let [re, im] = self.components.get_level(level)? else {
+            unreachable!("There are always two components")
+        };
+        Some(re.iter().copied().zip(im.iter().copied()).collect())
+    }
+
+    /// Returns the mean magnitudes on given level.
+    /// If the magnitudes were not kept, or the level is out of bounds, returns None
+    pub fn get_magnitudes(&self, level: usize) -> Option<&[T]> {
+        self.magnitudes.as_ref()?.get_level(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleaved_matches_pairs() {
+        let samples = [(1.0f32, 2.0), (3.0, -2.0), (0.5, 0.5)];
+        let interleaved: Vec<f32> = samples.iter().flat_map(|&(re, im)| [re, im]).collect();
+
+        let pairs = ComplexMipMap::new(&samples);
+        let frames = ComplexMipMap::from_interleaved(&interleaved);
+        assert_eq!(pairs.num_levels(), 3);
+        for level in 0..pairs.num_levels() {
+            assert_eq!(pairs.get_level(level), frames.get_level(level));
+        }
+        assert_eq!(pairs.get_level(1).unwrap(), [(2.0, 0.0), (0.5, 0.5)]);
+        assert_eq!(pairs.get_level(3), None);
+        assert_eq!(pairs.get_magnitudes(0), None);
+    }
+
+    #[test]
+    fn test_magnitudes() {
+        let samples = [(3.0, 4.0), (0.0, -1.0), (-6.0, 8.0)];
+        let mipmap = ComplexMipMap::with_magnitudes(&samples);
+
+        assert_eq!(*mipmap.get_magnitudes(0).unwrap(), [5.0, 1.0, 10.0]);
+        assert_eq!(*mipmap.get_magnitudes(1).unwrap(), [3.0, 10.0]);
+        assert_eq!(mipmap.get_magnitudes(3), None);
+    }
+}
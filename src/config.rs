@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::storage::VecStorage;
+use crate::{MipMap1D, NanPolicy, Reduction, Rounding};
+
+/// Options of a [`MipMap1D`], which together with the source fully determine its levels.
+/// A mipmap can be stored compactly as its source and configuration, and rebuilt with [`MipMap1D::from_config`].
+/// The crate does not implement serde traits, so both have to be written by the caller, e.g. field by field.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, Reduction};
+///
+/// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+/// mipmap.rebuild_levels_with(Reduction::Max);
+///
+/// let (source, config) = (mipmap.get_level(0).unwrap().to_vec(), mipmap.config());
+/// let rebuilt = MipMap1D::from_config(source, config);
+/// assert_eq!(rebuilt.get_level(1), mipmap.get_level(1));
+/// assert_eq!(rebuilt.reduction(), Reduction::Max);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MipMapConfig {
+    /// Ignored for integer types, which have no NaN: their means are always computed like with [`NanPolicy::Propagate`].
+    pub nan_policy: NanPolicy,
+    pub rounding: Rounding,
+    pub reduction: Reduction,
+    /// Maximum number of levels, e.g. chosen by [`MipMap1D::with_memory_budget`], or None if there is no limit.
+    pub max_levels: Option<usize>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Constructs the levels of `source` with given configuration.
+    pub fn from_config(source: Vec<T>, config: MipMapConfig) -> Self {
+        Self::build_in(VecStorage::default(), source, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_roundtrip() {
+        let nan = f64::NAN;
+        let source = vec![1.0, nan, 3.0, 4.0, nan, 8.0, 5.0, 1.0, 2.0];
+        let configs = [
+            MipMapConfig::default(),
+            MipMapConfig {
+                nan_policy: NanPolicy::Missing,
+                reduction: Reduction::Min,
+                max_levels: Some(3),
+                ..Default::default()
+            },
+            MipMapConfig {
+                nan_policy: NanPolicy::Skip,
+                rounding: Rounding::HalfEven,
+                ..Default::default()
+            },
+        ];
+
+        for config in configs {
+            let mipmap = MipMap1D::from_config(source.clone(), config);
+            assert_eq!(mipmap.config(), config);
+            assert_eq!(mipmap.verify(), Ok(()));
+        }
+        let limited = MipMap1D::from_config(source, configs[1]);
+        assert_eq!(limited.num_levels(), 3);
+        assert_eq!(*limited.get_level(2).unwrap(), [1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_config_of_constructors() {
+        assert_eq!(MipMap1D::new(vec![1]).config(), MipMapConfig::default());
+        let budget = MipMap1D::with_memory_budget(vec![1u8; 16], 24);
+        assert_eq!(budget.config().max_levels, Some(2));
+        let rounded = MipMap1D::with_rounding(vec![1, 2], Rounding::Ceil);
+        assert_eq!(rounded.config().rounding, Rounding::Ceil);
+    }
+
+    #[test]
+    fn test_integer_nan_policies_are_ignored() {
+        for nan_policy in [NanPolicy::Skip, NanPolicy::Missing] {
+            let config = MipMapConfig {
+                nan_policy,
+                rounding: Rounding::Ceil,
+                ..Default::default()
+            };
+            let wide = MipMap1D::from_config(vec![200u8; 5], config);
+            assert_eq!(*wide.get_level(1).unwrap(), [200, 200, 200]);
+            assert_eq!(*wide.get_level(3).unwrap(), [200]);
+            assert_eq!(wide.nan_policy(), NanPolicy::Propagate);
+
+            let rounded = MipMap1D::from_config(vec![1, 2, 3, 4], config);
+            assert_eq!(*rounded.get_level(1).unwrap(), [2, 4]);
+            assert_eq!(*rounded.get_level(2).unwrap(), [3]);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use alloc::vec::Vec;
+
+use num_traits::{FromPrimitive, Num, NumCast, ToPrimitive};
+
+use crate::mipmap::{is_integral, rounds_up};
+use crate::{MipMap1D, Rounding, Transform};
+
+/// Creates several downsampled versions of given vector, storing the downsampled levels in another type than the source,
+/// e.g. `i16` samples with `f32` levels.
+/// Integer means are then not rounded on every level, so they do not drift when re-averaged across many levels.
+/// The source is kept as is and is available through [`ConvertedMipMap1D::source`].
+/// Example:
+/// ```rust
+/// use mipmap_1d::ConvertedMipMap1D;
+///
+/// let mipmap: ConvertedMipMap1D<i16, f32> = ConvertedMipMap1D::new(vec![1, 2, 4, 4, 9]);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// assert_eq!(*mipmap.source(), [1, 2, 4, 4, 9]);
+/// assert_eq!(mipmap.get_level(0), None);
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [1.5, 4.0, 9.0]);
+/// assert_eq!(*mipmap.get_level(2).unwrap(), [2.75, 9.0]);
+/// assert_eq!(mipmap.get(0, 4), Some(9.0));
+/// ```
+pub struct ConvertedMipMap1D<I, O: Num + ToPrimitive + FromPrimitive> {
+    source: Vec<I>,
+    /// Levels starting from `1`, or None if the source has at most one element.
+    levels: Option<MipMap1D<O>>,
+}
+
+impl<I: ToPrimitive + Copy, O: Num + NumCast + ToPrimitive + FromPrimitive + Copy>
+    ConvertedMipMap1D<I, O>
+{
+    /// Constructs the levels, converting the source into the level type before averaging.
+    ///
+    /// # Panics
+    /// Panics if a source sample can not be represented in the level type.
+    pub fn new(source: Vec<I>) -> Self {
+        let levels = (source.len() > 1).then(|| {
+            let first = source
+                .chunks(2)
+                .map(|pair| {
+                    let pair: Vec<O> = pair.iter().map(|&x| convert(x)).collect();
+                    MipMap1D::average(&pair)
+                })
+                .collect();
+            MipMap1D::new(first)
+        });
+
+        Self { source, levels }
+    }
+
+    /// Returns the total number of downsampled levels, including the source.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        1 + self.levels.as_ref().map_or(0, MipMap1D::num_levels)
+    }
+
+    /// Returns the source data.
+    pub fn source(&self) -> &Vec<I> {
+        &self.source
+    }
+
+    /// Returns the data on given downsampled level.
+    /// If the level is `0` (which is stored as the source type) or out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[O]> {
+        self.levels.as_ref()?.get_level(level.checked_sub(1)?)
+    }
+
+    /// Returns the element at `index` of given level, converting source samples into the level type.
+    /// If the level or the index is out of bounds, returns None
+    pub fn get(&self, level: usize, index: usize) -> Option<O> {
+        match level {
+            0 => self.source.get(index).map(|&x| convert(x)),
+            _ => self.get_level(level)?.get(index).copied(),
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Converts every element of every level into another type in one pass, without downsampling again.
+    /// Floats converted into integers are rounded according to the [`Rounding`] of the mipmap,
+    /// so the result does not depend on the platform. The configuration is kept.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Rounding};
+    ///
+    /// let mipmap = MipMap1D::with_rounding(vec![0.5, 1.5, -2.5, 4.0], Rounding::HalfEven);
+    /// let rounded = mipmap.convert::<i32>();
+    /// assert_eq!(*rounded.get_level(0).unwrap(), [0, 2, -2, 4]);
+    /// assert_eq!(*rounded.get_level(1).unwrap(), [1, 1]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if an element can not be represented in the new type, e.g. a NaN or a too large value converted into an integer.
+    pub fn convert<U: Num + NumCast + ToPrimitive + FromPrimitive + Clone>(&self) -> MipMap1D<U> {
+        let rounding = (is_integral::<U>() && !is_integral::<T>()).then_some(self.rounding());
+        self.map(Transform::Affine, |x| match rounding {
+            Some(rounding) => convert(round(x.to_f64().unwrap(), rounding)),
+            None => convert(x.clone()),
+        })
+    }
+
+    /// Converts every level into `f32`, e.g. for a renderer or a GPU buffer, while this mipmap
+    /// keeps the full precision. Values are rounded to the nearest `f32`, and out of range ones become infinite.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![0.1, 0.2, 1e300]);
+    /// let single = mipmap.as_f32();
+    /// assert_eq!(*single.get_level(0).unwrap(), [0.1, 0.2, f32::INFINITY]);
+    /// assert_eq!(single.get_level(1).unwrap()[0], 0.15);
+    /// ```
+    pub fn as_f32(&self) -> MipMap1D<f32> {
+        self.map(Transform::Affine, |x| x.to_f64().unwrap() as f32)
+    }
+}
+
+/// Rounds a float to an integral value according to `rounding`.
+fn round(value: f64, rounding: Rounding) -> f64 {
+    // Floats of this magnitude are integers, and smaller ones fit into i64. NaNs are kept.
+    if value.is_nan() || value.abs() >= (1u64 << 52) as f64 {
+        return value;
+    }
+    let truncated = value as i64 as f64;
+    let floor = match truncated > value {
+        true => truncated - 1.0,
+        false => truncated,
+    };
+    let remainder = value - floor;
+    let up = match rounding {
+        _ if remainder == 0.0 => false,
+        Rounding::HalfEven | Rounding::HalfAway if remainder != 0.5 => remainder > 0.5,
+        _ => rounds_up(rounding, floor < 0.0, floor as i64 & 1 == 1),
+    };
+    match up {
+        true => floor + 1.0,
+        false => floor,
+    }
+}
+
+/// Converts a source sample into the level type.
+fn convert<I: ToPrimitive, O: NumCast>(value: I) -> O {
+    O::from(value).expect("The sample can not be represented in the level type")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_drift_across_levels() {
+        let source: Vec<u32> = (0..64).map(|x| x % 3).collect();
+        let converted: ConvertedMipMap1D<u32, f64> = ConvertedMipMap1D::new(source.clone());
+        let rounded = MipMap1D::new(source.clone());
+
+        let top = converted.num_levels() - 1;
+        assert_eq!(top, rounded.num_levels() - 1);
+        let mean = source.iter().sum::<u32>() as f64 / 64.0;
+        assert_eq!(converted.get(top, 0), Some(mean));
+        assert_eq!(rounded.get_level(top).unwrap()[0], 0);
+    }
+
+    #[test]
+    fn test_convert_whole_mipmap() {
+        let source = vec![-2.5, -1.5, -0.25, 0.75, 2.5, 3.0];
+        let expected = [
+            (Rounding::TowardZero, [-2, -1, 0, 0, 2, 3]),
+            (Rounding::Floor, [-3, -2, -1, 0, 2, 3]),
+            (Rounding::Ceil, [-2, -1, 0, 1, 3, 3]),
+            (Rounding::HalfEven, [-2, -2, 0, 1, 2, 3]),
+            (Rounding::HalfAway, [-3, -2, 0, 1, 3, 3]),
+        ];
+        for (rounding, level) in expected {
+            let mipmap = MipMap1D::with_rounding(source.clone(), rounding);
+            let converted = mipmap.convert::<i8>();
+            assert_eq!(*converted.get_level(0).unwrap(), level);
+            assert_eq!(converted.num_levels(), mipmap.num_levels());
+            assert_eq!(converted.rounding(), rounding);
+        }
+
+        let ints = MipMap1D::new(vec![1u8, 2, 255]);
+        assert_eq!(*ints.convert::<f64>().get_level(1).unwrap(), [1.0, 255.0]);
+        assert_eq!(ints.as_f32().level_stats(0).unwrap().max, 255.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_convert_nan_to_integer_panics() {
+        MipMap1D::new(vec![1.0, f64::NAN]).convert::<i32>();
+    }
+
+    #[test]
+    fn test_short_sources() {
+        let single: ConvertedMipMap1D<i8, f32> = ConvertedMipMap1D::new(vec![7]);
+        assert_eq!(single.num_levels(), 1);
+        assert_eq!(single.get(0, 0), Some(7.0));
+        assert_eq!(single.get(1, 0), None);
+
+        let empty: ConvertedMipMap1D<i8, f32> = ConvertedMipMap1D::new(vec![]);
+        assert_eq!(empty.num_levels(), 1);
+        assert_eq!(empty.get(0, 0), None);
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Counts of flagged source samples in every bucket, with the same levels as a [`MipMap1D`] of the source.
+/// Lets zoomed-out views show how often something happened, rather than just a washed-out mean.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new(vec![2, 14, 6, 18, 19]);
+/// let counts = mipmap.count_above(10);
+/// assert_eq!(counts.num_levels(), mipmap.num_levels());
+/// assert_eq!(*counts.get_level(0).unwrap(), [0, 1, 0, 1, 1]);
+/// assert_eq!(*counts.get_level(1).unwrap(), [1, 1, 1]);
+/// assert_eq!(*counts.get_level(2).unwrap(), [2, 1]);
+/// assert_eq!(*counts.get_level(3).unwrap(), [3]);
+/// ```
+pub struct BucketCounts {
+    data: Vec<Vec<usize>>,
+}
+
+impl BucketCounts {
+    /// Constructs the counts from a flag for every source sample.
+    pub fn from_flags(flags: impl IntoIterator<Item = bool>) -> Self {
+        Self::from_counts(flags.into_iter().map(usize::from).collect())
+    }
+
+    /// Constructs the levels by summing the pairs of counts, starting with the counts of single samples.
+    fn from_counts(source: Vec<usize>) -> Self {
+        let mut data = vec![source];
+
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1]
+                .chunks(2)
+                .map(|pair| pair.iter().sum())
+                .collect();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the counts on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<usize>> {
+        self.data.get(level)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone + PartialOrd> MipMap1D<T> {
+    /// Counts the source samples exceeding `threshold` in every bucket.
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn count_above(&self, threshold: T) -> BucketCounts {
+        BucketCounts::from_flags(self.source().iter().map(|x| *x > threshold))
+    }
+
+    /// Counts the zero crossings in every bucket, a cheap indicator of frequency content.
+    /// A crossing is attributed to the first sample whose sign differs from the last non-zero sample before it,
+    /// so samples equal to zero (or NaN) never start a crossing themselves.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![1, -1, -2, 0, 3, 2, -1, 1]);
+    /// let crossings = mipmap.zero_crossings();
+    /// assert_eq!(*crossings.get_level(0).unwrap(), [0, 1, 0, 0, 1, 0, 1, 1]);
+    /// assert_eq!(*crossings.get_level(3).unwrap(), [4]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn zero_crossings(&self) -> BucketCounts {
+        let mut last_positive = None;
+        let flags = self.source().iter().map(|x| {
+            let positive = if *x > T::zero() {
+                true
+            } else if *x < T::zero() {
+                false
+            } else {
+                return false;
+            };
+            let crossed = last_positive.is_some_and(|last| last != positive);
+            last_positive = Some(positive);
+            crossed
+        });
+
+        BucketCounts::from_flags(flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_add_up() {
+        let source: Vec<f64> = (0..100).map(|x| (x as f64 * 0.7).sin()).collect();
+        let mipmap = MipMap1D::new(source.clone());
+        let counts = mipmap.count_above(0.5);
+        let total = source.iter().filter(|&&x| x > 0.5).count();
+
+        assert_eq!(counts.num_levels(), mipmap.num_levels());
+        for level in 0..counts.num_levels() {
+            let res = counts.get_level(level).unwrap();
+            assert_eq!(res.len(), mipmap.get_level(level).unwrap().len());
+            assert_eq!(res.iter().sum::<usize>(), total);
+        }
+    }
+
+    #[test]
+    fn test_nan_is_not_above() {
+        let mipmap = MipMap1D::new(vec![f64::NAN, 1.0]);
+
+        assert_eq!(*mipmap.count_above(0.0).get_level(1).unwrap(), [1]);
+    }
+
+    #[test]
+    fn test_zero_crossings_of_sine() {
+        // 10 full periods starting right after a crossing contain 19 more crossings.
+        let source: Vec<f64> = (0..1000)
+            .map(|x| (x as f64 / 100.0 * std::f64::consts::TAU + 0.01).sin())
+            .collect();
+        let crossings = MipMap1D::new(source).zero_crossings();
+        let top = crossings.num_levels() - 1;
+
+        assert_eq!(*crossings.get_level(top).unwrap(), [19]);
+        assert_eq!(
+            crossings.get_level(top - 1).unwrap().iter().sum::<usize>(),
+            19
+        );
+    }
+
+    #[test]
+    fn test_zeros_do_not_cross() {
+        let crossings =
+            MipMap1D::new(vec![0.0, 0.0, 1.0, 0.0, 1.0, f64::NAN, -1.0]).zero_crossings();
+
+        assert_eq!(*crossings.get_level(0).unwrap(), [0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let counts = BucketCounts::from_flags([]);
+
+        assert_eq!(counts.num_levels(), 1);
+        assert!(counts.get_level(0).unwrap().is_empty());
+        assert_eq!(counts.get_level(1), None);
+    }
+
+    #[test]
+    fn test_clone_only_samples() {
+        use crate::fixtures::CloneInt;
+
+        let mipmap = MipMap1D::new([1, -2, 3, 4, -5].into_iter().map(CloneInt).collect());
+        assert_eq!(
+            *mipmap.count_above(CloneInt(2)).get_level(1).unwrap(),
+            [0, 2, 0]
+        );
+        assert_eq!(
+            *mipmap.zero_crossings().get_level(0).unwrap(),
+            [0, 1, 1, 0, 1]
+        );
+    }
+}
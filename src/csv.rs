@@ -0,0 +1,179 @@
+use std::io::{self, BufRead};
+
+use crate::{MipMap1D, NanPolicy, Rounding};
+
+/// Selects the column read by [`from_csv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumn<'a> {
+    /// The column with this name in the header row.
+    Name(&'a str),
+    /// The column at this index, starting at `0`.
+    /// The first row is skipped as a header if its field is not a number.
+    Index(usize),
+}
+
+/// A mipmap of a CSV column, with the element type inferred from its values.
+pub enum CsvMipMap {
+    /// All values are integers.
+    Int(MipMap1D<i64>),
+    /// Some values are not integers or are empty. Empty values are missing samples, using [`NanPolicy::Missing`].
+    Float(MipMap1D<f64>),
+}
+
+impl CsvMipMap {
+    /// Returns the mipmap as floats, converting integers if needed.
+    pub fn into_f64(self) -> MipMap1D<f64> {
+        match self {
+            Self::Int(mipmap) => to_float(&mipmap),
+            Self::Float(mipmap) => mipmap,
+        }
+    }
+}
+
+/// Reads one column of CSV data, pushing every value into a mipmap as it is read.
+/// Values are integers until the first value which is not, and floats from then on.
+/// Fields are separated by commas and may be quoted, but may not contain line breaks.
+/// Invalid values or rows without the column are reported as [`io::ErrorKind::InvalidData`].
+/// Example:
+/// ```rust
+/// use mipmap_1d::csv::{from_csv, CsvColumn, CsvMipMap};
+///
+/// let data = "time,value\n0,2\n1,4\n2,6.5\n3,8\n";
+/// let CsvMipMap::Float(mipmap) = from_csv(data.as_bytes(), CsvColumn::Name("value")).unwrap() else {
+///     panic!("6.5 is not an integer");
+/// };
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [3.0, 7.25]);
+/// ```
+pub fn from_csv(reader: impl BufRead, column: CsvColumn) -> io::Result<CsvMipMap> {
+    let invalid = |row: usize, message: String| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("row {}: {message}", row + 1),
+        )
+    };
+    let (mut index, may_have_header) = match column {
+        CsvColumn::Name(_) => (None, false),
+        CsvColumn::Index(index) => (Some(index), true),
+    };
+    let mut mipmap = CsvMipMap::Int(MipMap1D::new(vec![]));
+
+    for (row, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let fields = split(&line);
+        let Some(position) = index else {
+            let CsvColumn::Name(name) = column else {
+                unreachable!()
+            };
+            let found = fields.iter().position(|field| field == name);
+            index = Some(found.ok_or_else(|| invalid(row, format!("no column named {name:?}")))?);
+            continue;
+        };
+        let field = fields
+            .get(position)
+            .ok_or_else(|| invalid(row, format!("no column {position}")))?
+            .trim();
+
+        let is_header = row == 0 && may_have_header;
+        match (&mut mipmap, field.parse::<i64>()) {
+            (CsvMipMap::Int(ints), Ok(value)) => ints.push(value),
+            (CsvMipMap::Float(floats), Ok(value)) => floats.push(value as f64),
+            (_, Err(_)) => {
+                let value = match field {
+                    "" => f64::NAN,
+                    _ => match field.parse::<f64>() {
+                        Ok(value) => value,
+                        Err(_) if is_header => continue,
+                        Err(_) => return Err(invalid(row, format!("{field:?} is not a number"))),
+                    },
+                };
+                if let CsvMipMap::Int(ints) = &mipmap {
+                    mipmap = CsvMipMap::Float(to_float(ints));
+                }
+                if let CsvMipMap::Float(floats) = &mut mipmap {
+                    floats.push(value);
+                }
+            }
+        }
+    }
+    Ok(mipmap)
+}
+
+/// Converts a mipmap of integers into one of floats, with NaNs as missing samples.
+fn to_float(ints: &MipMap1D<i64>) -> MipMap1D<f64> {
+    let source = ints
+        .get_level(0)
+        .unwrap()
+        .iter()
+        .map(|&x| x as f64)
+        .collect();
+    MipMap1D::build(source, NanPolicy::Missing, Rounding::default())
+}
+
+/// Splits a CSV record into its fields, removing quotes.
+fn split(line: &str) -> Vec<String> {
+    let (mut fields, mut field, mut quoted) = (vec![], String::new(), false);
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_column_by_index() {
+        let data = "a,b\n1,10\n2,20\n\n3,\"30\"\n";
+        let CsvMipMap::Int(mipmap) = from_csv(data.as_bytes(), CsvColumn::Index(1)).unwrap() else {
+            panic!("all values are integers");
+        };
+        assert_eq!(mipmap.get_level(0).unwrap(), [10, 20, 30]);
+        assert_eq!(mipmap.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_switch_to_floats() {
+        let data = "x,\"y, in m\"\n0,1\n0,\n0,2.5\n0,3\n0,4\n";
+        let mipmap = from_csv(data.as_bytes(), CsvColumn::Name("y, in m"))
+            .unwrap()
+            .into_f64();
+        let expected =
+            MipMap1D::with_nan_policy(vec![1.0, f64::NAN, 2.5, 3.0, 4.0], NanPolicy::Missing);
+
+        for level in 1..expected.num_levels() {
+            assert_eq!(mipmap.get_level(level), expected.get_level(level));
+        }
+        assert_eq!(mipmap.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let error =
+            |data: &str, column| from_csv(data.as_bytes(), column).err().unwrap().to_string();
+
+        assert_eq!(
+            error("a\n1\n", CsvColumn::Name("b")),
+            "row 1: no column named \"b\""
+        );
+        assert_eq!(error("1,2\n3\n", CsvColumn::Index(1)), "row 2: no column 1");
+        assert_eq!(
+            error("1\nx\n", CsvColumn::Index(0)),
+            "row 2: \"x\" is not a number"
+        );
+        assert_eq!(split("\"a \"\"b\"\"\",c"), ["a \"b\"", "c"]);
+    }
+}
@@ -0,0 +1,224 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{Bounded, CheckedAdd, FromPrimitive, Num, Saturating, ToPrimitive};
+
+use crate::overflow::saturating_add;
+use crate::{MipMap1D, OverflowError, OverflowPolicy};
+
+/// Levels of the running total of the source data, e.g. for "total so far" curves.
+/// Every element is the total up to the end of its bucket, so level `k` element `i` equals
+/// the sum of the first `min((i + 1) * 2^k, len)` source samples.
+/// Coarser levels pick the total at the end of every pair instead of re-integrating downsampled values,
+/// so they never drift from the source.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new(vec![1, 2, 3, 4, 5]);
+/// let totals = mipmap.cumulative_sum();
+/// assert_eq!(*totals.get_level(0).unwrap(), [1, 3, 6, 10, 15]);
+/// assert_eq!(*totals.get_level(1).unwrap(), [3, 10, 15]);
+/// assert_eq!(*totals.get_level(3).unwrap(), [15]);
+/// assert_eq!(totals.total(), Some(15));
+/// ```
+pub struct CumulativeMipMap<T> {
+    data: Vec<Vec<T>>,
+}
+
+impl<T: Num + Bounded + PartialOrd + Copy> CumulativeMipMap<T> {
+    /// Computes the running totals of `source`. Integer totals which do not fit into the type saturate at its bounds;
+    /// use [`CumulativeMipMap::with_overflow_policy`] to detect them, or [`CumulativeMipMap::widen`] to avoid them.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::CumulativeMipMap;
+    ///
+    /// let totals = CumulativeMipMap::new(&[200u8, 100, 0]);
+    /// assert_eq!(*totals.get_level(0).unwrap(), [200, 255, 255]);
+    /// ```
+    pub fn new(source: &[T]) -> Self {
+        let totals = source
+            .iter()
+            .scan(T::zero(), |total, &x| {
+                *total = saturating_add(*total, x);
+                Some(*total)
+            })
+            .collect();
+
+        Self::from_totals(totals)
+    }
+
+    /// Computes the running totals in a wider type than the samples, e.g. `i64` totals of `i32` samples,
+    /// so they do not overflow.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::CumulativeMipMap;
+    ///
+    /// let totals = CumulativeMipMap::<i64>::widen(&[i32::MAX, i32::MAX]);
+    /// assert_eq!(totals.total(), Some(2 * i32::MAX as i64));
+    /// ```
+    pub fn widen<S: Copy>(source: &[S]) -> Self
+    where
+        T: From<S>,
+    {
+        let totals = source
+            .iter()
+            .scan(T::zero(), |total, &x| {
+                *total = saturating_add(*total, T::from(x));
+                Some(*total)
+            })
+            .collect();
+
+        Self::from_totals(totals)
+    }
+}
+
+impl<T: Num + Copy> CumulativeMipMap<T> {
+    /// Constructs the levels from the running totals of the source.
+    fn from_totals(totals: Vec<T>) -> Self {
+        let mut data = vec![totals];
+
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1]
+                .chunks(2)
+                .map(|pair| pair[pair.len() - 1])
+                .collect();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the running totals at the end of every bucket on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<T>> {
+        self.data.get(level)
+    }
+
+    /// Returns the sum of all source samples.
+    /// If the source is empty, returns None
+    pub fn total(&self) -> Option<T> {
+        self.data[0].last().copied()
+    }
+}
+
+impl<T: Num + CheckedAdd + Saturating + Copy> CumulativeMipMap<T> {
+    /// Same as [`CumulativeMipMap::new`], but totals which do not fit into the type are handled according to `policy`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{CumulativeMipMap, OverflowError, OverflowPolicy};
+    ///
+    /// let source = [100u8, 100, 100];
+    /// let checked = CumulativeMipMap::with_overflow_policy(&source, OverflowPolicy::Checked);
+    /// assert_eq!(checked.err(), Some(OverflowError { index: 2 }));
+    /// let saturated = CumulativeMipMap::with_overflow_policy(&source, OverflowPolicy::Saturate).unwrap();
+    /// assert_eq!(*saturated.get_level(0).unwrap(), [100, 200, 255]);
+    /// ```
+    pub fn with_overflow_policy(
+        source: &[T],
+        policy: OverflowPolicy,
+    ) -> Result<Self, OverflowError> {
+        let mut totals = Vec::with_capacity(source.len());
+        let mut total = T::zero();
+
+        for (index, x) in source.iter().enumerate() {
+            total = match policy {
+                OverflowPolicy::Checked => total.checked_add(x).ok_or(OverflowError { index })?,
+                OverflowPolicy::Saturate => total.saturating_add(*x),
+            };
+            totals.push(total);
+        }
+
+        Ok(Self::from_totals(totals))
+    }
+}
+
+impl<T: Num + Bounded + PartialOrd + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Computes the running totals of the source data, with the same bucket boundaries as this mipmap,
+    /// like [`CumulativeMipMap::new`].
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn cumulative_sum(&self) -> CumulativeMipMap<T> {
+        CumulativeMipMap::new(self.source())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_match_source_totals() {
+        let source: Vec<u64> = (1..=37).collect();
+        let totals = CumulativeMipMap::new(&source);
+
+        for level in 0..totals.num_levels() {
+            for (i, &total) in totals.get_level(level).unwrap().iter().enumerate() {
+                let end = ((i + 1) << level).min(source.len()) as u64;
+                assert_eq!(total, end * (end + 1) / 2);
+            }
+        }
+        assert_eq!(totals.get_level(totals.num_levels()), None);
+    }
+
+    #[test]
+    fn test_no_drift() {
+        let source = vec![0.1f64; 1000];
+        let mipmap = MipMap1D::new(source);
+        let totals = mipmap.cumulative_sum();
+
+        let top = totals.get_level(totals.num_levels() - 1).unwrap()[0];
+        assert_eq!(Some(top), totals.total());
+        assert!((top - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overflow_policies() {
+        let source = [i8::MIN, -100, 100, 127, 127];
+
+        let checked = CumulativeMipMap::with_overflow_policy(&source, OverflowPolicy::Checked);
+        assert_eq!(checked.err(), Some(OverflowError { index: 1 }));
+
+        let saturated =
+            CumulativeMipMap::with_overflow_policy(&source, OverflowPolicy::Saturate).unwrap();
+        assert_eq!(*saturated.get_level(0).unwrap(), [-128, -128, -28, 99, 127]);
+        assert_eq!(*saturated.get_level(1).unwrap(), [-128, 99, 127]);
+
+        let widened = CumulativeMipMap::<i16>::widen(&source);
+        assert_eq!(*widened.get_level(0).unwrap(), [-128, -228, -128, -1, 126]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The source was pruned")]
+    fn test_pruned_source_panics() {
+        let mut mipmap = MipMap1D::new(vec![1, 2, 3, 4]);
+        mipmap.prune_below(1);
+        mipmap.cumulative_sum();
+    }
+
+    #[test]
+    fn test_default_totals_saturate() {
+        let totals = CumulativeMipMap::new(&[200u8, 100]);
+        assert_eq!(totals.total(), Some(255));
+        let totals = MipMap1D::new(vec![i32::MIN, -1, i32::MAX, i32::MAX]).cumulative_sum();
+        assert_eq!(
+            *totals.get_level(0).unwrap(),
+            [i32::MIN, i32::MIN, -1, i32::MAX - 1]
+        );
+        let totals = CumulativeMipMap::new(&[f64::MAX, f64::MAX]);
+        assert_eq!(totals.total(), Some(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_empty_source() {
+        let totals = CumulativeMipMap::<i32>::new(&[]);
+
+        assert_eq!(totals.num_levels(), 1);
+        assert_eq!(totals.total(), None);
+    }
+}
@@ -0,0 +1,185 @@
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::is_integral;
+use crate::MipMap1D;
+
+/// Levels of a mipmap where every element is stored as its difference from the previous one, packed as a varint.
+/// Integers store the zigzag-encoded difference, floats store the XOR of their `f64` bits,
+/// so both are lossless and slowly varying levels take a byte or two per element.
+/// Levels are decoded on access.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new((1000..1064).collect::<Vec<u32>>());
+/// let encoded = mipmap.delta_encoded();
+/// assert_eq!(encoded.get_level(5), Some(vec![1015, 1047]));
+/// assert_eq!(encoded.encoded_size(0), 65);
+/// ```
+pub struct DeltaLevels<T> {
+    /// Encoded levels and their number of elements.
+    levels: Vec<(Vec<u8>, usize)>,
+    integral: bool,
+    element: PhantomData<T>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Encodes every level as varint differences of successive elements.
+    pub fn delta_encoded(&self) -> DeltaLevels<T> {
+        let integral = is_integral::<T>();
+        let levels = (0..self.num_levels())
+            .map(|level| {
+                let level = self.get_level(level).unwrap();
+                (encode(level, integral), level.len())
+            })
+            .collect();
+
+        DeltaLevels {
+            levels,
+            integral,
+            element: PhantomData,
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive> DeltaLevels<T> {
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Decodes the data on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Vec<T>> {
+        let (bytes, len) = self.levels.get(level)?;
+        Some(decode(bytes, *len, self.integral))
+    }
+
+    /// Returns the number of bytes of given encoded level.
+    /// If the level is out of bounds, returns 0.
+    pub fn encoded_size(&self, level: usize) -> usize {
+        self.levels.get(level).map_or(0, |(bytes, _)| bytes.len())
+    }
+}
+
+/// Encodes the differences of successive elements as varints.
+/// `integral` must be the result of [`is_integral`] for the element type.
+pub(crate) fn encode<T: ToPrimitive>(level: &[T], integral: bool) -> Vec<u8> {
+    let mut bytes = vec![];
+    let mut previous = 0;
+    for value in level {
+        let bits = to_bits(value, integral);
+        match integral {
+            true => {
+                let delta = (bits as i128).wrapping_sub(previous as i128);
+                write_varint(&mut bytes, ((delta << 1) ^ (delta >> 127)) as u128);
+            }
+            false => write_varint(&mut bytes, bits ^ previous),
+        }
+        previous = bits;
+    }
+    bytes
+}
+
+/// Decodes `len` elements encoded by [`encode`].
+pub(crate) fn decode<T: FromPrimitive>(bytes: &[u8], len: usize, integral: bool) -> Vec<T> {
+    let mut bytes = bytes.iter();
+    let mut previous = 0;
+    (0..len)
+        .map(|_| {
+            let encoded = read_varint(&mut bytes);
+            previous = match integral {
+                true => {
+                    let delta = (encoded >> 1) as i128 ^ -((encoded & 1) as i128);
+                    (previous as i128).wrapping_add(delta) as u128
+                }
+                false => encoded ^ previous,
+            };
+            from_bits(previous, integral)
+        })
+        .collect()
+}
+
+/// Returns the bits which are encoded for given value: the value itself for integers, and its `f64` bits otherwise.
+fn to_bits<T: ToPrimitive>(value: &T, integral: bool) -> u128 {
+    match integral {
+        true => value
+            .to_i128()
+            .map(|x| x as u128)
+            .or_else(|| value.to_u128())
+            .unwrap(),
+        false => u128::from(value.to_f64().unwrap().to_bits()),
+    }
+}
+
+fn from_bits<T: FromPrimitive>(bits: u128, integral: bool) -> T {
+    match integral {
+        true => T::from_i128(bits as i128).or_else(|| T::from_u128(bits)),
+        false => T::from_f64(f64::from_bits(bits as u64)),
+    }
+    .unwrap()
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint<'a>(bytes: &mut impl Iterator<Item = &'a u8>) -> u128 {
+    let mut value = 0;
+    for (i, &byte) in bytes.enumerate() {
+        value |= u128::from(byte & 0x7f) << (7 * i);
+        if byte < 0x80 {
+            break;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossless_roundtrip() {
+        let floats: Vec<f64> = (0..100).map(|x| (x as f64 * 0.1).sin()).collect();
+        let floats = MipMap1D::new([floats, vec![f64::INFINITY, -0.0]].concat());
+        let encoded = floats.delta_encoded();
+        for level in 0..floats.num_levels() {
+            let decoded = encoded.get_level(level).unwrap();
+            let expected = floats.get_level(level).unwrap();
+            assert!(decoded
+                .iter()
+                .zip(expected)
+                .all(|(a, b)| a.to_bits() == b.to_bits()));
+        }
+        assert_eq!(encoded.num_levels(), floats.num_levels());
+        assert_eq!(encoded.get_level(floats.num_levels()), None);
+
+        let extremes = MipMap1D::new(vec![i64::MIN, i64::MAX, 0, -1, u8::MAX.into()]);
+        assert_eq!(
+            extremes.delta_encoded().get_level(0).unwrap(),
+            extremes.get_level(0).unwrap()
+        );
+        let unsigned = MipMap1D::new(vec![u128::MAX, 0, u128::MAX - 1]);
+        assert_eq!(
+            unsigned.delta_encoded().get_level(0).unwrap(),
+            [u128::MAX, 0, u128::MAX - 1]
+        );
+    }
+
+    #[test]
+    fn test_smooth_levels_are_small() {
+        let mipmap = MipMap1D::new((0..4096).map(|x| 100_000 + x / 8).collect::<Vec<i64>>());
+        let encoded = mipmap.delta_encoded();
+
+        assert_eq!(encoded.encoded_size(0), 4096 + 2);
+        assert_eq!(encoded.encoded_size(mipmap.num_levels()), 0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::delta::{decode, encode};
+use crate::mipmap::is_integral;
+use crate::MipMap1D;
+
+/// Levels of a mipmap where rarely accessed levels are kept delta-encoded and decoded on access.
+/// Compression is lossless, using the delta and varint encoding of [`DeltaLevels`](crate::DeltaLevels),
+/// which pays off for smooth signals; general-purpose compressors like lz4 or zstd are not used.
+/// The most recently decompressed levels are cached, so switching between a few zoom levels does not decompress again.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{DeltaCompressedMipMap, MipMap1D};
+///
+/// let mipmap = MipMap1D::new((0..1000).collect::<Vec<u32>>());
+/// // Compress the levels in the middle of the pyramid, keeping the two finest and the two coarsest ones.
+/// let compressed = DeltaCompressedMipMap::new(&mipmap, |level, levels| (2..levels - 2).contains(&level), 2);
+/// assert!(compressed.is_compressed(4));
+/// assert_eq!(*compressed.get_level(4).unwrap(), *mipmap.get_level(4).unwrap());
+/// assert!(!compressed.is_compressed(0));
+/// ```
+pub struct DeltaCompressedMipMap<T> {
+    levels: Vec<Level<T>>,
+    /// Recently decompressed levels, the most recent one last.
+    cache: Mutex<Vec<(usize, Arc<[T]>)>>,
+    cache_capacity: usize,
+}
+
+enum Level<T> {
+    Plain(Arc<[T]>),
+    /// Encoded bytes and the number of elements.
+    Compressed(Vec<u8>, usize),
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> DeltaCompressedMipMap<T> {
+    /// Copies the levels of `mipmap`, compressing every level for which `compress(level, num_levels)` is true.
+    /// Up to `cache_capacity` decompressed levels are cached.
+    pub fn new(
+        mipmap: &MipMap1D<T>,
+        compress: impl Fn(usize, usize) -> bool,
+        cache_capacity: usize,
+    ) -> Self {
+        let levels = (0..mipmap.num_levels())
+            .map(|level| {
+                let data = mipmap.get_level(level).unwrap();
+                match compress(level, mipmap.num_levels()) {
+                    true => Level::Compressed(encode(data, is_integral::<T>()), data.len()),
+                    false => Level::Plain(data.into()),
+                }
+            })
+            .collect();
+
+        Self {
+            levels,
+            cache: Mutex::new(vec![]),
+            cache_capacity,
+        }
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns true if given level is stored compressed.
+    /// If the level is out of bounds, returns false.
+    pub fn is_compressed(&self, level: usize) -> bool {
+        matches!(self.levels.get(level), Some(Level::Compressed(..)))
+    }
+
+    /// Returns the data on given level, decompressing it if it is not cached.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Arc<[T]>> {
+        let (bytes, len) = match self.levels.get(level)? {
+            Level::Plain(data) => return Some(Arc::clone(data)),
+            Level::Compressed(bytes, len) => (bytes, *len),
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+        let data = match cache.iter().position(|(cached, _)| *cached == level) {
+            Some(position) => cache.remove(position).1,
+            None => decode(bytes, len, is_integral::<T>()).into(),
+        };
+        cache.push((level, Arc::clone(&data)));
+        if cache.len() > self.cache_capacity {
+            cache.remove(0);
+        }
+        Some(data)
+    }
+
+    /// Returns the levels currently cached in decompressed form, the least recently used one first.
+    pub fn cached_levels(&self) -> Vec<usize> {
+        let cache = self.cache.lock().unwrap();
+        cache.iter().map(|&(level, _)| level).collect()
+    }
+
+    /// Returns the number of bytes taken by the stored levels, without the cache.
+    pub fn stored_size(&self) -> usize {
+        self.levels
+            .iter()
+            .map(|level| match level {
+                Level::Plain(data) => data.len() * size_of::<T>(),
+                Level::Compressed(bytes, _) => bytes.len(),
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_are_decompressed_losslessly() {
+        let mipmap = MipMap1D::new((0..300).map(|x| (x as f32 * 0.05).sin()).collect());
+        let compressed = DeltaCompressedMipMap::new(&mipmap, |level, _| level % 2 == 1, 1);
+
+        assert_eq!(compressed.num_levels(), mipmap.num_levels());
+        for level in 0..mipmap.num_levels() {
+            assert_eq!(compressed.is_compressed(level), level % 2 == 1);
+            assert_eq!(
+                *compressed.get_level(level).unwrap(),
+                *mipmap.get_level(level).unwrap()
+            );
+        }
+        assert!(compressed.get_level(mipmap.num_levels()).is_none());
+        assert!(!compressed.is_compressed(mipmap.num_levels()));
+    }
+
+    #[test]
+    fn test_least_recently_used_level_is_evicted() {
+        let mipmap = MipMap1D::new(vec![7u64; 64]);
+        let compressed = DeltaCompressedMipMap::new(&mipmap, |_, _| true, 2);
+
+        compressed.get_level(0);
+        compressed.get_level(1);
+        compressed.get_level(0);
+        assert_eq!(compressed.cached_levels(), [1, 0]);
+        compressed.get_level(2);
+        assert_eq!(compressed.cached_levels(), [0, 2]);
+        assert!(compressed.stored_size() < 2 * 64);
+
+        let uncached = DeltaCompressedMipMap::new(&mipmap, |_, _| true, 0);
+        assert_eq!(*uncached.get_level(6).unwrap(), [7]);
+        assert!(uncached.cached_levels().is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+
+use num_traits::{Bounded, FromPrimitive, Num, Signed, ToPrimitive};
+
+use crate::overflow::saturating_sub;
+use crate::MipMap1D;
+
+impl<T: Num + Signed + Bounded + PartialOrd + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Constructs a mipmap of the first differences of the source data (the rate of change per sample),
+    /// using the same NaN policy.
+    /// The first difference is zero, so both mipmaps have the same length and bucket boundaries on every level,
+    /// and a coarse element is the mean rate of change over its bucket.
+    /// Integer differences which do not fit into the type saturate at its bounds,
+    /// see [`MipMap1D::widened_derivative`] to avoid that.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![1, 3, 7, 7, 5]);
+    /// let rate = mipmap.derivative();
+    /// assert_eq!(*rate.get_level(0).unwrap(), [0, 2, 4, 0, -2]);
+    /// assert_eq!(*rate.get_level(1).unwrap(), [1, 2, -2]);
+    /// assert_eq!(rate.num_levels(), mipmap.num_levels());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn derivative(&self) -> Self {
+        let source = self.source();
+        let differences = source
+            .first()
+            .map(|_| T::zero())
+            .into_iter()
+            .chain(
+                source
+                    .windows(2)
+                    .map(|pair| saturating_sub(pair[1], pair[0])),
+            )
+            .collect();
+
+        Self::build(differences, self.nan_policy(), self.rounding())
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Same as [`MipMap1D::derivative`], but the differences are computed in a wider type than the samples,
+    /// e.g. `i64` differences of `i32` samples, so they do not overflow.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![i32::MIN, i32::MAX]);
+    /// let rate = mipmap.widened_derivative::<i64>();
+    /// assert_eq!(*rate.get_level(0).unwrap(), [0, u32::MAX as i64]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn widened_derivative<W>(&self) -> MipMap1D<W>
+    where
+        W: Num + Signed + ToPrimitive + FromPrimitive + Copy + From<T>,
+    {
+        let source: Vec<W> = self.source().iter().map(|&x| W::from(x)).collect();
+        let differences = source
+            .first()
+            .map(|_| W::zero())
+            .into_iter()
+            .chain(source.windows(2).map(|pair| pair[1] - pair[0]))
+            .collect();
+
+        MipMap1D::build(differences, self.nan_policy(), self.rounding())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::NanPolicy;
+
+    #[test]
+    fn test_levels_are_aligned() {
+        let source: Vec<f64> = (0..37).map(|x| (x as f64).powi(2)).collect();
+        let mipmap = MipMap1D::new(source);
+        let rate = mipmap.derivative();
+
+        assert_eq!(rate.num_levels(), mipmap.num_levels());
+        for level in 0..mipmap.num_levels() {
+            assert_eq!(
+                rate.get_level(level).unwrap().len(),
+                mipmap.get_level(level).unwrap().len()
+            );
+        }
+        assert_eq!(rate.get_level(1).unwrap()[1], 4.0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_nan_policy_is_kept() {
+        let mipmap = MipMap1D::with_nan_policy(vec![1.0, f64::NAN, 4.0, 6.0], NanPolicy::Skip);
+        let rate = mipmap.derivative();
+
+        assert!(rate.get_level(0).unwrap()[1].is_nan());
+        assert_eq!(*rate.get_level(1).unwrap(), [0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_extreme_differences() {
+        let mipmap = MipMap1D::new(vec![i32::MIN, i32::MAX, i32::MIN, 0]);
+        let rate = mipmap.derivative();
+        assert_eq!(
+            *rate.get_level(0).unwrap(),
+            [0, i32::MAX, i32::MIN, i32::MAX]
+        );
+
+        let widened = mipmap.widened_derivative::<i64>();
+        let span = i64::from(i32::MAX) - i64::from(i32::MIN);
+        assert_eq!(
+            *widened.get_level(0).unwrap(),
+            [0, span, -span, -i64::from(i32::MIN)]
+        );
+        assert_eq!(widened.num_levels(), mipmap.num_levels());
+    }
+
+    #[test]
+    fn test_empty_source() {
+        let mipmap = MipMap1D::<i32>::new(vec![]);
+        assert_eq!(*mipmap.derivative().get_level(0).unwrap(), []);
+    }
+}
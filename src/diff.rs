@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::is_nan;
+use crate::stats::compare;
+use crate::MipMap1D;
+
+/// Differences between the same level of two mipmaps, see [`MipMap1D::compare`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelDiff {
+    pub level: usize,
+    /// Number of elements of the level in both mipmaps.
+    pub lens: (usize, usize),
+    /// Largest absolute difference between elements at the same index.
+    /// A NaN compared to a number is infinitely far from it, and two NaNs are equal.
+    pub max_deviation: f64,
+    /// Mean absolute difference between elements at the same index.
+    pub mean_deviation: f64,
+    /// Index of the first differing element, or of the first element only one of the levels has,
+    /// or None if the levels are equal.
+    pub first_difference: Option<usize>,
+}
+
+/// Differences between all levels of two mipmaps, see [`MipMap1D::compare`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelDiffReport {
+    /// Levels present in both mipmaps, from the finest one.
+    pub levels: Vec<LevelDiff>,
+    /// Number of levels of both mipmaps.
+    pub num_levels: (usize, usize),
+}
+
+impl LevelDiffReport {
+    /// Returns true if both mipmaps have the same levels.
+    pub fn is_identical(&self) -> bool {
+        self.num_levels.0 == self.num_levels.1
+            && self
+                .levels
+                .iter()
+                .all(|diff| diff.first_difference.is_none())
+    }
+
+    /// Returns the finest level which differs between the mipmaps, or None if all compared levels are equal.
+    pub fn first_differing_level(&self) -> Option<usize> {
+        self.levels
+            .iter()
+            .find(|diff| diff.first_difference.is_some())
+            .map(|diff| diff.level)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Compares every level with the same level of `other`, e.g. to check that levels updated by
+    /// [`MipMap1D::push`] match rebuilt ones, or how far another reduction or rounding moves them.
+    /// Levels pruned or missing in either mipmap are not compared.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Rounding};
+    ///
+    /// let data = vec![1, 2, 4, 7, 9];
+    /// let floor = MipMap1D::with_rounding(data.clone(), Rounding::Floor);
+    /// let report = floor.compare(&MipMap1D::with_rounding(data, Rounding::Ceil));
+    /// assert_eq!(report.first_differing_level(), Some(1));
+    /// let level = report.levels[1];
+    /// assert_eq!((level.max_deviation, level.first_difference), (1.0, Some(0)));
+    /// assert!(!report.is_identical());
+    /// ```
+    pub fn compare(&self, other: &Self) -> LevelDiffReport {
+        let levels = (0..self.num_levels().min(other.num_levels()))
+            .filter_map(|level| {
+                let (a, b) = (self.get_level(level)?, other.get_level(level)?);
+                Some(diff(level, a, b))
+            })
+            .collect();
+
+        LevelDiffReport {
+            levels,
+            num_levels: (self.num_levels(), other.num_levels()),
+        }
+    }
+}
+
+/// Compares two versions of a level.
+fn diff<T: Num + ToPrimitive>(level: usize, a: &[T], b: &[T]) -> LevelDiff {
+    let (mut max, mut sum, mut first_difference) = (0.0f64, 0.0, None);
+    for (index, (x, y)) in a.iter().zip(b).enumerate() {
+        let (equal, deviation) = match (is_nan(x), is_nan(y)) {
+            (true, true) => (true, 0.0),
+            (false, false) => (
+                compare(x, y) == Some(Ordering::Equal),
+                (x.to_f64().unwrap() - y.to_f64().unwrap()).abs(),
+            ),
+            _ => (false, f64::INFINITY),
+        };
+        if !equal {
+            first_difference = first_difference.or(Some(index));
+        }
+        max = max.max(deviation);
+        sum += deviation;
+    }
+    if a.len() != b.len() {
+        first_difference = first_difference.or(Some(a.len().min(b.len())));
+    }
+
+    let compared = a.len().min(b.len());
+    LevelDiff {
+        level,
+        lens: (a.len(), b.len()),
+        max_deviation: max,
+        mean_deviation: match compared {
+            0 => 0.0,
+            _ => sum / compared as f64,
+        },
+        first_difference,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pushed_matches_rebuilt() {
+        let source: Vec<f64> = (0..300).map(|x| (x as f64 * 0.3).sin()).collect();
+        let mut pushed = MipMap1D::new(vec![]);
+        for &x in &source {
+            pushed.push(x);
+        }
+        let report = pushed.compare(&MipMap1D::new(source));
+        assert!(report.is_identical());
+        assert_eq!(report.levels.len(), 10);
+        assert!(report.levels.iter().all(|diff| diff.max_deviation == 0.0));
+    }
+
+    #[test]
+    fn test_differences() {
+        let a = MipMap1D::new(vec![1.0, f64::NAN, 3.0, 4.0, 5.0]);
+        let b = MipMap1D::new(vec![1.0, f64::NAN, 3.0, 6.0]);
+        let report = a.compare(&b);
+
+        assert_eq!(report.num_levels, (4, 3));
+        let source = report.levels[0];
+        assert_eq!(source.lens, (5, 4));
+        assert_eq!(source.first_difference, Some(3));
+        assert_eq!((source.max_deviation, source.mean_deviation), (2.0, 0.5));
+        // Both first buckets are NaN, but the second ones differ.
+        assert_eq!(report.levels[1].first_difference, Some(1));
+        assert_eq!(report.levels[1].max_deviation, 1.0);
+
+        let c = MipMap1D::new(vec![1.0, 2.0]);
+        let d = MipMap1D::new(vec![f64::NAN, 2.0]);
+        assert_eq!(c.compare(&d).levels[0].max_deviation, f64::INFINITY);
+        assert_eq!(c.compare(&d).first_differing_level(), Some(0));
+    }
+}
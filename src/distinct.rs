@@ -0,0 +1,185 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Approximate numbers of distinct values in every bucket, e.g. unique user IDs per interval.
+/// Every bucket carries a HyperLogLog sketch with `2^precision` registers, and coarser buckets merge the sketches below them,
+/// so the estimates are never re-derived from the source.
+/// Sketches are stored from a selected coarse level upward.
+/// The standard error of the estimates is about `1.04 / sqrt(2^precision)`.
+/// Example:
+/// ```rust
+/// use mipmap_1d::DistinctLevels;
+///
+/// let ids: Vec<u32> = (0..4000).map(|x| x % 1000).collect();
+/// let distinct = DistinctLevels::new(&ids, 12, 2);
+/// let top = distinct.num_levels() - 1;
+/// assert!((distinct.estimate(top, 0).unwrap() - 1000.0).abs() < 50.0);
+/// assert_eq!(distinct.estimate(0, 0), None);
+/// ```
+pub struct DistinctLevels {
+    precision: u8,
+    min_level: usize,
+    /// Registers of the levels starting from `min_level`, stored as `data[level - min_level][index * 2^precision + register]`.
+    data: Vec<Vec<u8>>,
+}
+
+impl DistinctLevels {
+    /// Computes sketches for every bucket of the levels starting from `min_level`.
+    ///
+    /// # Panics
+    /// Panics if `precision` is not within `4..=16`.
+    pub fn new<T: Hash>(source: &[T], precision: u8, min_level: usize) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "The precision of a sketch must be within 4..=16"
+        );
+
+        let mut num_levels = 1;
+        while source.len() > 1 << (num_levels - 1) {
+            num_levels += 1;
+        }
+
+        let min_level = min_level.min(num_levels - 1);
+        let mut distinct = Self {
+            precision,
+            min_level,
+            data: vec![],
+        };
+
+        let registers = distinct.registers();
+        let mut level = vec![0; source.len().div_ceil(1 << min_level) * registers];
+        for (i, value) in source.iter().enumerate() {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            let hash = hasher.finish();
+
+            let register = (hash >> (64 - precision)) as usize;
+            let rank = ((hash << precision) | (1 << (precision - 1))).leading_zeros() as u8 + 1;
+            let slot = &mut level[(i >> min_level) * registers + register];
+            *slot = (*slot).max(rank);
+        }
+        distinct.data.push(level);
+
+        while distinct.num_levels() < num_levels {
+            let current = &distinct.data[distinct.data.len() - 1];
+            let mipmap = current
+                .chunks(2 * registers)
+                .flat_map(|pair| {
+                    (0..registers).map(move |register| {
+                        pair.iter()
+                            .skip(register)
+                            .step_by(registers)
+                            .copied()
+                            .max()
+                            .unwrap()
+                    })
+                })
+                .collect();
+            distinct.data.push(mipmap);
+        }
+
+        distinct
+    }
+
+    /// Returns the total number of downsampled levels, including those without sketches.
+    pub fn num_levels(&self) -> usize {
+        self.min_level + self.data.len()
+    }
+
+    /// Returns the finest level which has sketches.
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Estimates the number of distinct values in bucket `index` of given level.
+    /// If the level has no sketches, or the level or the index is out of bounds, returns None
+    pub fn estimate(&self, level: usize, index: usize) -> Option<f64> {
+        let registers = self.registers();
+        let level = self.data.get(level.checked_sub(self.min_level)?)?;
+        let sketch = level.get(index * registers..(index + 1) * registers)?;
+
+        let m = registers as f64;
+        let alpha = match registers {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = sketch.iter().map(|&rank| (-f64::from(rank)).exp2()).sum();
+        let raw = alpha * m * m / sum;
+
+        // Linear counting is more accurate for small cardinalities.
+        let empty = sketch.iter().filter(|&&rank| rank == 0).count();
+        if raw <= 2.5 * m && empty > 0 {
+            return Some(m * (m / empty as f64).ln());
+        }
+
+        Some(raw)
+    }
+
+    /// Returns the estimates of all buckets on given level.
+    /// If the level has no sketches or is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Vec<f64>> {
+        let len = self.data.get(level.checked_sub(self.min_level)?)?.len() / self.registers();
+        (0..len).map(|index| self.estimate(level, index)).collect()
+    }
+
+    /// Returns the number of registers of every sketch.
+    fn registers(&self) -> usize {
+        1 << self.precision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_buckets() {
+        let ids = ["a", "b", "a", "a", "c"];
+        let distinct = DistinctLevels::new(&ids, 10, 0);
+
+        assert_eq!(distinct.num_levels(), 4);
+        let round = |level| -> Vec<f64> {
+            distinct
+                .get_level(level)
+                .unwrap()
+                .iter()
+                .map(|x| x.round())
+                .collect()
+        };
+        assert_eq!(round(0), [1.0; 5]);
+        assert_eq!(round(1), [2.0, 1.0, 1.0]);
+        assert_eq!(round(3), [3.0]);
+        assert_eq!(distinct.estimate(3, 1), None);
+        assert_eq!(distinct.get_level(4), None);
+    }
+
+    #[test]
+    fn test_large_cardinalities() {
+        let ids: Vec<u64> = (0..100_000).collect();
+        let distinct = DistinctLevels::new(&ids, 12, 8);
+
+        assert_eq!(distinct.min_level(), 8);
+        let top = distinct.num_levels() - 1;
+        let estimate = distinct.estimate(top, 0).unwrap();
+        assert!((estimate - 100_000.0).abs() / 100_000.0 < 0.05);
+
+        let halves = distinct.get_level(top - 1).unwrap();
+        assert!((halves[0] - 65_536.0).abs() / 65_536.0 < 0.05);
+    }
+
+    #[test]
+    fn test_empty_source() {
+        let distinct = DistinctLevels::new::<u8>(&[], 4, 3);
+
+        assert_eq!(distinct.num_levels(), 1);
+        assert_eq!(distinct.get_level(0), Some(vec![]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_precision() {
+        DistinctLevels::new(&[1], 20, 0);
+    }
+}
@@ -0,0 +1,114 @@
+use core::ops::{Deref, DerefMut};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::storage::LevelStorage;
+use crate::MipMap1D;
+
+/// Mutable access to one level of a mipmap, created by [`MipMap1D::level_mut`].
+/// When the guard is dropped after the level was changed, all coarser levels are recomputed from it,
+/// so the mipmap never stays inconsistent. Finer levels are kept as they are.
+pub struct LevelMut<'a, T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> {
+    mipmap: &'a mut MipMap1D<T, S>,
+    level: usize,
+    changed: bool,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> MipMap1D<T, S> {
+    /// Lets the elements of given level be patched in place, e.g. to inject a precomputed segment.
+    /// The coarser levels, statistics and hashes are recomputed once the returned guard is dropped,
+    /// which takes `O(len / 2^level)` steps. Elements can not be added or removed.
+    /// With [`NanPolicy::Missing`](crate::NanPolicy::Missing), patched elements above the source
+    /// are assumed to have as many valid samples as the ones they replace.
+    /// If the level is out of bounds or was pruned, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// mipmap.level_mut(0).unwrap()[2..4].copy_from_slice(&[10, 12]);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 11, 9]);
+    /// assert_eq!(*mipmap.get_level(3).unwrap(), [8]);
+    ///
+    /// mipmap.level_mut(2).unwrap()[1] = 1;
+    /// assert_eq!(*mipmap.get_level(3).unwrap(), [4]);
+    /// ```
+    pub fn level_mut(&mut self, level: usize) -> Option<LevelMut<'_, T, S>> {
+        self.get_level(level)?;
+        Some(LevelMut {
+            mipmap: self,
+            level,
+            changed: false,
+        })
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> Deref
+    for LevelMut<'_, T, S>
+{
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.mipmap.get_level(self.level).unwrap()
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> DerefMut
+    for LevelMut<'_, T, S>
+{
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.changed = true;
+        self.mipmap.level_data_mut(self.level).unwrap()
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> Drop for LevelMut<'_, T, S> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.mipmap.recompute_above(self.level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::NanPolicy;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_patched_source_matches_rebuilt() {
+        let mut source: Vec<f64> = (0..100).map(|x| (x as f64 * 0.2).sin()).collect();
+        for policy in [NanPolicy::Propagate, NanPolicy::Missing] {
+            let mut mipmap = MipMap1D::with_nan_policy(source.clone(), policy);
+            let segment = [1.0, f64::NAN, 3.0, 4.0, 5.0];
+            mipmap.level_mut(0).unwrap()[37..42].copy_from_slice(&segment);
+            source[37..42].copy_from_slice(&segment);
+
+            let rebuilt = MipMap1D::with_nan_policy(source.clone(), policy);
+            assert!(mipmap.compare(&rebuilt).is_identical());
+            assert_eq!(mipmap.level_stats(3), rebuilt.level_stats(3));
+            assert_eq!(mipmap.digest(), rebuilt.digest());
+            assert_eq!(mipmap.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_coarse_edits_and_bounds() {
+        let mut mipmap = MipMap1D::new(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let snapshot = mipmap.clone();
+        assert_eq!(mipmap.level_mut(1).unwrap().len(), 4);
+        // Reading through the guard neither copies the shared levels nor recomputes them.
+        assert_eq!(mipmap.buffer().as_ptr(), snapshot.buffer().as_ptr());
+
+        mipmap.level_mut(1).unwrap()[3] = 20;
+        assert_eq!(*mipmap.get_level(0).unwrap(), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(*mipmap.get_level(2).unwrap(), [2, 12]);
+        assert_eq!(*mipmap.get_level(3).unwrap(), [7]);
+
+        mipmap.prune_below(2);
+        assert!(mipmap.level_mut(1).is_none());
+        assert!(mipmap.level_mut(4).is_none());
+    }
+}
@@ -0,0 +1,196 @@
+use alloc::{vec, vec::Vec};
+
+/// Creates several downsampled versions of given vector of event flags, e.g. markers overlaid on a chart.
+/// A coarse bucket is `true` if any of the underlying samples is `true`,
+/// so rare events stay visible when zoomed out.
+/// Levels are stored as packed bitmaps of `u64` words and reduced a word at a time,
+/// taking 8x less memory than `Vec<bool>`.
+/// Example:
+/// ```rust
+/// use mipmap_1d::EventMipMap;
+///
+/// let data = vec![false, false, false, true, false];
+/// let mipmap = EventMipMap::new(data);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// assert_eq!(mipmap.get_level(1).unwrap(), [false, true, false]);
+/// assert_eq!(mipmap.get_level(2).unwrap(), [true, false]);
+/// assert_eq!(mipmap.get_level(3).unwrap(), [true]);
+/// assert_eq!(mipmap.get(2, 0), Some(true));
+/// ```
+pub struct EventMipMap {
+    data: Vec<BitLevel>,
+}
+
+/// A packed level of flags.
+/// Bit `i % 64` of word `i / 64` holds flag `i`; bits past `len` are always zero.
+struct BitLevel {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl EventMipMap {
+    pub fn new(source: Vec<bool>) -> Self {
+        let mut words = vec![0; source.len().div_ceil(64)];
+        for (i, _) in source.iter().enumerate().filter(|(_, &x)| x) {
+            words[i / 64] |= 1 << (i % 64);
+        }
+
+        let mut data = vec![BitLevel {
+            words,
+            len: source.len(),
+        }];
+
+        while data[data.len() - 1].len > 1 {
+            let mipmap = data[data.len() - 1].downsample();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the number of flags on given level.
+    /// If the level is out of bounds, returns None
+    pub fn level_len(&self, level: usize) -> Option<usize> {
+        self.data.get(level).map(|level| level.len)
+    }
+
+    /// Returns the flag at `index` of given level.
+    /// If the level or the index is out of bounds, returns None
+    pub fn get(&self, level: usize, index: usize) -> Option<bool> {
+        let level = self.data.get(level)?;
+        if index >= level.len {
+            return None;
+        }
+
+        Some(level.words[index / 64] >> (index % 64) & 1 == 1)
+    }
+
+    /// Returns the unpacked flags on given level.
+    /// Level `0` returns the source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Vec<bool>> {
+        let len = self.level_len(level)?;
+        (0..len).map(|index| self.get(level, index)).collect()
+    }
+
+    /// Returns the packed words of given level.
+    /// Bit `i % 64` of word `i / 64` holds flag `i`, and the bits past the end of the level are zero.
+    /// If the level is out of bounds, returns None
+    pub fn level_words(&self, level: usize) -> Option<&[u64]> {
+        self.data.get(level).map(|level| level.words.as_slice())
+    }
+}
+
+impl BitLevel {
+    /// ORs the pairs of flags, turning every two source words into one.
+    fn downsample(&self) -> Self {
+        let words = self
+            .words
+            .chunks(2)
+            .map(|pair| {
+                let low = compress_pairs(pair[0]);
+                let high = pair.get(1).map_or(0, |&word| compress_pairs(word));
+                low | (high << 32)
+            })
+            .collect();
+
+        Self {
+            words,
+            len: self.len.div_ceil(2),
+        }
+    }
+}
+
+/// ORs every pair of adjacent bits of a word, packing the 32 results into its lower half.
+fn compress_pairs(word: u64) -> u64 {
+    let mut x = (word | (word >> 1)) & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Downsamples the flags one by one, as a reference for the packed implementation.
+    fn naive_levels(source: Vec<bool>) -> Vec<Vec<bool>> {
+        let mut data = vec![source];
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1]
+                .chunks(2)
+                .map(|pair| pair.iter().any(|&x| x))
+                .collect();
+            data.push(mipmap);
+        }
+        data
+    }
+
+    #[test]
+    fn test_compress_pairs() {
+        assert_eq!(compress_pairs(0), 0);
+        assert_eq!(compress_pairs(u64::MAX), 0xFFFF_FFFF);
+        assert_eq!(compress_pairs(0b0110_0001), 0b1101);
+        assert_eq!(compress_pairs(1 << 63), 1 << 31);
+    }
+
+    #[test]
+    fn test_matches_naive_levels() {
+        let mut state = 12345u32;
+        for len in [0, 1, 2, 63, 64, 65, 127, 128, 129, 1000] {
+            let source: Vec<bool> = (0..len)
+                .map(|_| {
+                    state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                    state >> 16 & 0x1F == 0
+                })
+                .collect();
+            let mipmap = EventMipMap::new(source.clone());
+            let target = naive_levels(source);
+
+            assert_eq!(mipmap.num_levels(), target.len());
+            for (level, target_item) in target.iter().enumerate() {
+                assert_eq!(
+                    mipmap.get_level(level).unwrap(),
+                    *target_item,
+                    "length {len}"
+                );
+                assert_eq!(
+                    mipmap.level_words(level).unwrap().len(),
+                    target_item.len().div_ceil(64)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rare_event_stays_visible() {
+        let mut data = vec![false; 1000];
+        data[777] = true;
+        let mipmap = EventMipMap::new(data);
+
+        for level in 0..mipmap.num_levels() {
+            let flags = mipmap.get_level(level).unwrap();
+            assert_eq!(flags.iter().filter(|&&x| x).count(), 1);
+            assert_eq!(mipmap.get(level, 777 >> level), Some(true));
+        }
+    }
+
+    #[test]
+    fn test_no_events() {
+        let mipmap = EventMipMap::new(vec![false; 5]);
+
+        assert_eq!(mipmap.num_levels(), 4);
+        assert_eq!(mipmap.get_level(3).unwrap(), [false]);
+        assert_eq!(mipmap.get_level(4), None);
+        assert_eq!(mipmap.get(0, 5), None);
+        assert_eq!(mipmap.level_len(2), Some(2));
+    }
+}
@@ -0,0 +1,155 @@
+//! C API for mipmaps of `double` samples, declared in `include/mipmap_1d.h`.
+//! A C library is built with `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`).
+//! Handles are created by [`mipmap_f64_new`] and must be released with [`mipmap_f64_free`].
+//! Pointers to level data stay valid until the handle is modified or released.
+//! The `python` feature builds the same API for the ctypes module in `python/mipmap_1d.py`.
+
+use std::ptr;
+
+use crate::viewport::Viewport;
+use crate::MipMap1D;
+
+/// Opaque handle to a mipmap of `double` samples.
+pub struct MipMapF64(pub(crate) MipMap1D<f64>);
+
+/// The part of a level which covers a viewport, as returned by [`mipmap_f64_query`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MipMapSlice {
+    /// The level the data comes from.
+    pub level: usize,
+    /// Index of the first element of `data` within the level.
+    pub start: usize,
+    pub data: *const f64,
+    pub len: usize,
+}
+
+/// Creates a mipmap of `len` samples starting at `data`, which are copied.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable samples, or may be null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_new(data: *const f64, len: usize) -> *mut MipMapF64 {
+    let source = match len {
+        0 => vec![],
+        _ => unsafe { std::slice::from_raw_parts(data, len) }.to_vec(),
+    };
+    Box::into_raw(Box::new(MipMapF64(MipMap1D::new(source))))
+}
+
+/// Releases a mipmap. Null handles are ignored.
+///
+/// # Safety
+///
+/// `mipmap` must be null or a handle returned by [`mipmap_f64_new`] which was not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_free(mipmap: *mut MipMapF64) {
+    if !mipmap.is_null() {
+        drop(unsafe { Box::from_raw(mipmap) });
+    }
+}
+
+/// Appends a sample, updating all levels.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_push(mipmap: *mut MipMapF64, value: f64) {
+    unsafe { &mut *mipmap }.0.push(value);
+}
+
+/// Returns the total number of downsampled levels.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_num_levels(mipmap: *const MipMapF64) -> usize {
+    unsafe { &*mipmap }.0.num_levels()
+}
+
+/// Returns the data on given level and writes its length to `len`.
+/// If the level is out of bounds, returns null and writes `0`.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle and `len` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_get_level(
+    mipmap: *const MipMapF64,
+    level: usize,
+    len: *mut usize,
+) -> *const f64 {
+    let (data, level_len) = match unsafe { &*mipmap }.0.get_level(level) {
+        Some(data) => (data.as_ptr(), data.len()),
+        None => (ptr::null(), 0),
+    };
+    unsafe { len.write(level_len) };
+    data
+}
+
+/// Returns the data of the finest level which displays the source samples `start..end`
+/// with at most `max_points` points, like [`MipMap1D::query`].
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_query(
+    mipmap: *const MipMapF64,
+    start: usize,
+    end: usize,
+    max_points: usize,
+) -> MipMapSlice {
+    let slice = unsafe { &*mipmap }
+        .0
+        .query(&Viewport::new(start..end, max_points));
+    MipMapSlice {
+        level: slice.level,
+        start: slice.start,
+        data: slice.data.as_ptr(),
+        len: slice.data.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_lifecycle() {
+        let source = [2.0, 4.0, 6.0, 8.0];
+        unsafe {
+            let mipmap = mipmap_f64_new(source.as_ptr(), source.len());
+            mipmap_f64_push(mipmap, 9.0);
+            assert_eq!(mipmap_f64_num_levels(mipmap), 4);
+
+            let mut len = 0;
+            let level = mipmap_f64_get_level(mipmap, 1, &mut len);
+            assert_eq!(std::slice::from_raw_parts(level, len), [3.0, 7.0, 9.0]);
+            assert!(mipmap_f64_get_level(mipmap, 4, &mut len).is_null());
+            assert_eq!(len, 0);
+
+            let slice = mipmap_f64_query(mipmap, 1, 5, 2);
+            assert_eq!((slice.level, slice.start, slice.len), (2, 0, 2));
+            assert_eq!(
+                std::slice::from_raw_parts(slice.data, slice.len),
+                [5.0, 9.0]
+            );
+            mipmap_f64_free(mipmap);
+        }
+    }
+
+    #[test]
+    fn test_empty_and_null() {
+        unsafe {
+            let mipmap = mipmap_f64_new(ptr::null(), 0);
+            assert_eq!(mipmap_f64_num_levels(mipmap), 1);
+            assert_eq!(mipmap_f64_query(mipmap, 0, 10, 5).len, 0);
+            mipmap_f64_free(mipmap);
+            mipmap_f64_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,100 @@
+// Numeric types for tests which are neither primitive integers nor floats.
+
+/// Defines a minimal fixed-point type with `$scale` steps per unit, stored as `$repr`,
+/// which only implements the num-traits traits the mipmaps rely on.
+/// Converting from f64 panics, so any floating-point round trip fails the test.
+/// The type is not `Copy`, like arbitrary-precision types.
+macro_rules! fixed_point {
+    ($name:ident, $repr:ty, $wide:ty, $scale:expr) => {
+        #[derive(Debug, Clone, PartialEq, PartialOrd)]
+        pub(crate) struct $name(pub(crate) $repr);
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+            fn mul(self, other: Self) -> Self {
+                Self((<$wide>::from(self.0) * <$wide>::from(other.0) / $scale) as $repr)
+            }
+        }
+
+        impl std::ops::Div for $name {
+            type Output = Self;
+            fn div(self, other: Self) -> Self {
+                Self((<$wide>::from(self.0) * $scale / <$wide>::from(other.0)) as $repr)
+            }
+        }
+
+        impl std::ops::Rem for $name {
+            type Output = Self;
+            fn rem(self, other: Self) -> Self {
+                Self(self.0 % other.0)
+            }
+        }
+
+        impl num_traits::Zero for $name {
+            fn zero() -> Self {
+                Self(0)
+            }
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl num_traits::One for $name {
+            fn one() -> Self {
+                Self($scale as $repr)
+            }
+        }
+
+        impl num_traits::Num for $name {
+            type FromStrRadixErr = ();
+            fn from_str_radix(_: &str, _: u32) -> Result<Self, ()> {
+                Err(())
+            }
+        }
+
+        impl num_traits::ToPrimitive for $name {
+            fn to_i64(&self) -> Option<i64> {
+                num_traits::ToPrimitive::to_i64(&(<$wide>::from(self.0) / $scale))
+            }
+            fn to_u64(&self) -> Option<u64> {
+                num_traits::ToPrimitive::to_u64(&(<$wide>::from(self.0) / $scale))
+            }
+            fn to_f64(&self) -> Option<f64> {
+                Some(self.0 as f64 / $scale as f64)
+            }
+        }
+
+        impl num_traits::FromPrimitive for $name {
+            fn from_i64(n: i64) -> Option<Self> {
+                Some(Self((<$wide>::from(n) * $scale) as $repr))
+            }
+            fn from_u64(n: u64) -> Option<Self> {
+                Some(Self((n as $wide * $scale) as $repr))
+            }
+            fn from_f64(_: f64) -> Option<Self> {
+                panic!("Fixed-point values must not go through f64")
+            }
+        }
+    };
+}
+
+// A decimal with nine fractional digits.
+fixed_point!(Decimal, i128, i128, 1_000_000_000);
+// A binary fixed-point number with 16 integer and 16 fractional bits.
+fixed_point!(I16F16, i32, i64, 1 << 16);
+// An integer which is only `Clone`, like arbitrary-precision integers.
+fixed_point!(CloneInt, i128, i128, 1);
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, MipMapConfig, NanPolicy, Reduction, Rounding};
+
+/// Number of bytes which choose the configuration.
+const CONFIG_LEN: usize = 4;
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Constructs a valid mipmap from arbitrary bytes, e.g. the input of a fuzz target.
+    /// The crate does not implement `arbitrary::Arbitrary`, so the bytes are decoded by this method instead.
+    /// The first bytes choose the configuration, and every further pair of bytes a sample:
+    /// 16-bit integers, of which only the low byte is used if they do not fit into `T`, and NaN for `0x8000` if `T` has NaN.
+    /// Every input is accepted, so fuzzers quickly reach the code under test.
+    /// The NaN policy byte only matters for float types, see [`MipMapConfig::nan_policy`].
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// // E.g. `fuzz_target!(|data: &[u8]| render(&MipMap1D::<f32>::from_fuzz_bytes(data)))`.
+    /// let mipmap = MipMap1D::<f32>::from_fuzz_bytes(&[0, 0, 0, 0, 1, 0, 0, 0x80, 3, 0]);
+    /// assert_eq!(mipmap.get_level(0).unwrap()[0], 1.0);
+    /// assert!(mipmap.get_level(0).unwrap()[1].is_nan());
+    /// ```
+    pub fn from_fuzz_bytes(bytes: &[u8]) -> Self {
+        let (config, samples) = bytes.split_at(bytes.len().min(CONFIG_LEN));
+        let config = config_of(config);
+        let source = samples
+            .chunks(2)
+            .map(|pair| {
+                let value = i16::from_le_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]);
+                let nan = (value == i16::MIN).then(|| T::from_f64(f64::NAN)).flatten();
+                nan.or_else(|| T::from_i16(value))
+                    .or_else(|| T::from_u16(value as u16))
+                    .or_else(|| T::from_u8(value as u8))
+                    .unwrap_or_else(T::zero)
+            })
+            .collect();
+        Self::from_config(source, config)
+    }
+
+    /// Returns an endless sequence of mipmaps with random samples and configurations, generated from `seed`,
+    /// e.g. as the cases of a property test. This is not a proptest strategy, so failing cases are not shrunk.
+    /// The same seed always yields the same mipmaps, so failing cases can be reproduced.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Viewport};
+    ///
+    /// for mipmap in MipMap1D::<i32>::random_cases(42).take(100) {
+    ///     let slice = mipmap.query(&Viewport::new(0..1000, 64));
+    ///     // Mipmaps with limited levels may not reduce the viewport enough.
+    ///     assert!(slice.data.len() <= 64 || slice.level == mipmap.num_levels() - 1);
+    /// }
+    /// ```
+    pub fn random_cases(seed: u64) -> impl Iterator<Item = Self> {
+        let mut state = seed;
+        core::iter::repeat_with(move || {
+            let mut next = || {
+                // SplitMix64.
+                state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+                let mut z = state;
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^ (z >> 31)
+            };
+            let len = CONFIG_LEN + (next() % 512) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            Self::from_fuzz_bytes(&bytes)
+        })
+    }
+}
+
+/// Chooses a configuration from up to [`CONFIG_LEN`] bytes; missing bytes are zero.
+fn config_of(bytes: &[u8]) -> MipMapConfig {
+    let byte = |index: usize| bytes.get(index).copied().unwrap_or(0);
+    MipMapConfig {
+        nan_policy: match byte(0) % 3 {
+            0 => NanPolicy::Propagate,
+            1 => NanPolicy::Skip,
+            _ => NanPolicy::Missing,
+        },
+        rounding: match byte(1) % 5 {
+            0 => Rounding::TowardZero,
+            1 => Rounding::Floor,
+            2 => Rounding::Ceil,
+            3 => Rounding::HalfEven,
+            _ => Rounding::HalfAway,
+        },
+        reduction: match byte(2) % 5 {
+            0 => Reduction::Mean,
+            1 => Reduction::Min,
+            2 => Reduction::Max,
+            3 => Reduction::First,
+            _ => Reduction::Last,
+        },
+        max_levels: match byte(3) {
+            0 => None,
+            max => Some(usize::from(max % 16) + 1),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_any_bytes_are_accepted() {
+        assert_eq!(MipMap1D::<u8>::from_fuzz_bytes(&[]).num_levels(), 1);
+        let mipmap = MipMap1D::<u8>::from_fuzz_bytes(&[1, 0, 1, 5, 0xff, 0xff, 7]);
+        // Integers have no NaN, so the chosen policy is ignored.
+        assert_eq!(mipmap.config().nan_policy, NanPolicy::Propagate);
+        assert_eq!(mipmap.config().reduction, Reduction::Min);
+        // -1 does not fit, so its low byte is used; a single trailing byte is still a sample.
+        assert_eq!(*mipmap.get_level(0).unwrap(), [255, 7]);
+    }
+
+    #[test]
+    fn test_integer_cases_do_not_panic() {
+        for seed in 0..4 {
+            assert!(MipMap1D::<u8>::random_cases(seed)
+                .take(200)
+                .all(|case| case.verify().is_ok()));
+            assert!(MipMap1D::<i16>::random_cases(seed)
+                .take(200)
+                .all(|case| case.verify().is_ok()));
+            assert!(MipMap1D::<i64>::random_cases(seed)
+                .take(200)
+                .all(|case| case.verify().is_ok()));
+        }
+
+        // Every policy byte, with samples which overflow when summed in the sample type.
+        let bytes: Vec<u8> = (0..=255).chain([200, 0].repeat(64)).collect();
+        for start in 0..256 {
+            let mipmap = MipMap1D::<u8>::from_fuzz_bytes(&bytes[start..]);
+            assert!(mipmap.verify().is_ok());
+            assert!(MipMap1D::<i16>::from_fuzz_bytes(&bytes[start..])
+                .verify()
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_random_cases_are_reproducible() {
+        let cases: Vec<_> = MipMap1D::<i32>::random_cases(7).take(50).collect();
+        let again: Vec<_> = MipMap1D::<i32>::random_cases(7).take(50).collect();
+
+        for (case, other) in cases.iter().zip(&again) {
+            assert_eq!(case.config(), other.config());
+            assert_eq!(case.get_level(0), other.get_level(0));
+            assert!(case.verify().is_ok());
+        }
+        assert!(cases.iter().any(|case| case.config().max_levels.is_some()));
+        assert!(cases
+            .iter()
+            .any(|case| case.get_level(0).unwrap().len() > 100));
+    }
+}
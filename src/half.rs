@@ -0,0 +1,76 @@
+use crate::MipMap1D;
+
+impl MipMap1D<f32> {
+    /// Constructs the levels of half-precision (IEEE 754 binary16) samples, given as their bit patterns.
+    /// Samples are widened to f32 first, so the levels do not lose precision when averaged.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// // 1.0, 2.0, and 0.5 in half precision.
+    /// let mipmap = MipMap1D::from_f16_bits(&[0x3c00, 0x4000, 0x3800]);
+    /// assert_eq!(*mipmap.get_level(0).unwrap(), [1.0, 2.0, 0.5]);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [1.5, 0.5]);
+    /// ```
+    pub fn from_f16_bits(source: &[u16]) -> Self {
+        Self::new(source.iter().map(|&bits| f16_to_f32(bits)).collect())
+    }
+
+    /// Constructs the levels of bfloat16 samples, given as their bit patterns.
+    /// Samples are widened to f32 first, so the levels do not lose precision when averaged.
+    pub fn from_bf16_bits(source: &[u16]) -> Self {
+        Self::new(source.iter().map(|&bits| bf16_to_f32(bits)).collect())
+    }
+}
+
+/// Converts a binary16 bit pattern into f32 without loss.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let bits = u32::from(bits);
+    let sign = (bits & 0x8000) << 16;
+    let exponent = (bits >> 10) & 0x1f;
+    let fraction = bits & 0x3ff;
+
+    match exponent {
+        // Subnormal numbers are normal in f32, so they are converted arithmetically.
+        0 => {
+            // Scaled by 2^-24, the value of the lowest fraction bit.
+            let magnitude = fraction as f32 / 16_777_216.0;
+            f32::from_bits(sign | magnitude.to_bits())
+        }
+        // Infinities and NaNs.
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (fraction << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (fraction << 13)),
+    }
+}
+
+/// Converts a bfloat16 bit pattern into f32 without loss.
+pub fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits(u32::from(bits) << 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f16_conversion() {
+        assert_eq!(f16_to_f32(0x0000), 0.0);
+        assert!(f16_to_f32(0x8000).is_sign_negative());
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x7bff), 65504.0);
+        assert_eq!(f16_to_f32(0x0001), 5.960_464_5e-8);
+        assert_eq!(f16_to_f32(0x03ff), 6.097_555e-5);
+        assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+        assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+        assert!(f16_to_f32(0x7e00).is_nan());
+    }
+
+    #[test]
+    fn test_bf16_levels() {
+        // 1.0, 1.0078125 (the next value after 1.0), 3.0 and -1.0 in bfloat16.
+        let mipmap = MipMap1D::from_bf16_bits(&[0x3f80, 0x3f81, 0x4040, 0xbf80]);
+
+        assert_eq!(*mipmap.get_level(1).unwrap(), [1.003_906_3, 1.0]);
+        assert_eq!(bf16_to_f32(0x7f80), f32::INFINITY);
+    }
+}
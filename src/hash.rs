@@ -0,0 +1,90 @@
+use num_traits::{Num, ToPrimitive};
+
+use crate::mipmap::is_integral;
+
+/// Running hash of a sequence of numbers, which can be continued as elements are appended.
+/// Bytes are added with FNV-1a, and numbers as whole 64-bit words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LevelHash(u64);
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+/// Floats below this magnitude represent their integer part exactly.
+const EXACT_INTEGERS: f64 = 9_007_199_254_740_992.0;
+/// Multiplier used to mix in words, the one of FxHash.
+const WORD_PRIME: u64 = 0x517c_c1b7_2722_0a95;
+
+impl LevelHash {
+    pub(crate) fn new() -> Self {
+        Self(OFFSET_BASIS)
+    }
+
+    /// Adds the bytes of a value.
+    pub(crate) fn write(self, bytes: &[u8]) -> Self {
+        Self(bytes.iter().fold(self.0, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        }))
+    }
+
+    /// Adds a word, which takes one multiplication instead of one per byte.
+    fn word(self, word: u64) -> Self {
+        Self((self.0.rotate_left(5) ^ word).wrapping_mul(WORD_PRIME))
+    }
+
+    /// Adds a number. Integers are hashed by their exact value, and other numbers by their float value.
+    /// The integer part of numbers beyond the precision of `f64` is hashed as well, so it is not lost either.
+    pub(crate) fn with<T: Num + ToPrimitive>(self, value: &T) -> Self {
+        if is_integral::<T>() {
+            if let Some(x) = value.to_i64() {
+                return self.word(x as u64);
+            }
+            return self.integer(value);
+        }
+
+        let float = value.to_f64().unwrap_or(f64::NAN);
+        let hash = self.word(float.to_bits());
+        match float.abs() < EXACT_INTEGERS {
+            true => hash,
+            false => hash.integer(value),
+        }
+    }
+
+    /// Adds the integer value of a number, or zero if it has none.
+    fn integer<T: ToPrimitive>(self, value: &T) -> Self {
+        let integer = value
+            .to_i128()
+            .map(|x| x as u128)
+            .or_else(|| value.to_u128())
+            .unwrap_or(0);
+        self.word(integer as u64).word((integer >> 64) as u64)
+    }
+
+    pub(crate) fn value(self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_distinguishes_values() {
+        let hash = |values: &[f64]| {
+            values
+                .iter()
+                .fold(LevelHash::new(), |hash, x| hash.with(x))
+                .value()
+        };
+        assert_eq!(hash(&[1.0, 2.0]), hash(&[1.0, 2.0]));
+        assert_ne!(hash(&[1.0, 2.0]), hash(&[2.0, 1.0]));
+        assert_ne!(hash(&[1.0]), hash(&[1.5]));
+        assert_ne!(hash(&[0.0]), hash(&[-0.0]));
+
+        let big = LevelHash::new().with(&u64::MAX).value();
+        assert_ne!(big, LevelHash::new().with(&(u64::MAX - 1)).value());
+        let small = LevelHash::new().with(&-1i8).value();
+        assert_ne!(small, LevelHash::new().with(&1i8).value());
+        assert_ne!(small, LevelHash::new().with(&u8::MAX).value());
+    }
+}
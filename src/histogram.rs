@@ -0,0 +1,156 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Fixed-bin histograms of the source samples in every bucket, stored from a selected coarse level upward.
+/// Enables density renderings (e.g. darker shading where values cluster) when zoomed far out.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new(vec![0.1, 0.2, 0.9, 0.3, 0.6]);
+/// let histograms = mipmap.histograms(0.0..1.0, 2, 1);
+/// assert_eq!(histograms.histogram(0, 0), None);
+/// assert_eq!(histograms.histogram(1, 1), Some(&[1, 1][..]));
+/// assert_eq!(histograms.histogram(3, 0), Some(&[3, 2][..]));
+/// ```
+pub struct HistogramLevels {
+    range: Range<f64>,
+    bins: usize,
+    min_level: usize,
+    /// Histograms of the levels starting from `min_level`, stored as `data[level - min_level][index * bins + bin]`.
+    data: Vec<Vec<u32>>,
+}
+
+impl HistogramLevels {
+    /// Returns the total number of downsampled levels, including those without histograms.
+    pub fn num_levels(&self) -> usize {
+        self.min_level + self.data.len()
+    }
+
+    /// Returns the finest level which has histograms.
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Returns the number of bins of every histogram.
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Returns the range of values counted by given bin.
+    pub fn bin_range(&self, bin: usize) -> Range<f64> {
+        let width = (self.range.end - self.range.start) / self.bins as f64;
+        let start = self.range.start + width * bin as f64;
+        start..start + width
+    }
+
+    /// Returns the histogram of bucket `index` on given level.
+    /// If the level has no histograms, or the level or the index is out of bounds, returns None
+    pub fn histogram(&self, level: usize, index: usize) -> Option<&[u32]> {
+        let level = self.data.get(level.checked_sub(self.min_level)?)?;
+        level.get(index * self.bins..(index + 1) * self.bins)
+    }
+
+    /// Returns the bin a value falls into. Values outside of the range are clamped to the edge bins.
+    fn bin_of(&self, value: f64) -> usize {
+        let position = (value - self.range.start) / (self.range.end - self.range.start);
+        ((position * self.bins as f64) as usize).min(self.bins - 1)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Computes histograms with `bins` equal bins over `range` for every bucket of the levels starting from `min_level`.
+    /// Values outside of `range` are counted in the edge bins, NaNs are not counted.
+    ///
+    /// # Panics
+    /// Panics if `bins` is zero or `range` is empty, or if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn histograms(&self, range: Range<f64>, bins: usize, min_level: usize) -> HistogramLevels {
+        assert!(bins > 0, "A histogram must have at least one bin");
+        assert!(
+            range.start < range.end,
+            "The range of a histogram must not be empty"
+        );
+
+        let min_level = min_level.min(self.num_levels() - 1);
+        let mut histograms = HistogramLevels {
+            range,
+            bins,
+            min_level,
+            data: vec![],
+        };
+
+        let mut level = vec![0; self.get_level(min_level).unwrap().len() * bins];
+        for (i, value) in self.source().iter().enumerate() {
+            let value = value.to_f64().unwrap();
+            if !value.is_nan() {
+                level[(i >> min_level) * bins + histograms.bin_of(value)] += 1;
+            }
+        }
+        histograms.data.push(level);
+
+        while histograms.num_levels() < self.num_levels() {
+            let current = &histograms.data[histograms.data.len() - 1];
+            let mipmap = current
+                .chunks(2 * bins)
+                .flat_map(|pair| {
+                    (0..bins).map(move |bin| pair.iter().skip(bin).step_by(bins).sum::<u32>())
+                })
+                .collect();
+            histograms.data.push(mipmap);
+        }
+
+        histograms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histograms_add_up() {
+        let source: Vec<f64> = (0..50).map(|x| (x as f64 * 0.37).sin()).collect();
+        let mipmap = MipMap1D::new(source);
+        let histograms = mipmap.histograms(-1.0..1.0, 4, 2);
+
+        assert_eq!(histograms.num_levels(), mipmap.num_levels());
+        assert_eq!(histograms.min_level(), 2);
+        for level in 2..histograms.num_levels() {
+            let len = mipmap.get_level(level).unwrap().len();
+            let total: u32 = (0..len)
+                .map(|index| {
+                    histograms
+                        .histogram(level, index)
+                        .unwrap()
+                        .iter()
+                        .sum::<u32>()
+                })
+                .sum();
+            assert_eq!(total, 50);
+            assert_eq!(histograms.histogram(level, len), None);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_clamped() {
+        let mipmap = MipMap1D::new(vec![-5, 0, 5, 10]);
+        let histograms = mipmap.histograms(0.0..10.0, 2, 0);
+
+        assert_eq!(histograms.histogram(0, 0), Some(&[1, 0][..]));
+        assert_eq!(histograms.histogram(0, 3), Some(&[0, 1][..]));
+        assert_eq!(histograms.bin_range(1), 5.0..10.0);
+    }
+
+    #[test]
+    fn test_nans_are_not_counted() {
+        let mipmap = MipMap1D::new(vec![f64::NAN, 1.0]);
+        let histograms = mipmap.histograms(0.0..2.0, 2, 5);
+
+        assert_eq!(histograms.min_level(), 1);
+        assert_eq!(histograms.histogram(1, 0), Some(&[0, 1][..]));
+    }
+}
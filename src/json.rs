@@ -0,0 +1,152 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, Viewport};
+
+/// Shape of the points in the JSON written by [`MipMap1D::to_json`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonLayout {
+    /// Separate `"x"` and `"y"` arrays, as uPlot expects its data.
+    #[default]
+    Columns,
+    /// A `"data"` array of `[x, y]` pairs, as ECharts expects the data of a series.
+    Pairs,
+}
+
+/// Options of [`MipMap1D::to_json`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JsonExport {
+    pub layout: JsonLayout,
+    /// The x coordinate of source sample `i` is `origin + i * spacing`, e.g. a timestamp.
+    pub origin: f64,
+    pub spacing: f64,
+}
+
+impl Default for JsonExport {
+    fn default() -> Self {
+        Self {
+            layout: JsonLayout::default(),
+            origin: 0.0,
+            spacing: 1.0,
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Queries the viewport and writes the points as a JSON object, e.g. as the response of an HTTP endpoint.
+    /// Besides the points, the object contains the `"level"`, the number of source samples per point as `"bucket"`,
+    /// and the source indices `"start"` and `"end"` covered by the points.
+    /// The x coordinate of a point is the middle of its source samples. NaN and infinite values are written as `null`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{JsonExport, JsonLayout, MipMap1D, Viewport};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+    /// let viewport = Viewport::new(0..5, 3);
+    /// assert_eq!(
+    ///     mipmap.to_json(&viewport, &JsonExport::default()),
+    ///     r#"{"level":1,"bucket":2,"start":0,"end":5,"x":[0.5,2.5,4],"y":[3,7,9]}"#
+    /// );
+    /// let export = JsonExport { layout: JsonLayout::Pairs, origin: 1000.0, spacing: 10.0 };
+    /// assert_eq!(
+    ///     mipmap.to_json(&viewport, &export),
+    ///     r#"{"level":1,"bucket":2,"start":0,"end":5,"data":[[1005,3],[1025,7],[1040,9]]}"#
+    /// );
+    /// ```
+    pub fn to_json(&self, viewport: &Viewport, export: &JsonExport) -> String {
+        let slice = self.query(viewport);
+        let len = self.len();
+        let points = slice.data.iter().enumerate().map(|(index, value)| {
+            let samples = slice.source_range(index);
+            let middle = (samples.start + samples.end.min(len) - 1) as f64 / 2.0;
+            (
+                export.origin + middle * export.spacing,
+                value.to_f64().unwrap(),
+            )
+        });
+        let start = (slice.start << slice.level).min(len);
+        let end = ((slice.start + slice.data.len()) << slice.level).min(len);
+
+        let mut json = String::new();
+        write!(
+            json,
+            r#"{{"level":{},"bucket":{},"start":{start},"end":{end},"#,
+            slice.level,
+            1usize << slice.level
+        )
+        .unwrap();
+        match export.layout {
+            JsonLayout::Columns => {
+                let (xs, ys): (Vec<_>, Vec<_>) = points.unzip();
+                json.push_str(r#""x":"#);
+                write_array(&mut json, &xs);
+                json.push_str(r#","y":"#);
+                write_array(&mut json, &ys);
+            }
+            JsonLayout::Pairs => {
+                json.push_str(r#""data":["#);
+                for (index, (x, y)) in points.enumerate() {
+                    if index > 0 {
+                        json.push(',');
+                    }
+                    write_array(&mut json, &[x, y]);
+                }
+                json.push(']');
+            }
+        }
+        json.push('}');
+        json
+    }
+}
+
+/// Writes numbers as a JSON array.
+fn write_array(json: &mut String, values: &[f64]) {
+    json.push('[');
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        match value.is_finite() {
+            true => write!(json, "{value}").unwrap(),
+            false => json.push_str("null"),
+        }
+    }
+    json.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_of_viewport() {
+        let mipmap = MipMap1D::new((0..64).collect::<Vec<u32>>());
+        let json = mipmap.to_json(&Viewport::new(16..32, 2), &JsonExport::default());
+        assert_eq!(
+            json,
+            r#"{"level":3,"bucket":8,"start":16,"end":32,"x":[19.5,27.5],"y":[19,27]}"#
+        );
+
+        let empty = mipmap.to_json(&Viewport::new(100..200, 2), &JsonExport::default());
+        assert_eq!(
+            empty,
+            r#"{"level":0,"bucket":1,"start":0,"end":0,"x":[],"y":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_non_finite_values() {
+        let mipmap = MipMap1D::new(vec![1.5, f64::NAN, f64::INFINITY]);
+        let export = JsonExport {
+            layout: JsonLayout::Pairs,
+            ..Default::default()
+        };
+        assert_eq!(
+            mipmap.to_json(&Viewport::new(0..3, 3), &export),
+            r#"{"level":0,"bucket":1,"start":0,"end":3,"data":[[0,1.5],[1,null],[2,null]]}"#
+        );
+    }
+}
@@ -1,2 +1,187 @@
+// Without the default `std` feature, the core types only need `alloc`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+// Lets the code generated by `mipmap-1d-derive` refer to this crate from its own tests.
+extern crate self as mipmap_1d;
+
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod arithmetic;
+pub mod atomic;
+pub mod borrowed;
+#[cfg(feature = "std")]
+pub mod bundle;
+#[cfg(feature = "bytes")]
+pub mod bytes;
+#[cfg(feature = "std")]
+pub mod complex;
+pub mod config;
+pub mod converted;
+pub mod counts;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod cumulative;
+pub mod delta;
+#[cfg(feature = "delta-compression")]
+pub mod delta_compressed;
+pub mod derivative;
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod distinct;
+pub mod edit;
+pub mod events;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+#[cfg(feature = "half")]
+pub mod half;
+mod hash;
+pub mod histogram;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod memory;
 pub mod mipmap;
+pub mod mipmap2d;
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
+pub mod multi;
+pub mod nan;
+#[cfg(feature = "std")]
+pub mod normalize;
+#[cfg(feature = "npy")]
+pub mod npy;
+pub mod overflow;
+pub mod overlapping;
+pub mod overview;
+pub mod packed;
+pub mod padding;
+#[cfg(feature = "plot-source")]
+pub mod plot_source;
+pub mod preprocess;
+pub mod quantile;
+pub mod quantized;
+pub mod record;
+pub mod reduced;
+pub mod reduction;
+pub mod resample;
+pub mod rle;
+pub mod rounding;
+#[cfg(feature = "series")]
+pub mod series;
+#[cfg(feature = "std")]
+pub mod set;
+pub mod shared;
+pub mod slice_storage;
+#[cfg(feature = "sparkline")]
+pub mod sparkline;
+#[cfg(feature = "spectrogram")]
+pub mod spectrogram;
+#[cfg(feature = "archive")]
+pub mod spilled;
+pub mod stats;
+pub mod stats_mipmap;
+pub mod storage;
+pub mod strip;
+mod sum;
+#[cfg(feature = "std")]
+pub mod sync;
+#[cfg(feature = "std")]
+pub mod tolerance;
+#[cfg(feature = "trace-hook")]
+pub mod trace;
+pub mod transform;
+#[cfg(feature = "validity")]
+pub mod validity;
+pub mod variance;
+pub mod verify;
+pub mod vertices;
+pub mod view;
+pub mod viewport;
+#[cfg(feature = "wasm-exports")]
+pub mod wasm_exports;
+#[cfg(feature = "wav")]
+pub mod wav;
+pub mod weighted;
+pub mod zip;
+#[cfg(feature = "archive")]
+pub use archive::{ArchiveError, ArchivedMipMap};
+pub use atomic::{AtomicReader, AtomicWriter};
+pub use borrowed::MipMap1DRef;
+#[cfg(feature = "std")]
+pub use bundle::{MipMapBundle, Strategy};
+#[cfg(feature = "bytes")]
+pub use bytes::Pod;
+#[cfg(feature = "std")]
+pub use complex::ComplexMipMap;
+pub use config::MipMapConfig;
+pub use converted::ConvertedMipMap1D;
+pub use counts::BucketCounts;
+pub use cumulative::CumulativeMipMap;
+pub use delta::DeltaLevels;
+#[cfg(feature = "delta-compression")]
+pub use delta_compressed::DeltaCompressedMipMap;
+pub use diff::{LevelDiff, LevelDiffReport};
+#[cfg(feature = "std")]
+pub use distinct::DistinctLevels;
+pub use edit::LevelMut;
+pub use events::EventMipMap;
+pub use histogram::HistogramLevels;
+#[cfg(feature = "json")]
+pub use json::{JsonExport, JsonLayout};
+pub use memory::MemoryUsage;
 pub use mipmap::MipMap1D;
+pub use mipmap2d::MipMap2D;
+#[cfg(all(feature = "mmap", unix))]
+pub use mmap::MappedMipMap;
+pub use multi::MultiMipMap;
+pub use nan::NanPolicy;
+#[cfg(feature = "std")]
+pub use normalize::Normalization;
+#[cfg(feature = "npy")]
+pub use npy::NpyElement;
+pub use overflow::{OverflowError, OverflowPolicy};
+pub use overlapping::OverlappingLevels;
+pub use overview::OverviewMipMap;
+pub use packed::PackedLevels;
+pub use padding::Padding;
+#[cfg(feature = "plot-source")]
+pub use plot_source::PlotSource;
+pub use preprocess::Preprocess;
+pub use quantile::QuantileLevels;
+pub use quantized::QuantizedLevel;
+pub use record::{Downsample, RecordMipMap};
+pub use reduced::ReducedPrecisionMipMap;
+pub use reduction::Reduction;
+pub use rle::{RunLengthMipMap, RunSlice};
+pub use rounding::Rounding;
+#[cfg(feature = "std")]
+pub use set::MipMapSet;
+pub use shared::SharedLevel;
+pub use slice_storage::SliceStorage;
+#[cfg(feature = "spectrogram")]
+pub use spectrogram::SpectrogramLevels;
+#[cfg(feature = "archive")]
+pub use spilled::{SpilledMipMap, SpilledSlice};
+pub use stats::LevelStats;
+pub use stats_mipmap::{BucketStats, StatsMipMap1D};
+pub use storage::{LevelStorage, VecStorage};
+pub use strip::StripChart;
+#[cfg(feature = "std")]
+pub use sync::SharedMipMap;
+pub use transform::Transform;
+#[cfg(feature = "validity")]
+pub use validity::ValidityLevel;
+pub use variance::VarianceLevels;
+pub use verify::VerifyError;
+pub use vertices::{EnvelopeVertices, VertexLayout};
+pub use view::MipMapView;
+pub use viewport::{LevelSlice, Refinement, Viewport};
+pub use weighted::WeightedMipMap1D;
+pub use zip::ZippedMipMap;
+
+#[cfg(feature = "derive")]
+pub use mipmap_1d_derive::Downsample;
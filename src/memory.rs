@@ -0,0 +1,122 @@
+use alloc::vec::Vec;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, NanPolicy, Rounding};
+
+/// Memory used by a mipmap, as reported by [`MipMap1D::memory_usage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Bytes taken by the elements of every level, and by their sample counts with [`NanPolicy::Missing`].
+    /// Pruned levels take no bytes.
+    pub levels: Vec<usize>,
+    /// Bytes taken by the whole structure, including spare capacity and bookkeeping.
+    /// A buffer shared with clones is counted in full.
+    pub total: usize,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Same as [`MipMap1D::new`], but coarser levels are only generated while the elements
+    /// of all levels fit into `bytes`, so the mipmap may not reach a single-element level.
+    /// Level `0` is always stored, and the number of levels is not increased by [`MipMap1D::push`] later on.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let data: Vec<u32> = (0..1000).collect();
+    /// let mipmap = MipMap1D::with_memory_budget(data, 7000);
+    /// assert_eq!(mipmap.num_levels(), 3);
+    /// assert!(mipmap.memory_usage().levels.iter().sum::<usize>() <= 7000);
+    /// ```
+    pub fn with_memory_budget(source: Vec<T>, bytes: usize) -> Self {
+        let mut len = source.len();
+        let (mut used, mut max_levels) = (len * size_of::<T>(), 1);
+        while len > 1 {
+            len = len.div_ceil(2);
+            used += len * size_of::<T>();
+            if used > bytes {
+                break;
+            }
+            max_levels += 1;
+        }
+
+        Self::build_limited(
+            source,
+            NanPolicy::Propagate,
+            Rounding::default(),
+            max_levels,
+        )
+    }
+
+    /// Returns the number of bytes used by every level and by the whole mipmap.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![2u16, 4, 6, 8, 9]);
+    /// let usage = mipmap.memory_usage();
+    /// assert_eq!(usage.levels, [10, 6, 4, 2]);
+    /// assert!(usage.total > 22);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryUsage {
+        let per_element = size_of::<T>()
+            + match self.nan_policy() {
+                NanPolicy::Missing => size_of::<usize>(),
+                _ => 0,
+            };
+        let levels = (0..self.num_levels())
+            .map(|level| self.get_level(level).map_or(0, <[T]>::len) * per_element)
+            .collect();
+
+        MemoryUsage {
+            levels,
+            total: size_of::<Self>() + self.heap_size(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_limits_levels() {
+        let data: Vec<f64> = (0..100).map(f64::from).collect();
+        let full = MipMap1D::new(data.clone());
+
+        for bytes in [0, 800, 1200, 1400, 100_000] {
+            let limited = MipMap1D::with_memory_budget(data.clone(), bytes);
+            let usage = limited.memory_usage();
+            assert!(usage.levels.iter().sum::<usize>() <= bytes.max(800));
+            assert_eq!(limited.verify(), Ok(()));
+            for level in 0..limited.num_levels() {
+                assert_eq!(limited.get_level(level), full.get_level(level));
+            }
+        }
+        assert_eq!(
+            MipMap1D::with_memory_budget(data.clone(), 0).num_levels(),
+            1
+        );
+        assert_eq!(MipMap1D::with_memory_budget(data, 1200).num_levels(), 2);
+    }
+
+    #[test]
+    fn test_push_respects_budget() {
+        let mut mipmap = MipMap1D::with_memory_budget(vec![1, 2, 3, 4], 24);
+        assert_eq!(mipmap.num_levels(), 2);
+        for x in 5..20 {
+            mipmap.push(x);
+        }
+
+        assert_eq!(mipmap.num_levels(), 2);
+        assert_eq!(mipmap.get_level(1).unwrap().len(), 10);
+        assert_eq!(mipmap.verify(), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_counts_are_included() {
+        let mipmap = MipMap1D::with_nan_policy(vec![1.0f32, f32::NAN], NanPolicy::Missing);
+        assert_eq!(mipmap.memory_usage().levels, [24, 12]);
+    }
+}
@@ -1,11 +1,26 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use num_traits::Float;
 use num_traits::{FromPrimitive, Num, ToPrimitive};
 
+use crate::hash::LevelHash;
+use crate::stats::{compare, StatsSum};
+use crate::storage::{LevelStorage, VecStorage};
+use crate::sum::CompensatedSum;
+use crate::{LevelStats, MipMapConfig, NanPolicy, Reduction, Rounding, SharedLevel, Transform};
+
 /// Creates several downsampled versions of given vector.
 /// This data structure takes 2x space of original data.
+/// All levels are stored in a single contiguous buffer, see [`MipMap1D::buffer`].
+/// The buffer is shared between clones until one of them is modified, so cloning does not copy the levels.
+/// Values only need to be `Clone`, so arbitrary-precision numbers implementing the num-traits traits can be used too.
 /// Example:
 /// ```rust
 /// use mipmap_1d::MipMap1D;
-/// 
+///
 /// let data = vec![2, 4, 6, 8, 9];
 /// let mipmap = MipMap1D::new(data);
 /// assert_eq!(mipmap.num_levels(), 4);
@@ -15,55 +30,912 @@ use num_traits::{FromPrimitive, Num, ToPrimitive};
 /// assert_eq!(*mipmap.get_level(3).unwrap(), [7]);
 /// assert_eq!(mipmap.get_level(4), None);
 /// ```
-pub struct MipMap1D<T: Num + ToPrimitive + FromPrimitive> {
-    data: Vec<Vec<T>>,
+#[derive(Clone)]
+pub struct MipMap1D<T: Num + ToPrimitive + FromPrimitive, S = VecStorage<T>> {
+    data: S,
+    nan_policy: NanPolicy,
+    rounding: Rounding,
+    reduction: Reduction,
+    /// Number of valid samples below every element, only tracked with [`NanPolicy::Missing`].
+    counts: VecStorage<usize>,
+    /// Statistics of every level without its last element, which is the only one changed by [`MipMap1D::push`].
+    stats: Vec<Option<StatsSum<T>>>,
+    /// Hashes of every level without its last element, maintained like `stats`.
+    hashes: Vec<LevelHash>,
+    /// No levels are added past this number, see [`MipMap1D::with_memory_budget`].
+    max_levels: usize,
+    /// Levels below this one were dropped by [`MipMap1D::prune_below`].
+    min_level: usize,
 }
 
-impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
-    pub fn new(source: Vec<T>) -> Self {
-        let mut data = vec![source.clone()];
-        let mut current = source;
+impl<T: Num + ToPrimitive + FromPrimitive + Clone, S: LevelStorage<T>> MipMap1D<T, S> {
+    /// Same as [`MipMap1D::new`], but the levels are stored in `storage`, which must not have any levels yet.
+    ///
+    /// # Panics
+    /// Panics if `storage` is not empty.
+    pub fn with_storage(source: Vec<T>, storage: S) -> Self {
+        Self::build_in(storage, source, MipMapConfig::default())
+    }
+
+    /// Constructs levels in `storage` with given configuration.
+    pub(crate) fn build_in(mut storage: S, source: Vec<T>, mut config: MipMapConfig) -> Self {
+        config.nan_policy = nan_policy_of::<T>(config.nan_policy);
+        let counts = match config.nan_policy {
+            NanPolicy::Missing => source.iter().map(|x| usize::from(!is_nan(x))).collect(),
+            _ => vec![],
+        };
+        assert_eq!(storage.num_levels(), 0, "The storage must be empty");
+        storage.push_level(source);
+        let mut mipmap = Self {
+            data: storage,
+            nan_policy: config.nan_policy,
+            rounding: config.rounding,
+            reduction: config.reduction,
+            counts: VecStorage::new(counts),
+            stats: vec![],
+            hashes: vec![],
+            max_levels: config.max_levels.unwrap_or(usize::MAX).max(1),
+            min_level: 0,
+        };
+
+        mipmap.build_levels();
+        mipmap
+    }
+
+    /// Regenerates all levels above level `0` combining buckets with `reduction`, e.g. to switch between a mean
+    /// and a min/max view of the same data. Level `0` is kept in place.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Reduction};
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// mipmap.rebuild_levels_with(Reduction::Max);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [4, 8, 9]);
+    /// assert_eq!(*mipmap.get_level(3).unwrap(), [9]);
+    /// mipmap.rebuild_levels_with(Reduction::Mean);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 7, 9]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn rebuild_levels_with(&mut self, reduction: Reduction) {
+        assert_eq!(
+            self.min_level, 0,
+            "Can not rebuild after the source was pruned"
+        );
+        self.reduction = reduction;
+        self.data.truncate(1);
+        self.counts.truncate(1);
+        self.build_levels();
+    }
+
+    /// Applies `f` to every element of every level, e.g. a new calibration of the samples.
+    /// If `f` commutes with the reduction according to `transform`, the levels are mapped directly,
+    /// otherwise only the source is mapped and the levels above it are rebuilt.
+    /// Integer means mapped directly may differ by the rounding from rebuilt ones.
+    /// `f` must keep NaNs as NaN and other values as numbers, or the statistics of [`NanPolicy::Missing`] break.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Transform};
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+    /// mipmap.map_in_place(Transform::Affine, |x| 10.0 * x + 1.0);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [31.0, 71.0, 91.0]);
+    /// mipmap.map_in_place(Transform::Arbitrary, |x| x * x);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [1061.0, 5141.0, 8281.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the levels must be rebuilt and level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn map_in_place(&mut self, transform: Transform, f: impl Fn(T) -> T) {
+        let direct = transform.commutes_with(self.reduction);
+        assert!(
+            direct || self.min_level == 0,
+            "Can not rebuild after the source was pruned"
+        );
+        let levels = match direct {
+            true => self.min_level..self.num_levels(),
+            false => 0..1,
+        };
+        for level in levels {
+            for x in self.data.level_mut(level).unwrap() {
+                *x = f(x.clone());
+            }
+        }
+
+        match direct {
+            true => self.compute_stats(),
+            false => {
+                self.data.truncate(1);
+                self.counts.truncate(1);
+                self.build_levels();
+            }
+        }
+    }
+
+    /// Same as [`MipMap1D::map_in_place`], but the mapped levels are returned as a new mipmap
+    /// of another type, e.g. `i16` samples converted to volts as `f32`.
+    /// The new mipmap keeps the configuration of this one.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Transform};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2i16, 4, 6, 8, 9]);
+    /// let volts = mipmap.map(Transform::Affine, |&x| f32::from(x) / 1000.0);
+    /// assert_eq!(*volts.get_level(2).unwrap(), [0.005, 0.009]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the levels must be rebuilt and level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn map<U: Num + ToPrimitive + FromPrimitive + Clone>(
+        &self,
+        transform: Transform,
+        f: impl Fn(&T) -> U,
+    ) -> MipMap1D<U> {
+        if !transform.commutes_with(self.reduction) {
+            assert_eq!(
+                self.min_level, 0,
+                "Can not rebuild after the source was pruned"
+            );
+            return MipMap1D::from_config(self.level(0).iter().map(f).collect(), self.config());
+        }
+
+        let mut data = VecStorage::default();
+        for level in 0..self.num_levels() {
+            data.push_level(self.level(level).iter().map(&f).collect());
+        }
+        self.with_levels(data)
+    }
+
+    /// Creates a mipmap with the configuration, sample counts and pruned levels of this one, but other levels
+    /// computed from its levels, e.g. mapped element by element.
+    pub(crate) fn with_levels<U: Num + ToPrimitive + FromPrimitive + Clone>(
+        &self,
+        data: VecStorage<U>,
+    ) -> MipMap1D<U> {
+        let mut mipmap = MipMap1D {
+            data,
+            nan_policy: self.nan_policy,
+            rounding: self.rounding,
+            reduction: self.reduction,
+            counts: self.counts.clone(),
+            stats: vec![],
+            hashes: vec![],
+            max_levels: self.max_levels,
+            min_level: self.min_level,
+        };
+        mipmap.compute_stats();
+        mipmap
+    }
+
+    /// Gives mutable access to the elements of given level, see [`MipMap1D::level_mut`].
+    /// If the level is out of bounds or was pruned, returns None
+    pub(crate) fn level_data_mut(&mut self, level: usize) -> Option<&mut [T]> {
+        if level < self.min_level {
+            return None;
+        }
+        self.data.level_mut(level)
+    }
+
+    /// Recomputes all levels coarser than `level` from it, together with the statistics and hashes,
+    /// e.g. after the level was edited. The sample counts of an edited source are recomputed as well.
+    pub(crate) fn recompute_above(&mut self, level: usize) {
+        if self.nan_policy == NanPolicy::Missing && level == 0 {
+            let counts: Vec<_> = self
+                .level(0)
+                .iter()
+                .map(|x| usize::from(!is_nan(x)))
+                .collect();
+            self.counts[0].copy_from_slice(&counts);
+        }
+        for level in level + 1..self.num_levels() {
+            for index in 0..self.level(level).len() {
+                let (value, count) = self.reduce(level - 1, index);
+                self.data.level_mut(level).unwrap()[index] = value;
+                if self.nan_policy == NanPolicy::Missing {
+                    self.counts[level][index] = count;
+                }
+            }
+        }
+        self.compute_stats();
+    }
+
+    /// Generates the levels above the existing ones until a single element is left, and computes their statistics.
+    fn build_levels(&mut self) {
+        #[cfg(feature = "trace-hook")]
+        let start = std::time::Instant::now();
+
+        while !self.is_complete() {
+            #[cfg(feature = "trace-hook")]
+            let level_start = std::time::Instant::now();
+
+            let level = self.num_levels() - 1;
+            let len = self.level(level).len().div_ceil(2);
+            let (data, counts) = match (self.reduction, self.nan_policy) {
+                (Reduction::Mean, NanPolicy::Propagate) => {
+                    (mean_level(self.level(level), self.rounding), vec![])
+                }
+                _ => (0..len).map(|index| self.reduce(level, index)).unzip(),
+            };
+            self.data.push_level(data);
+            if self.nan_policy == NanPolicy::Missing {
+                self.counts.push_level(counts);
+            }
+
+            #[cfg(feature = "trace-hook")]
+            crate::trace::emit(|| crate::trace::TraceEvent::LevelBuilt {
+                level: level + 1,
+                len,
+                elapsed: level_start.elapsed(),
+            });
+        }
+
+        self.compute_stats();
+
+        #[cfg(feature = "trace-hook")]
+        crate::trace::emit(|| crate::trace::TraceEvent::Built {
+            source_len: self.level(0).len(),
+            levels: self.num_levels(),
+            elapsed: start.elapsed(),
+        });
+    }
+
+    /// Recomputes the statistics and hashes of all but the last element of every level.
+    fn compute_stats(&mut self) {
+        self.stats = (0..self.num_levels())
+            .map(|level| {
+                let level = self.level(level);
+                StatsSum::of(level.iter().take(level.len().saturating_sub(1)))
+            })
+            .collect();
+        self.hashes = (0..self.num_levels())
+            .map(|level| {
+                let level = self.level(level);
+                level[..level.len().saturating_sub(1)]
+                    .iter()
+                    .fold(LevelHash::new(), LevelHash::with)
+            })
+            .collect();
+    }
+
+    /// Appends a sample to the source data, updating the last bucket of every level
+    /// and adding a new level when needed.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8]);
+    /// mipmap.push(9);
+    /// assert_eq!(mipmap.num_levels(), 4);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 7, 9]);
+    /// assert_eq!(*mipmap.get_level(3).unwrap(), [7]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn push(&mut self, value: T) {
+        assert_eq!(
+            self.min_level, 0,
+            "Can not push after the source was pruned"
+        );
+        let len = self.level(0).len();
+        let count = usize::from(!is_nan(&value));
+        self.set_or_push(0, len, value, count);
+
+        let mut level = 0;
+        while self.level(level).len() > 1 && level + 1 < self.max_levels {
+            let index = (self.level(level).len() - 1) / 2;
+            let (value, count) = self.reduce(level, index);
+
+            if level + 1 == self.num_levels() {
+                self.data.push_level(vec![]);
+                self.stats.push(None);
+                self.hashes.push(LevelHash::new());
+                if self.nan_policy == NanPolicy::Missing {
+                    self.counts.push_level(vec![]);
+                }
+            }
+            self.set_or_push(level + 1, index, value, count);
 
-        while current.len() > 1 {
-            let mipmap = Self::downsample(&current);
-            current.clone_from(&mipmap);
-            data.push(mipmap);
+            level += 1;
         }
 
-        Self { data }
+        #[cfg(feature = "trace-hook")]
+        crate::trace::emit(|| crate::trace::TraceEvent::Pushed { levels: level + 1 });
+    }
+
+    /// Overwrites the element at `index` of given level, or appends it if `index` is right past the end.
+    /// Once a new element is appended, the previous last one is final and is added to the statistics and hashes.
+    fn set_or_push(&mut self, level: usize, index: usize, value: T, count: usize) {
+        let data = self.data.level_mut(level).unwrap();
+        if index < data.len() {
+            data[index] = value;
+        } else {
+            if let Some(last) = data.last().cloned() {
+                self.hashes[level] = self.hashes[level].with(&last);
+                self.stats[level] = StatsSum::with(self.stats[level].take(), last);
+            }
+            self.data.push(level, value);
+        }
+
+        if self.nan_policy == NanPolicy::Missing {
+            if index < self.counts[level].len() {
+                self.counts[level][index] = count;
+            } else {
+                self.counts.push(level, count);
+            }
+        }
     }
 
     /// Returns the total number of downsampled levels.
     /// Equal to `ceil(log2(source.len())`
     pub fn num_levels(&self) -> usize {
-        self.data.len()
+        self.data.num_levels()
+    }
+
+    /// Returns the number of source samples.
+    /// After [`MipMap1D::prune_below`], it is rounded up to whole buckets of the finest remaining level.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// assert_eq!(mipmap.len(), 5);
+    /// mipmap.prune_below(1);
+    /// assert_eq!(mipmap.len(), 6);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.level(self.min_level).len() << self.min_level
+    }
+
+    /// Returns true if there are no source samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of elements on given level.
+    /// If the level is out of bounds or was pruned, returns None
+    pub fn level_len(&self, level: usize) -> Option<usize> {
+        self.get_level(level).map(<[T]>::len)
+    }
+
+    /// Returns the number of source samples summarized by every element of given level, i.e. `2^level`.
+    /// The last element may summarize fewer samples.
+    /// If the level is out of bounds, returns None
+    pub fn scale_of(&self, level: usize) -> Option<usize> {
+        (level < self.num_levels()).then(|| 1 << level)
     }
 
     /// Returns the data on given level.
     /// Level `0` returns the source data; the higher the level, the higher the compression (i.e. smaller vectors are returned).
+    /// If the level is out of bounds or was pruned, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[T]> {
+        if level < self.min_level {
+            return None;
+        }
+        self.data.level(level)
+    }
+
+    /// Returns the finest level which was not dropped by [`MipMap1D::prune_below`].
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Returns the source data for methods which need every sample.
+    pub(crate) fn source(&self) -> &[T] {
+        self.get_level(0).expect("The source was pruned")
+    }
+
+    /// Returns given level, which must be in bounds.
+    fn level(&self, level: usize) -> &[T] {
+        self.data.level(level).expect("The level is out of bounds")
+    }
+
+    /// Returns the minimum, maximum, mean and count of the elements on given level, ignoring NaNs.
+    /// The statistics are maintained during construction and [`MipMap1D::push`], so this does not scan the level.
+    /// If the level is out of bounds or has no valid elements, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// let stats = mipmap.level_stats(1).unwrap();
+    /// assert_eq!((stats.min, stats.max, stats.count), (3, 9, 3));
+    /// assert_eq!(stats.mean, 19.0 / 3.0);
+    /// ```
+    pub fn level_stats(&self, level: usize) -> Option<LevelStats<T>> {
+        let last = self.get_level(level)?.last()?.clone();
+        StatsSum::with(self.stats[level].clone(), last).map(|stats| stats.stats())
+    }
+
+    /// Returns a hash of the elements on given level, e.g. to find out which levels a cache has to upload again.
+    /// Like the statistics, the hashes are maintained during construction and [`MipMap1D::push`],
+    /// so this does not scan the level. Equal levels have equal hashes in every build of the crate.
+    /// If the level is out of bounds or was pruned, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8]);
+    /// let before: Vec<_> = (0..3).map(|level| mipmap.level_hash(level)).collect();
+    /// mipmap.push(9);
+    /// // Every level gained an element.
+    /// assert!((0..3).all(|level| mipmap.level_hash(level) != before[level]));
+    /// assert_eq!(mipmap.level_hash(3), MipMap1D::new(vec![7]).level_hash(0));
+    /// ```
+    pub fn level_hash(&self, level: usize) -> Option<u64> {
+        let data = self.get_level(level)?;
+        let hash = self.hashes[level];
+        Some(data.last().map_or(hash, |last| hash.with(last)).value())
+    }
+
+    /// Returns a hash of all levels which were not pruned and of the configuration,
+    /// which changes whenever any level changes.
+    pub fn digest(&self) -> u64 {
+        let config = self.config();
+        let mut hash = LevelHash::new()
+            .write(&[
+                config.nan_policy as u8,
+                config.rounding as u8,
+                config.reduction as u8,
+            ])
+            .write(&(self.min_level as u64).to_le_bytes());
+        for level in self.min_level..self.num_levels() {
+            hash = hash.write(&self.level_hash(level).unwrap().to_le_bytes());
+        }
+        hash.value()
+    }
+
+    /// Returns the finest level which has at most `max_points` elements.
+    /// If even the coarsest level is larger, returns the coarsest level.
+    pub fn level_within(&self, max_points: usize) -> usize {
+        (self.min_level..self.num_levels())
+            .find(|&level| self.level(level).len() <= max_points)
+            .unwrap_or(self.num_levels() - 1)
+    }
+
+    /// Returns true if no more levels can be added, because the coarsest one has a single element
+    /// or the limit of [`MipMap1D::with_memory_budget`] is reached.
+    pub(crate) fn is_complete(&self) -> bool {
+        let levels = self.num_levels();
+        self.level(levels - 1).len() <= 1 || levels >= self.max_levels
+    }
+
+    /// Returns the policy used for NaN values.
+    pub fn nan_policy(&self) -> NanPolicy {
+        self.nan_policy
+    }
+
+    /// Returns the rounding mode of integer means.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Returns how buckets are combined, see [`MipMap1D::rebuild_levels_with`].
+    pub fn reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    /// Returns the configuration the levels are built with.
+    pub fn config(&self) -> MipMapConfig {
+        MipMapConfig {
+            nan_policy: self.nan_policy,
+            rounding: self.rounding,
+            reduction: self.reduction,
+            max_levels: (self.max_levels != usize::MAX).then_some(self.max_levels),
+        }
+    }
+
+    /// Combines the bucket `index` of given level into one element of the next level.
+    /// Returns it together with its number of valid samples, which is only tracked with [`NanPolicy::Missing`].
+    pub(crate) fn reduce(&self, level: usize, index: usize) -> (T, usize) {
+        let range = 2 * index..(2 * index + 2).min(self.level(level).len());
+        let bucket = &self.level(level)[range.clone()];
+
+        if self.reduction != Reduction::Mean {
+            let propagate = self.nan_policy == NanPolicy::Propagate;
+            let count = match self.nan_policy {
+                NanPolicy::Missing => self.counts[level][range].iter().sum(),
+                _ => 0,
+            };
+            let value = match self.reduction {
+                Reduction::Min => extreme(bucket, Ordering::Less, propagate),
+                Reduction::Max => extreme(bucket, Ordering::Greater, propagate),
+                Reduction::First if !propagate => bucket.iter().find(|x| !is_nan(*x)),
+                Reduction::Last if !propagate => bucket.iter().rev().find(|x| !is_nan(*x)),
+                Reduction::Last => bucket.last(),
+                _ => bucket.first(),
+            };
+            return (value.unwrap_or(&bucket[0]).clone(), count);
+        }
+
+        match self.nan_policy {
+            NanPolicy::Propagate => match bucket {
+                [a, b] => (midpoint(a.clone(), b.clone(), self.rounding), 0),
+                _ => (bucket[0].clone(), 0),
+            },
+            NanPolicy::Skip => (MipMap1D::<T>::skip_nan_average(bucket), 0),
+            NanPolicy::Missing => MipMap1D::<T>::weighted_mean(bucket, &self.counts[level][range]),
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    pub fn new(source: Vec<T>) -> Self {
+        Self::build(source, NanPolicy::Propagate, Rounding::default())
+    }
+
+    /// Same as [`MipMap1D::new`], but integer means are rounded according to `rounding`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Rounding};
+    ///
+    /// let data = vec![1, 2, -4, -3, 3, 4];
+    /// let floor = MipMap1D::with_rounding(data.clone(), Rounding::Floor);
+    /// assert_eq!(*floor.get_level(1).unwrap(), [1, -4, 3]);
+    /// let half_even = MipMap1D::with_rounding(data, Rounding::HalfEven);
+    /// assert_eq!(*half_even.get_level(1).unwrap(), [2, -4, 4]);
+    /// ```
+    pub fn with_rounding(source: Vec<T>, rounding: Rounding) -> Self {
+        Self::build(source, NanPolicy::Propagate, rounding)
+    }
+
+    /// Constructs levels by repeatedly downsampling the last one until a single element is left.
+    pub(crate) fn build(source: Vec<T>, nan_policy: NanPolicy, rounding: Rounding) -> Self {
+        Self::build_limited(source, nan_policy, rounding, usize::MAX)
+    }
+
+    /// Same as [`MipMap1D::build`], but stops after `max_levels` levels.
+    pub(crate) fn build_limited(
+        source: Vec<T>,
+        nan_policy: NanPolicy,
+        rounding: Rounding,
+        max_levels: usize,
+    ) -> Self {
+        let config = MipMapConfig {
+            nan_policy,
+            rounding,
+            max_levels: Some(max_levels),
+            ..Default::default()
+        };
+        Self::build_in(VecStorage::default(), source, config)
+    }
+
+    /// Returns the buffer holding all levels, which can be exported without copying.
+    /// The buffer may also contain spare capacity left by [`MipMap1D::push`],
+    /// so levels should be located with [`MipMap1D::level_range`].
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// assert_eq!(mipmap.buffer(), [2, 4, 6, 8, 9, 3, 7, 9, 5, 9, 7]);
+    /// assert_eq!(mipmap.level_range(1), Some(5..8));
+    /// ```
+    pub fn buffer(&self) -> &[T] {
+        self.data.buffer()
+    }
+
+    /// Returns a handle to given level, which keeps it alive without borrowing the mipmap,
+    /// e.g. to hand it to a render thread.
+    /// Cloning the handle or the mipmap does not copy the level.
     /// If the level is out of bounds, returns None
-    pub fn get_level(&self, level: usize) -> Option<&Vec<T>> {
-        if level >= self.num_levels() {
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8]);
+    /// let level = mipmap.level_arc(1).unwrap();
+    /// mipmap.push(9);
+    /// assert_eq!(*level, [3, 7]);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 7, 9]);
+    /// ```
+    pub fn level_arc(&self, level: usize) -> Option<SharedLevel<T>> {
+        let range = self.data.range(level)?;
+        Some(SharedLevel::new(self.data.share(), range))
+    }
+
+    /// Returns the range of given level in [`MipMap1D::buffer`].
+    /// If the level is out of bounds, returns None
+    pub fn level_range(&self, level: usize) -> Option<Range<usize>> {
+        self.data.range(level)
+    }
+
+    /// Drops all levels coarser than `level`, freeing their memory.
+    /// Coarser levels are not generated again by [`MipMap1D::push`].
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new((0..1000).collect::<Vec<u32>>());
+    /// mipmap.prune_above(mipmap.level_within(1000 / 4));
+    /// assert_eq!(mipmap.num_levels(), 3);
+    /// mipmap.push(1000);
+    /// assert_eq!(mipmap.num_levels(), 3);
+    /// ```
+    pub fn prune_above(&mut self, level: usize) {
+        let levels = (level + 1).max(self.min_level + 1);
+        self.data.truncate(levels);
+        self.data.shrink_to_fit();
+        self.counts.truncate(levels);
+        self.stats.truncate(levels);
+        self.stats.shrink_to_fit();
+        self.hashes.truncate(levels);
+        self.hashes.shrink_to_fit();
+        self.max_levels = self.max_levels.min(levels);
+    }
+
+    /// Drops all levels finer than `level`, freeing their memory, e.g. the source after it has been exported.
+    /// The coarsest level is always kept. Pruned levels are returned as None by [`MipMap1D::get_level`],
+    /// and methods which read the source, such as [`MipMap1D::push`] or [`MipMap1D::cumulative_sum`], panic afterwards.
+    /// Queries and views use the finest remaining level instead.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// mipmap.prune_below(2);
+    /// assert_eq!(mipmap.min_level(), 2);
+    /// assert_eq!(mipmap.get_level(1), None);
+    /// assert_eq!(*mipmap.get_level(2).unwrap(), [5, 9]);
+    /// ```
+    pub fn prune_below(&mut self, level: usize) {
+        let level = level.min(self.num_levels() - 1).max(self.min_level);
+        self.data.clear_below(level);
+        if self.nan_policy == NanPolicy::Missing {
+            self.counts.clear_below(level);
+        }
+        self.min_level = level;
+    }
+
+    /// Assembles a mipmap from levels computed earlier with given configuration, e.g. loaded from a file,
+    /// without downsampling again. Levels below `min_level` were pruned and are ignored.
+    /// Returns None if the lengths of the levels do not fit together, or if the sample counts of
+    /// [`NanPolicy::Missing`] can not be restored because the source was pruned.
+    #[cfg(feature = "archive")]
+    pub(crate) fn from_levels(
+        levels: Vec<Vec<T>>,
+        mut config: MipMapConfig,
+        min_level: usize,
+    ) -> Option<Self> {
+        config.nan_policy = nan_policy_of::<T>(config.nan_policy);
+        let consistent = levels
+            .get(min_level..)?
+            .windows(2)
+            .all(|pair| pair[1].len() == pair[0].len().div_ceil(2));
+        let missing = config.nan_policy == NanPolicy::Missing;
+        if !consistent || (missing && min_level > 0) {
             return None;
         }
 
-        Some(&self.data[level])
+        let mut counts = VecStorage::default();
+        if missing {
+            let mut level: Vec<usize> = levels[0].iter().map(|x| usize::from(!is_nan(x))).collect();
+            for _ in 1..levels.len() {
+                let next = level.chunks(2).map(|pair| pair.iter().sum()).collect();
+                counts.push_level(core::mem::replace(&mut level, next));
+            }
+            counts.push_level(level);
+        } else {
+            counts.push_level(vec![]);
+        }
+
+        let mut data = VecStorage::default();
+        for level in levels {
+            data.push_level(level);
+        }
+        let mut mipmap = Self {
+            data,
+            nan_policy: config.nan_policy,
+            rounding: config.rounding,
+            reduction: config.reduction,
+            counts,
+            stats: vec![],
+            hashes: vec![],
+            max_levels: config.max_levels.unwrap_or(usize::MAX).max(1),
+            min_level,
+        };
+        mipmap.compute_stats();
+        Some(mipmap)
+    }
+
+    /// Returns the number of bytes allocated on the heap by the levels and their bookkeeping,
+    /// see [`MipMap1D::memory_usage`] for a breakdown.
+    pub fn heap_size(&self) -> usize {
+        self.data.heap_size()
+            + self.counts.heap_size()
+            + self.stats.capacity() * size_of::<Option<StatsSum<T>>>()
+            + self.hashes.capacity() * size_of::<LevelHash>()
     }
 
     /// Downsamples a vector to `ceil(len / 2)`` elements.
     /// Currently, downsampling is done by averaging the pair of elements
+    #[cfg(test)]
     fn downsample(source: &[T]) -> Vec<T> {
-        source
-            .chunks(2)
-            .map(|pair| match pair.len() {
-                1 => pair[0],
-                2 => T::from_f64((pair[0] + pair[1]).to_f64().unwrap() / 2.0).unwrap(),
-                _ => panic!("Unsound condition"),
-            })
-            .collect()
+        source.chunks(2).map(Self::average).collect()
+    }
+
+    /// Gives tests of other modules mutable access to the levels, e.g. to break their consistency.
+    #[cfg(test)]
+    pub(crate) fn data_mut(&mut self) -> &mut VecStorage<T> {
+        &mut self.data
+    }
+
+    /// Averages a bucket of one or two elements.
+    /// Integer means are exact and rounded toward zero.
+    pub(crate) fn average(pair: &[T]) -> T {
+        match pair.len() {
+            1 => pair[0].clone(),
+            2 => midpoint(pair[0].clone(), pair[1].clone(), Rounding::TowardZero),
+            _ => panic!("Unsound condition"),
+        }
+    }
+
+    /// Averages a bucket of one or two elements, ignoring NaNs.
+    pub(crate) fn skip_nan_average(pair: &[T]) -> T {
+        match pair {
+            [a, b] if is_nan(a) => b.clone(),
+            [a, b] if is_nan(b) => a.clone(),
+            _ => Self::average(pair),
+        }
+    }
+
+    /// Averages the values proportionally to their number of valid samples.
+    /// Returns the mean together with the total number of valid samples.
+    /// A bucket without valid samples keeps its first (NaN) value.
+    pub(crate) fn weighted_mean(values: &[T], weights: &[usize]) -> (T, usize) {
+        let total: usize = weights.iter().sum();
+        if total == 0 {
+            return (values[0].clone(), 0);
+        }
+
+        let sum: CompensatedSum<T> = values
+            .iter()
+            .zip(weights)
+            .filter(|(_, &weight)| weight > 0)
+            .map(|(value, &weight)| value.clone() * T::from_usize(weight).unwrap())
+            .collect();
+
+        (sum.value() / T::from_usize(total).unwrap(), total)
     }
 }
 
+// `Float` is only available with the standard library.
+#[cfg(feature = "std")]
+impl<T: Float + FromPrimitive> MipMap1D<T> {
+    /// Same as [`MipMap1D::new`], but NaN values are handled according to `policy`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, NanPolicy};
+    ///
+    /// let data = vec![2.0, f64::NAN, 6.0, 8.0];
+    /// let mipmap = MipMap1D::with_nan_policy(data, NanPolicy::Skip);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [2.0, 7.0]);
+    /// assert_eq!(*mipmap.get_level(2).unwrap(), [4.5]);
+    /// ```
+    pub fn with_nan_policy(source: Vec<T>, policy: NanPolicy) -> Self {
+        Self::build(source, policy, Rounding::default())
+    }
+}
+
+/// Appends every sample with [`MipMap1D::push`].
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> Extend<T> for MipMap1D<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, samples: I) {
+        for sample in samples {
+            self.push(sample);
+        }
+    }
+}
+
+/// Averages the pairs of a level like [`midpoint`], without the bookkeeping of [`MipMap1D::reduce`],
+/// so the loop is compiled into a kernel for every sample type.
+fn mean_level<T: Num + ToPrimitive + FromPrimitive + Clone>(
+    below: &[T],
+    rounding: Rounding,
+) -> Vec<T> {
+    below
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => midpoint(a.clone(), b.clone(), rounding),
+            _ => pair[0].clone(),
+        })
+        .collect()
+}
+
+/// Computes the mean of two values without going through floating point.
+/// Integers which fit into i64 (all primitive ones but 128-bit) are summed in i128, which can not overflow.
+/// Other integers are halved first and added together with the halves of the remainders.
+/// Integer means are rounded according to `rounding`.
+pub(crate) fn midpoint<T: Num + ToPrimitive + FromPrimitive + Clone>(
+    a: T,
+    b: T,
+    rounding: Rounding,
+) -> T {
+    let two = || T::one() + T::one();
+    if !is_integral::<T>() {
+        return (a + b) / two();
+    }
+
+    if let (Some(x), Some(y)) = (a.to_i64(), b.to_i64()) {
+        let sum = i128::from(x) + i128::from(y);
+        let floor = sum >> 1;
+        let mean = if sum & 1 == 1 && rounds_up(rounding, floor < 0, floor & 1 == 1) {
+            floor + 1
+        } else {
+            floor
+        };
+        if let Some(mean) = T::from_i128(mean) {
+            return mean;
+        }
+    }
+
+    let remainder = a.clone() % two() + b.clone() % two();
+    let mean = a / two() + b / two() + remainder.clone() / two();
+
+    // The exact mean is `mean + odd / 2`, where `odd` is -1, 0 or 1.
+    // Otherwise, it is exactly `floor + 1 / 2`.
+    let odd = remainder % two();
+    let floor = if odd.is_zero() {
+        return mean;
+    } else if odd.is_one() {
+        mean
+    } else {
+        mean - T::one()
+    };
+
+    let odd_floor = !(floor.clone() % two()).is_zero();
+    if rounds_up(rounding, is_negative(&floor), odd_floor) {
+        floor + T::one()
+    } else {
+        floor
+    }
+}
+
+/// Checks whether a mean exactly halfway between `floor` and `floor + 1` is rounded up.
+pub(crate) fn rounds_up(rounding: Rounding, negative_floor: bool, odd_floor: bool) -> bool {
+    match rounding {
+        Rounding::TowardZero => negative_floor,
+        Rounding::Floor => false,
+        Rounding::Ceil => true,
+        Rounding::HalfEven => odd_floor,
+        Rounding::HalfAway => !negative_floor,
+    }
+}
+
+/// Returns the smaller (for `Ordering::Less`) or the larger element of a bucket of one or two elements.
+/// If exactly one of them is NaN, it is returned if `propagate` is true and ignored otherwise.
+fn extreme<T: Num + ToPrimitive>(bucket: &[T], order: Ordering, propagate: bool) -> Option<&T> {
+    match bucket {
+        [a, b] if is_nan(a) != is_nan(b) => Some(if is_nan(a) == propagate { a } else { b }),
+        [a, b] if !is_nan(a) && compare(b, a) == Some(order) => Some(b),
+        _ => bucket.first(),
+    }
+}
+
+/// Returns the NaN policy used for `T`. Integers have no NaN, so they always use [`NanPolicy::Propagate`],
+/// whose means can not overflow and follow the rounding mode.
+pub(crate) fn nan_policy_of<T: Num>(policy: NanPolicy) -> NanPolicy {
+    match is_integral::<T>() {
+        true => NanPolicy::Propagate,
+        false => policy,
+    }
+}
+
+/// Checks whether the division of the type truncates, i.e. whether it is an integer type.
+pub(crate) fn is_integral<T: Num>() -> bool {
+    T::one() / (T::one() + T::one()) == T::zero()
+}
+
+/// Checks whether an integer is negative, without requiring the type to be ordered.
+fn is_negative<T: ToPrimitive>(value: &T) -> bool {
+    value.to_i128().is_some_and(|x| x < 0)
+}
+
+/// Checks whether a value is NaN. Always false for integers.
+pub(crate) fn is_nan<T: ToPrimitive>(value: &T) -> bool {
+    value.to_f64().is_some_and(f64::is_nan)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,4 +983,534 @@ mod tests {
 
         assert_eq!(mipmap.get_level(mipmap.num_levels()), None);
     }
+
+    #[test]
+    fn test_exact_integer_means() {
+        let midpoint = |a, b| midpoint(a, b, Rounding::TowardZero);
+        assert_eq!(midpoint(3, 4), 3);
+        assert_eq!(midpoint(-3, -4), -3);
+        assert_eq!(midpoint(3, -4), 0);
+        assert_eq!(midpoint(-3, 4), 0);
+        assert_eq!(midpoint(-3, 5), 1);
+        assert_eq!(midpoint(i64::MAX, i64::MAX - 2), i64::MAX - 1);
+        assert_eq!(midpoint(i64::MIN, i64::MIN + 1), i64::MIN + 1);
+        assert_eq!(
+            super::midpoint(u64::MAX, u64::MAX, Rounding::TowardZero),
+            u64::MAX
+        );
+        assert_eq!(super::midpoint(1.0, 4.0, Rounding::Floor), 2.5);
+
+        // Both are beyond the precision of f64.
+        let data = vec![(1i64 << 60) + 1, (1i64 << 60) + 3];
+        let mipmap = MipMap1D::new(data);
+        assert_eq!(*mipmap.get_level(1).unwrap(), [(1 << 60) + 2]);
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let pairs = [(2, 3), (3, 4), (-2, -3), (-3, -4), (-1, 2), (4, 6)];
+        let expected = [
+            (Rounding::TowardZero, [2, 3, -2, -3, 0, 5]),
+            (Rounding::Floor, [2, 3, -3, -4, 0, 5]),
+            (Rounding::Ceil, [3, 4, -2, -3, 1, 5]),
+            (Rounding::HalfEven, [2, 4, -2, -4, 0, 5]),
+            (Rounding::HalfAway, [3, 4, -3, -4, 1, 5]),
+        ];
+
+        for (rounding, means) in expected {
+            let got = pairs.map(|(a, b)| midpoint(a, b, rounding));
+            assert_eq!(got, means, "{rounding:?}");
+        }
+        assert_eq!(midpoint(i32::MIN, i32::MIN + 1, Rounding::Floor), i32::MIN);
+        assert_eq!(midpoint(u8::MAX - 1, u8::MAX, Rounding::Ceil), u8::MAX);
+    }
+
+    #[test]
+    fn test_bit_identical_levels() {
+        let data: Vec<f32> = (0..13).map(|x| x as f32 / 7.0).collect();
+        let mipmap = MipMap1D::new(data);
+
+        let bits = |level: usize| mipmap.get_level(level).unwrap()[0].to_bits();
+        assert_eq!(bits(1), 0x3d924925);
+        assert_eq!(bits(3), 0x3f000000);
+        assert_eq!(bits(4), 0x3f824925);
+    }
+
+    #[test]
+    fn test_128_bit_integers() {
+        let big = u128::MAX - 4;
+        let mipmap = MipMap1D::new(vec![big, big + 2, u128::MAX, 1]);
+        assert_eq!(*mipmap.get_level(1).unwrap(), [big + 1, 1 << 127]);
+        assert_eq!(mipmap.get_level(2).unwrap()[0], (1 << 127) + (1 << 126) - 2);
+
+        let stats = mipmap.level_stats(0).unwrap();
+        assert_eq!((stats.min, stats.max), (1, u128::MAX));
+
+        let small = i128::MIN + 1;
+        let mipmap = MipMap1D::new(vec![small, small + 2, small + 3]);
+        assert_eq!(*mipmap.get_level(1).unwrap(), [small + 1, small + 3]);
+        assert_eq!(mipmap.level_stats(1).unwrap().max, small + 3);
+    }
+
+    use crate::fixtures::{CloneInt, Decimal, I16F16};
+
+    #[test]
+    fn test_exact_decimal_means() {
+        let decimal = |x: &str| Decimal(x.replace('.', "").parse::<i128>().unwrap() * 100_000_000);
+        let mut mipmap = MipMap1D::new(vec![decimal("0.1"), decimal("0.2"), decimal("0.7")]);
+        mipmap.push(decimal("0.3"));
+
+        assert_eq!(
+            *mipmap.get_level(1).unwrap(),
+            [Decimal(150_000_000), decimal("0.5")]
+        );
+        assert_eq!(*mipmap.get_level(2).unwrap(), [Decimal(325_000_000)]);
+    }
+
+    #[test]
+    fn test_binary_fixed_point_means() {
+        // 1.5, 0.25, the smallest positive value and -3.0.
+        let data = vec![I16F16(0x18000), I16F16(0x4000), I16F16(1), I16F16(-0x30000)];
+        let mipmap = MipMap1D::new(data);
+
+        assert_eq!(
+            *mipmap.get_level(1).unwrap(),
+            [I16F16(0xe000), I16F16(-0x17fff)]
+        );
+        assert_eq!(*mipmap.get_level(2).unwrap(), [I16F16(-0x4fff)]);
+        assert_eq!(mipmap.level_stats(0).unwrap().min, I16F16(-0x30000));
+    }
+
+    #[test]
+    fn test_clone_only_integers() {
+        let data: Vec<CloneInt> = [3, 4, -7, 1, 10].into_iter().map(CloneInt).collect();
+        let mut mipmap = MipMap1D::with_rounding(vec![], Rounding::HalfAway);
+        for x in data.clone() {
+            mipmap.push(x);
+        }
+
+        assert_eq!(
+            mipmap.data,
+            MipMap1D::with_rounding(data, Rounding::HalfAway).data
+        );
+        assert_eq!(
+            *mipmap.get_level(1).unwrap(),
+            [CloneInt(4), CloneInt(-3), CloneInt(10)]
+        );
+        assert_eq!(*mipmap.get_level(3).unwrap(), [CloneInt(6)]);
+        let stats = mipmap.level_stats(0).unwrap();
+        assert_eq!((stats.min, stats.max), (CloneInt(-7), CloneInt(10)));
+    }
+
+    #[test]
+    fn test_narrow_integers_match_exact_rounding() {
+        let roundings = [
+            Rounding::TowardZero,
+            Rounding::Floor,
+            Rounding::Ceil,
+            Rounding::HalfEven,
+            Rounding::HalfAway,
+        ];
+        let exact = |sum: i32, rounding| {
+            let floor = sum.div_euclid(2);
+            match rounding {
+                _ if sum % 2 == 0 => floor,
+                Rounding::TowardZero => sum / 2,
+                Rounding::Floor => floor,
+                Rounding::Ceil => floor + 1,
+                Rounding::HalfEven => floor + floor.rem_euclid(2),
+                Rounding::HalfAway => floor + i32::from(floor >= 0),
+            }
+        };
+
+        for rounding in roundings {
+            for a in i8::MIN..=i8::MAX {
+                for b in i8::MIN..=i8::MAX {
+                    let expected = exact(i32::from(a) + i32::from(b), rounding);
+                    assert_eq!(i32::from(midpoint(a, b, rounding)), expected);
+                    assert_eq!(
+                        midpoint(CloneInt(a.into()), CloneInt(b.into()), rounding).0,
+                        expected.into()
+                    );
+                }
+            }
+            assert_eq!(
+                midpoint(u8::MAX, u8::MAX - 1, rounding),
+                exact(509, rounding) as u8
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn assert_levels_eq(mipmap: &MipMap1D<f64>, target: &[Vec<f64>]) {
+        assert_eq!(mipmap.num_levels(), target.len());
+        for (level, target_item) in target.iter().enumerate() {
+            let res = mipmap.get_level(level).unwrap();
+            assert_eq!(res.len(), target_item.len());
+            for (got, expected) in res.iter().zip(target_item) {
+                assert!(
+                    (got.is_nan() && expected.is_nan()) || got == expected,
+                    "level {level}: got {res:?}, expected {target_item:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_nan_propagates_by_default() {
+        let nan = f64::NAN;
+        let data = vec![2.0, nan, 6.0, 8.0];
+        let target = [vec![2.0, nan, 6.0, 8.0], vec![nan, 7.0], vec![nan]];
+
+        assert_levels_eq(&MipMap1D::new(data.clone()), &target);
+        assert_levels_eq(
+            &MipMap1D::with_nan_policy(data, NanPolicy::Propagate),
+            &target,
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_nan_skip() {
+        let nan = f64::NAN;
+        let data = vec![2.0, nan, nan, nan, 6.0, 8.0];
+        let target = [
+            vec![2.0, nan, nan, nan, 6.0, 8.0],
+            vec![2.0, nan, 7.0],
+            vec![2.0, 7.0],
+            vec![4.5],
+        ];
+        let mipmap = MipMap1D::with_nan_policy(data, NanPolicy::Skip);
+
+        assert_levels_eq(&mipmap, &target);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_nan_missing() {
+        let nan = f64::NAN;
+        let data = vec![2.0, nan, nan, nan, 6.0, 8.0, 1.0];
+        let target = [
+            vec![2.0, nan, nan, nan, 6.0, 8.0, 1.0],
+            vec![2.0, nan, 7.0, 1.0],
+            vec![2.0, 5.0],
+            vec![4.25],
+        ];
+        let mipmap = MipMap1D::with_nan_policy(data, NanPolicy::Missing);
+
+        assert_levels_eq(&mipmap, &target);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_all_nan_buckets() {
+        let nan = f64::NAN;
+        let data = vec![nan, nan, nan];
+        let target = [vec![nan, nan, nan], vec![nan, nan], vec![nan]];
+
+        for policy in [NanPolicy::Propagate, NanPolicy::Skip, NanPolicy::Missing] {
+            assert_levels_eq(&MipMap1D::with_nan_policy(data.clone(), policy), &target);
+        }
+    }
+
+    #[test]
+    fn test_push_matches_new() {
+        let data = [2, 4, 6, 8, 9, 1, 5];
+        let mut mipmap = MipMap1D::new(vec![]);
+
+        for (i, &x) in data.iter().enumerate() {
+            mipmap.push(x);
+            assert_eq!(mipmap.data, MipMap1D::new(data[..=i].to_vec()).data);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_push_with_nan_policies() {
+        let nan = f64::NAN;
+        let data = vec![2.0, nan, nan, nan, 6.0, 8.0, 1.0];
+
+        for policy in [NanPolicy::Propagate, NanPolicy::Skip, NanPolicy::Missing] {
+            let mut mipmap = MipMap1D::with_nan_policy(vec![], policy);
+            for &x in &data {
+                mipmap.push(x);
+            }
+
+            let expected = MipMap1D::with_nan_policy(data.clone(), policy);
+            let levels: Vec<Vec<f64>> = (0..expected.num_levels())
+                .map(|level| expected.get_level(level).unwrap().to_vec())
+                .collect();
+            assert_levels_eq(&mipmap, &levels);
+            assert_eq!(mipmap.counts, expected.counts);
+        }
+    }
+
+    #[test]
+    fn test_level_within() {
+        let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+
+        assert_eq!(mipmap.level_within(5), 0);
+        assert_eq!(mipmap.level_within(4), 1);
+        assert_eq!(mipmap.level_within(2), 2);
+        assert_eq!(mipmap.level_within(0), 3);
+    }
+
+    #[test]
+    fn test_level_stats() {
+        let nan = f64::NAN;
+        let mipmap = MipMap1D::new(vec![2.0, nan, 6.0, 8.0, -1.0]);
+
+        let stats = mipmap.level_stats(0).unwrap();
+        assert_eq!((stats.min, stats.max, stats.count), (-1.0, 8.0, 4));
+        assert_eq!(stats.mean, 3.75);
+        let stats = mipmap.level_stats(1).unwrap();
+        assert_eq!((stats.min, stats.max, stats.count), (-1.0, 7.0, 2));
+        assert_eq!(mipmap.level_stats(2).unwrap().count, 1);
+        assert_eq!(mipmap.level_stats(3), None);
+        assert_eq!(mipmap.level_stats(4), None);
+        assert_eq!(MipMap1D::<f64>::new(vec![]).level_stats(0), None);
+    }
+
+    #[test]
+    fn test_exact_integer_stats() {
+        // Summing these values in f64 would round the mean down to 2^53.
+        let big: i64 = (1 << 53) + 1;
+        let mipmap = MipMap1D::new(vec![big, big, big, big + 4]);
+        assert_eq!(mipmap.level_stats(0).unwrap().mean, (big + 1) as f64);
+
+        // The sums overflow i128, so they are continued in floating point.
+        let mipmap = MipMap1D::new(vec![u128::MAX, u128::MAX, u128::MAX]);
+        assert_eq!(mipmap.level_stats(0).unwrap().mean, u128::MAX as f64);
+        let mipmap = MipMap1D::new(vec![i128::MAX, i128::MAX, i128::MAX, i128::MAX]);
+        assert_eq!(mipmap.level_stats(0).unwrap().mean, i128::MAX as f64);
+    }
+
+    #[test]
+    fn test_level_stats_after_push() {
+        let data = [5, 1, 9, 3, 7, 2, 8];
+        let mut mipmap = MipMap1D::new(vec![]);
+
+        for (i, &x) in data.iter().enumerate() {
+            mipmap.push(x);
+            let expected = MipMap1D::new(data[..=i].to_vec());
+            for level in 0..expected.num_levels() {
+                let stats = mipmap.level_stats(level).unwrap();
+                let expected_stats = expected.level_stats(level).unwrap();
+                assert_eq!(
+                    (stats.min, stats.max, stats.count),
+                    (expected_stats.min, expected_stats.max, expected_stats.count)
+                );
+                assert!((stats.mean - expected_stats.mean).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_level_hashes_after_push() {
+        let data = [5.0, 1.0, 9.5, 3.0, 7.0, 2.0, 8.0];
+        let mut mipmap = MipMap1D::new(vec![]);
+
+        for (i, &x) in data.iter().enumerate() {
+            mipmap.push(x);
+            let expected = MipMap1D::new(data[..=i].to_vec());
+            for level in 0..expected.num_levels() {
+                assert_eq!(mipmap.level_hash(level), expected.level_hash(level));
+            }
+            assert_eq!(mipmap.digest(), expected.digest());
+        }
+
+        let digest = mipmap.digest();
+        mipmap.rebuild_levels_with(Reduction::Max);
+        assert_eq!(
+            mipmap.level_hash(0),
+            MipMap1D::new(data.to_vec()).level_hash(0)
+        );
+        assert_ne!(
+            mipmap.level_hash(1),
+            MipMap1D::new(data.to_vec()).level_hash(1)
+        );
+        assert_ne!(mipmap.digest(), digest);
+        mipmap.prune_below(1);
+        assert_eq!(mipmap.level_hash(0), None);
+    }
+
+    #[test]
+    fn test_introspection() {
+        let mut mipmap = MipMap1D::new((0..1000).collect::<Vec<u32>>());
+        assert_eq!((mipmap.len(), mipmap.is_empty()), (1000, false));
+        assert_eq!(mipmap.level_len(3), Some(125));
+        assert_eq!(mipmap.level_len(11), None);
+        assert_eq!(mipmap.scale_of(3), Some(8));
+        assert_eq!(mipmap.scale_of(11), None);
+        assert!(mipmap.heap_size() >= 2000 * size_of::<u32>());
+
+        mipmap.prune_below(3);
+        assert_eq!(mipmap.len(), 1000);
+        assert_eq!(mipmap.level_len(2), None);
+        assert!(MipMap1D::<f64>::new(vec![]).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_pruning() {
+        let data: Vec<f64> = (0..100).map(f64::from).collect();
+        let full = MipMap1D::with_nan_policy(data.clone(), NanPolicy::Missing);
+        let mut mipmap = MipMap1D::with_nan_policy(data, NanPolicy::Missing);
+        let before = mipmap.memory_usage().total;
+
+        mipmap.prune_above(4);
+        mipmap.prune_below(2);
+        assert_eq!((mipmap.min_level(), mipmap.num_levels()), (2, 5));
+        assert_eq!(mipmap.get_level(1), None);
+        for level in 2..5 {
+            assert_eq!(mipmap.get_level(level), full.get_level(level));
+        }
+        assert_eq!(mipmap.level_within(0), 4);
+        assert_eq!(mipmap.verify(), Ok(()));
+        assert!(mipmap.memory_usage().total < before / 3);
+
+        mipmap.prune_below(100);
+        mipmap.prune_above(0);
+        assert_eq!((mipmap.min_level(), mipmap.num_levels()), (4, 5));
+        assert_eq!(mipmap.get_level(4), full.get_level(4));
+    }
+
+    #[test]
+    fn test_push_after_pruning_coarse_levels() {
+        for keep in 0..3 {
+            let mut mipmap = MipMap1D::new(vec![1, 2, 3, 4]);
+            mipmap.push(5);
+            mipmap.prune_above(keep);
+            for x in 6..40 {
+                mipmap.push(x);
+            }
+
+            let full = MipMap1D::new((1..40).collect());
+            assert_eq!(mipmap.num_levels(), keep + 1);
+            for level in 0..=keep {
+                assert_eq!(mipmap.get_level(level), full.get_level(level));
+            }
+            assert_eq!(mipmap.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_push_after_pruning_source_panics() {
+        let mut mipmap = MipMap1D::new(vec![1, 2, 3]);
+        mipmap.prune_below(1);
+        mipmap.push(4);
+    }
+
+    #[test]
+    fn test_rebuild_with_reductions() {
+        let data = vec![3, -1, 4, 1, -5, 9, 2];
+        let mut mipmap = MipMap1D::new(data.clone());
+        let source = mipmap.get_level(0).unwrap().as_ptr();
+
+        let expected = [
+            (Reduction::Min, [-1, 1, -5, 2]),
+            (Reduction::Max, [3, 4, 9, 2]),
+            (Reduction::First, [3, 4, -5, 2]),
+            (Reduction::Last, [-1, 1, 9, 2]),
+            (Reduction::Mean, [1, 2, 2, 2]),
+        ];
+        for (reduction, level) in expected {
+            mipmap.rebuild_levels_with(reduction);
+            assert_eq!(mipmap.reduction(), reduction);
+            assert_eq!(*mipmap.get_level(1).unwrap(), level);
+            assert_eq!(mipmap.num_levels(), 4);
+            assert_eq!(mipmap.verify(), Ok(()));
+        }
+        assert_eq!(mipmap.get_level(0).unwrap().as_ptr(), source);
+        assert_eq!(mipmap.get_level(0).unwrap(), data);
+    }
+
+    #[test]
+    fn test_push_after_rebuild() {
+        let mut mipmap = MipMap1D::new(vec![1.0, 5.0, 2.0]);
+        mipmap.rebuild_levels_with(Reduction::Max);
+        for x in [7.0, 0.0, 3.0] {
+            mipmap.push(x);
+        }
+
+        assert_eq!(*mipmap.get_level(1).unwrap(), [5.0, 7.0, 3.0]);
+        assert_eq!(*mipmap.get_level(3).unwrap(), [7.0]);
+        assert_eq!(mipmap.level_stats(1).unwrap().max, 7.0);
+        assert_eq!(mipmap.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_map_matches_rebuilt() {
+        let source: Vec<f64> = (0..37).map(|x| ((x * 7919) % 101) as f64).collect();
+        for reduction in [Reduction::Mean, Reduction::Max, Reduction::First] {
+            for (transform, f) in [
+                (
+                    Transform::Affine,
+                    (|x: f64| 0.5 * x - 3.0) as fn(f64) -> f64,
+                ),
+                (Transform::Monotonic, f64::sqrt),
+                (Transform::Arbitrary, |x: f64| (x - 50.0).abs()),
+            ] {
+                let mut mipmap = MipMap1D::new(source.clone());
+                mipmap.rebuild_levels_with(reduction);
+                let mapped = mipmap.map(transform, |&x| f(x) as f32);
+                mipmap.map_in_place(transform, f);
+
+                let mut rebuilt = MipMap1D::new(source.iter().map(|&x| f(x)).collect());
+                rebuilt.rebuild_levels_with(reduction);
+                for level in 0..rebuilt.num_levels() {
+                    let expected = rebuilt.get_level(level).unwrap();
+                    let pairs = mipmap.get_level(level).unwrap().iter().zip(expected);
+                    assert!(pairs.clone().all(|(x, y)| (x - y).abs() < 1e-9));
+                    let pairs = mapped.get_level(level).unwrap().iter().zip(expected);
+                    assert!(pairs.clone().all(|(x, y)| (*x as f64 - y).abs() < 1e-3));
+                }
+                assert_eq!(mipmap.level_stats(1), rebuilt.level_stats(1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_after_pruning() {
+        let mut mipmap = MipMap1D::new(vec![1, 2, 3, 4, 5]);
+        mipmap.prune_below(1);
+        mipmap.map_in_place(Transform::Affine, |x| 2 * x);
+        assert_eq!(*mipmap.get_level(1).unwrap(), [2, 6, 10]);
+
+        let mapped = mipmap.map(Transform::Affine, |&x| x as f64);
+        assert_eq!(mapped.min_level(), 1);
+        assert_eq!(*mapped.get_level(2).unwrap(), [4.0, 10.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rebuilding_map_after_pruning_panics() {
+        let mut mipmap = MipMap1D::new(vec![1, 2, 3]);
+        mipmap.prune_below(1);
+        mipmap.map_in_place(Transform::Monotonic, |x| x + 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_extremes_with_nans() {
+        let nan = f64::NAN;
+        let data = vec![nan, 2.0, 3.0, nan, nan, nan];
+
+        let mut propagated = MipMap1D::new(data.clone());
+        propagated.rebuild_levels_with(Reduction::Min);
+        assert!(propagated.get_level(1).unwrap().iter().all(|x| x.is_nan()));
+
+        let mut skipped = MipMap1D::with_nan_policy(data.clone(), NanPolicy::Skip);
+        skipped.rebuild_levels_with(Reduction::Max);
+        assert_eq!(skipped.get_level(1).unwrap()[..2], [2.0, 3.0]);
+        assert_eq!(*skipped.get_level(3).unwrap(), [3.0]);
+
+        let mut missing = MipMap1D::with_nan_policy(data, NanPolicy::Missing);
+        missing.rebuild_levels_with(Reduction::Last);
+        assert_eq!(missing.get_level(2).unwrap()[0], 3.0);
+        assert_eq!(*missing.get_level(3).unwrap(), [3.0]);
+        assert_eq!(missing.verify(), Ok(()));
+    }
 }
@@ -0,0 +1,287 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use num_traits::Float;
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::{is_nan, nan_policy_of};
+use crate::resample::reduce;
+use crate::{MipMapConfig, NanPolicy};
+
+/// Creates several downsampled versions of given row-major grid, e.g. a heatmap or a spectrogram.
+/// Each level is downsampled from the previous one by averaging 2×2 blocks,
+/// so it has half the width and half the height of the previous level.
+/// Blocks on odd edges only average the elements they contain.
+/// Other reductions can be chosen with [`MipMap2D::from_config`].
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap2D;
+///
+/// let data = vec![
+///     2, 4, 6,
+///     8, 10, 12,
+/// ];
+/// let mipmap = MipMap2D::new(data, 3);
+/// assert_eq!(mipmap.num_levels(), 3);
+/// assert_eq!(mipmap.level_dims(1), Some((2, 1)));
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [6, 9]);
+/// assert_eq!(*mipmap.get_level(2).unwrap(), [7]);
+/// assert_eq!(mipmap.get(1, 1, 0), Some(&9));
+/// ```
+pub struct MipMap2D<T: Num + ToPrimitive + FromPrimitive> {
+    data: Vec<Vec<T>>,
+    /// `(width, height)` of every level.
+    dims: Vec<(usize, usize)>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap2D<T> {
+    /// Constructs the levels of a row-major grid with given `width`.
+    ///
+    /// # Panics
+    /// Panics if the length of `source` is not a multiple of `width`.
+    pub fn new(source: Vec<T>, width: usize) -> Self {
+        Self::from_config(source, width, MipMapConfig::default())
+    }
+
+    /// Constructs the levels of a row-major grid with given `width` and configuration,
+    /// which behaves the same way as for [`MipMap1D::from_config`](crate::MipMap1D::from_config).
+    /// All elements of a 2×2 block are reduced at once, so integer means are rounded only once.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap2D, MipMapConfig, Reduction, Rounding};
+    ///
+    /// let data = vec![
+    ///     1, 2, 5, 9,
+    ///     2, 2, 7, 3,
+    /// ];
+    /// let max = MipMapConfig { reduction: Reduction::Max, ..Default::default() };
+    /// assert_eq!(*MipMap2D::from_config(data.clone(), 4, max).get_level(1).unwrap(), [2, 9]);
+    /// let half_even = MipMapConfig { rounding: Rounding::HalfEven, ..Default::default() };
+    /// assert_eq!(*MipMap2D::from_config(data, 4, half_even).get_level(1).unwrap(), [2, 6]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the length of `source` is not a multiple of `width`.
+    pub fn from_config(source: Vec<T>, width: usize, mut config: MipMapConfig) -> Self {
+        config.nan_policy = nan_policy_of::<T>(config.nan_policy);
+        let missing = config.nan_policy == NanPolicy::Missing;
+        let max_levels = config.max_levels.unwrap_or(usize::MAX).max(1);
+        let mut dims = vec![Self::source_dims(&source, width)];
+        // Number of valid source samples below every element, only tracked with `NanPolicy::Missing`.
+        let mut counts: Vec<usize> = match missing {
+            true => source.iter().map(|x| usize::from(!is_nan(x))).collect(),
+            false => vec![],
+        };
+        let mut data = vec![source];
+
+        while dims.len() < max_levels {
+            let (width, height) = dims[dims.len() - 1];
+            if width <= 1 && height <= 1 {
+                break;
+            }
+
+            let current = &data[data.len() - 1];
+            let (mipmap, mipmap_counts): (Vec<T>, Vec<usize>) = blocks(width, height)
+                .map(|(xs, ys)| {
+                    let elements: Vec<(&T, usize)> = ys
+                        .flat_map(|y| xs.clone().map(move |x| y * width + x))
+                        .map(|i| (&current[i], if missing { counts[i] } else { 1 }))
+                        .collect();
+                    let count: usize = elements.iter().map(|(_, count)| count).sum();
+                    (reduce(&elements, &config), count)
+                })
+                .unzip();
+
+            if missing {
+                counts = mipmap_counts;
+            }
+            dims.push((width.div_ceil(2), height.div_ceil(2)));
+            data.push(mipmap);
+        }
+
+        Self { data, dims }
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the row-major data on given level.
+    /// Level `0` returns the source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<T>> {
+        self.data.get(level)
+    }
+
+    /// Returns the `(width, height)` of given level.
+    /// If the level is out of bounds, returns None
+    pub fn level_dims(&self, level: usize) -> Option<(usize, usize)> {
+        self.dims.get(level).copied()
+    }
+
+    /// Returns the element at column `x` and row `y` of given level.
+    /// If any of them is out of bounds, returns None
+    pub fn get(&self, level: usize, x: usize, y: usize) -> Option<&T> {
+        let (width, height) = self.level_dims(level)?;
+        if x >= width || y >= height {
+            return None;
+        }
+
+        Some(&self.data[level][y * width + x])
+    }
+
+    /// Computes the `(width, height)` of the source grid.
+    fn source_dims(source: &[T], width: usize) -> (usize, usize) {
+        if width == 0 {
+            assert!(source.is_empty(), "A non-empty grid must have a width");
+            return (0, 0);
+        }
+        assert_eq!(
+            source.len() % width,
+            0,
+            "The length of the grid must be a multiple of its width"
+        );
+
+        (width, source.len() / width)
+    }
+}
+
+// `Float` is only available with the standard library.
+#[cfg(feature = "std")]
+impl<T: Float + FromPrimitive> MipMap2D<T> {
+    /// Same as [`MipMap2D::new`], but NaN values are handled according to `policy`.
+    /// The policies behave the same way as for [`MipMap1D::with_nan_policy`].
+    pub fn with_nan_policy(source: Vec<T>, width: usize, policy: NanPolicy) -> Self {
+        let config = MipMapConfig {
+            nan_policy: policy,
+            ..Default::default()
+        };
+        Self::from_config(source, width, config)
+    }
+}
+
+/// Iterates over the column and row ranges of 2×2 blocks of a `width`×`height` grid,
+/// in row-major order of the downsampled grid.
+fn blocks(width: usize, height: usize) -> impl Iterator<Item = (Range<usize>, Range<usize>)> {
+    (0..height).step_by(2).flat_map(move |y| {
+        (0..width)
+            .step_by(2)
+            .map(move |x| (x..(x + 2).min(width), y..(y + 2).min(height)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MipMap1D, Reduction};
+
+    #[test]
+    fn test_even_grid() {
+        let data = vec![
+            1, 3, 5, 7, //
+            3, 5, 7, 9, //
+            2, 2, 4, 4, //
+            2, 2, 4, 4, //
+        ];
+        let mipmap = MipMap2D::new(data, 4);
+
+        assert_eq!(mipmap.dims, [(4, 4), (2, 2), (1, 1)]);
+        assert_eq!(mipmap.data[1], [3, 7, 2, 4]);
+        assert_eq!(mipmap.data[2], [4]);
+    }
+
+    #[test]
+    fn test_blocks_are_rounded_once() {
+        // Averaging the row means first would give `(1 + 2) / 2 = 1`.
+        let mipmap = MipMap2D::new(vec![1, 2, 2, 3], 2);
+        assert_eq!(mipmap.data[1], [2]);
+    }
+
+    #[test]
+    fn test_config() {
+        let data = vec![
+            4, 1, 6, //
+            2, 3, 5, //
+            9, 8, 7, //
+        ];
+        let config = |reduction| MipMapConfig {
+            reduction,
+            max_levels: Some(2),
+            ..Default::default()
+        };
+
+        let min = MipMap2D::from_config(data.clone(), 3, config(Reduction::Min));
+        assert_eq!(min.data, [data.clone(), vec![1, 5, 8, 7]]);
+        let last = MipMap2D::from_config(data.clone(), 3, config(Reduction::Last));
+        assert_eq!(last.data[1], [3, 5, 8, 7]);
+        assert_eq!(last.dims, [(3, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn test_single_column_matches_1d() {
+        let data = vec![2, 4, 6, 8, 9];
+        let mipmap = MipMap2D::new(data.clone(), 1);
+        let mipmap_1d = MipMap1D::new(data);
+
+        assert_eq!(mipmap.num_levels(), mipmap_1d.num_levels());
+        for level in 0..mipmap.num_levels() {
+            assert_eq!(
+                mipmap.get_level(level).map(Vec::as_slice),
+                mipmap_1d.get_level(level)
+            );
+            assert_eq!(mipmap.level_dims(level).unwrap().0, 1);
+        }
+    }
+
+    #[test]
+    fn test_fails_on_nonexistent_level_or_cell() {
+        let mipmap = MipMap2D::new(vec![1.0, 2.0, 3.0, 4.0], 2);
+
+        assert_eq!(mipmap.get_level(mipmap.num_levels()), None);
+        assert_eq!(mipmap.level_dims(mipmap.num_levels()), None);
+        assert_eq!(mipmap.get(0, 2, 0), None);
+        assert_eq!(mipmap.get(1, 0, 1), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_nan_policies() {
+        let nan = f64::NAN;
+        let data = vec![
+            1.0, nan, nan, nan, //
+            3.0, 5.0, nan, nan, //
+        ];
+
+        let propagate = MipMap2D::with_nan_policy(data.clone(), 4, NanPolicy::Propagate);
+        assert!(propagate.data[1].iter().all(|x| x.is_nan()));
+
+        // The valid elements of a block are averaged at once, not row by row.
+        let skip = MipMap2D::with_nan_policy(data.clone(), 4, NanPolicy::Skip);
+        assert_eq!(skip.data[1][0], 3.0);
+        assert!(skip.data[1][1].is_nan());
+        assert_eq!(skip.data[2], [3.0]);
+
+        let missing = MipMap2D::with_nan_policy(data.clone(), 4, NanPolicy::Missing);
+        assert_eq!(missing.data[1][0], 3.0);
+        assert!(missing.data[1][1].is_nan());
+        assert_eq!(missing.data[2], [3.0]);
+
+        let config = MipMapConfig {
+            nan_policy: NanPolicy::Missing,
+            reduction: Reduction::Max,
+            ..Default::default()
+        };
+        let max = MipMap2D::from_config(data, 4, config);
+        assert_eq!(max.data[1][0], 5.0);
+        assert!(max.data[1][1].is_nan());
+        assert_eq!(max.data[2], [5.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ragged_grid() {
+        MipMap2D::new(vec![1, 2, 3], 2);
+    }
+}
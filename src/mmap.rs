@@ -0,0 +1,209 @@
+use std::ffi::{c_int, c_void};
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::bytes::Pod;
+use crate::viewport::{bucket_range, LevelSlice, Viewport};
+use crate::{ArchiveError, ArchivedMipMap, MipMap1D, MipMapConfig};
+
+const PROT_READ: c_int = 1;
+const MAP_PRIVATE: c_int = 2;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: c_int,
+        flags: c_int,
+        fd: c_int,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// A read-only mapping of a whole file, unmapped on drop.
+struct Mapping {
+    ptr: *mut c_void,
+    len: usize,
+}
+
+// The mapping is never written, so it can be read from any thread.
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File) -> io::Result<Self> {
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, ArchiveError::Truncated))?;
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                ArchiveError::NotAnArchive,
+            ));
+        }
+        let ptr = unsafe {
+            mmap(
+                std::ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { ptr, len })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr.cast(), self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        unsafe { munmap(self.ptr, self.len) };
+    }
+}
+
+/// Levels of a mipmap served directly from a memory-mapped archive written by [`MipMap1D::save`].
+/// Opening only reads the header, and the operating system loads the pages of a level when it is accessed,
+/// so even very large mipmaps open instantly and only take memory for the parts being displayed.
+/// The file must not be modified while it is mapped.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, Viewport};
+///
+/// let path = std::env::temp_dir().join("mipmap-1d-mmap-doctest.bin");
+/// MipMap1D::new(vec![2, 4, 6, 8, 9]).save(&path).unwrap();
+///
+/// let mapped = MipMap1D::<i32>::open_mmap(&path).unwrap();
+/// assert_eq!(*mapped.get_level(2).unwrap(), [5, 9]);
+/// assert_eq!(mapped.query(&Viewport::new(1..5, 2)).data, [5, 9]);
+/// # drop(mapped);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub struct MappedMipMap<T: 'static> {
+    // Borrows from `mapping`, so it is declared first to be dropped first.
+    archived: ArchivedMipMap<'static, T>,
+    mapping: Mapping,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Pod> MipMap1D<T> {
+    /// Maps a file written by [`MipMap1D::save`] into memory, without reading the levels.
+    /// An archive which is invalid or stores another element type is reported
+    /// as [`io::ErrorKind::InvalidData`] wrapping an [`ArchiveError`].
+    pub fn open_mmap(path: impl AsRef<Path>) -> io::Result<MappedMipMap<T>> {
+        let mapping = Mapping::new(&File::open(path)?)?;
+        // The levels only live as long as the mapping, which is kept next to them, so they are only lent out with `&self`.
+        let bytes: &'static [u8] =
+            unsafe { std::slice::from_raw_parts(mapping.ptr.cast(), mapping.len) };
+        let archived = ArchivedMipMap::from_bytes(bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(MappedMipMap { archived, mapping })
+    }
+}
+
+impl<T: Pod> MappedMipMap<T> {
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.archived.num_levels()
+    }
+
+    /// Returns the finest level which was not pruned before saving.
+    pub fn min_level(&self) -> usize {
+        self.archived.min_level()
+    }
+
+    /// Returns the configuration of the mapped mipmap.
+    pub fn config(&self) -> MipMapConfig {
+        self.archived.config()
+    }
+
+    /// Returns the data on given level.
+    /// If the level is out of bounds or was pruned, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[T]> {
+        self.archived.get_level(level)
+    }
+
+    /// Returns the finest level which has at most `max_points` elements.
+    /// If even the coarsest level is larger, returns the coarsest level.
+    pub fn level_within(&self, max_points: usize) -> usize {
+        (self.min_level()..self.num_levels())
+            .find(|&level| self.get_level(level).unwrap().len() <= max_points)
+            .unwrap_or(self.num_levels() - 1)
+    }
+
+    /// Same as [`MipMap1D::query`], but levels which were pruned before saving are never returned.
+    pub fn query(&self, viewport: &Viewport) -> LevelSlice<'_, T> {
+        let min_level = self.min_level();
+        let len = self.get_level(min_level).unwrap().len() << min_level;
+        let range = viewport.range.start.min(len)..viewport.range.end.min(len);
+        let level = viewport
+            .level_for(&range)
+            .clamp(min_level, self.num_levels() - 1);
+
+        let data = self.get_level(level).unwrap();
+        let buckets = bucket_range(&range, level);
+        let (start, end) = (buckets.start.min(data.len()), buckets.end.min(data.len()));
+        LevelSlice {
+            level,
+            start,
+            data: &data[start..end],
+        }
+    }
+
+    /// Returns the mapped bytes of the whole archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.mapping.bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("mipmap-1d-{name}-{}.bin", std::process::id()))
+    }
+
+    #[test]
+    fn test_mapped_levels_match() {
+        let mut mipmap = MipMap1D::new((0..1000).map(|x| (x * 37 % 101) as f64).collect());
+        mipmap.prune_below(2);
+        let path = temp_path("mapped");
+        mipmap.save(&path).unwrap();
+        let mapped = MipMap1D::<f64>::open_mmap(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(mapped.num_levels(), mipmap.num_levels());
+        assert_eq!(mapped.min_level(), 2);
+        for level in 0..=mipmap.num_levels() {
+            assert_eq!(mapped.get_level(level), mipmap.get_level(level));
+        }
+        assert_eq!(mapped.level_within(100), 4);
+        let slice = mapped.query(&Viewport::new(0..1000, 2000));
+        assert_eq!((slice.level, slice.data.len()), (2, 250));
+        assert_eq!(mapped.as_bytes(), mipmap.to_archive());
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let path = temp_path("invalid");
+        std::fs::write(&path, b"").unwrap();
+        let empty = MipMap1D::<u8>::open_mmap(&path).err().unwrap();
+        MipMap1D::new(vec![1u8, 2]).save(&path).unwrap();
+        let mismatch = MipMap1D::<i8>::open_mmap(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(empty.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(mismatch.to_string(), "the archive stores u8, expected i8");
+        assert!(MipMap1D::<u8>::open_mmap(&path).is_err());
+    }
+}
@@ -0,0 +1,227 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Holds mipmaps of several equal-length channels that are downsampled together.
+/// All channels are always queried at the same level and range,
+/// so a multi-trace plot never shows channels at mismatched resolutions.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MultiMipMap;
+///
+/// let left = vec![2, 4, 6, 8, 9];
+/// let right = vec![1, 3, 5, 7, 9];
+/// let mipmap = MultiMipMap::new(vec![left, right]);
+/// assert_eq!(mipmap.num_channels(), 2);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [vec![3, 7, 9], vec![2, 6, 9]]);
+/// assert_eq!(mipmap.get_range(1, 1..3).unwrap(), [[7, 9], [6, 9]]);
+/// ```
+pub struct MultiMipMap<T: Num + ToPrimitive + FromPrimitive> {
+    /// Levels of every channel, indexed as `data[level][channel]`.
+    data: Vec<Vec<Vec<T>>>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MultiMipMap<T> {
+    /// Constructs the levels of all channels.
+    ///
+    /// # Panics
+    /// Panics if the channels have different lengths.
+    pub fn new(channels: Vec<Vec<T>>) -> Self {
+        let len = channels.first().map_or(0, Vec::len);
+        assert!(
+            channels.iter().all(|channel| channel.len() == len),
+            "All channels must have the same length"
+        );
+
+        Self::build(vec![channels], len)
+    }
+
+    /// Constructs the levels from interleaved frames, e.g. `[l0, r0, l1, r1, ...]` for stereo audio.
+    /// Samples are de-interleaved straight into the per-channel levels,
+    /// and the first downsampled level is computed in the same pass.
+    ///
+    /// # Panics
+    /// Panics if `num_channels` is zero or `frames` does not consist of whole frames.
+    pub fn from_interleaved(frames: &[T], num_channels: usize) -> Self {
+        assert!(num_channels > 0, "There must be at least one channel");
+        assert_eq!(
+            frames.len() % num_channels,
+            0,
+            "The number of samples must be a multiple of the number of channels"
+        );
+
+        let len = frames.len() / num_channels;
+        if len <= 1 {
+            let channels = (0..num_channels)
+                .map(|channel| {
+                    frames
+                        .iter()
+                        .skip(channel)
+                        .step_by(num_channels)
+                        .copied()
+                        .collect()
+                })
+                .collect();
+            return Self::build(vec![channels], len);
+        }
+
+        let mut source = vec![Vec::with_capacity(len); num_channels];
+        let mut mipmap = vec![Vec::with_capacity(len.div_ceil(2)); num_channels];
+
+        for pair in frames.chunks(2 * num_channels) {
+            for (channel, (source, mipmap)) in source.iter_mut().zip(&mut mipmap).enumerate() {
+                let first = pair[channel];
+                source.push(first);
+                match pair.get(num_channels + channel) {
+                    Some(&second) => {
+                        source.push(second);
+                        mipmap.push(MipMap1D::average(&[first, second]));
+                    }
+                    None => mipmap.push(first),
+                }
+            }
+        }
+
+        Self::build(vec![source, mipmap], len.div_ceil(2))
+    }
+
+    /// Keeps downsampling the last of `data` levels, which has `len` elements per channel,
+    /// until a single element is left.
+    fn build(mut data: Vec<Vec<Vec<T>>>, mut len: usize) -> Self {
+        while len > 1 {
+            let mipmap = Self::downsample(&data[data.len() - 1], len);
+            len = len.div_ceil(2);
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the number of channels.
+    pub fn num_channels(&self) -> usize {
+        self.data[0].len()
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(channel.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the data of all channels on given level.
+    /// Level `0` returns the source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[Vec<T>]> {
+        self.data.get(level).map(Vec::as_slice)
+    }
+
+    /// Returns the given range of all channels on given level.
+    /// If the level or the range is out of bounds, returns None
+    pub fn get_range(&self, level: usize, range: Range<usize>) -> Option<Vec<&[T]>> {
+        self.data
+            .get(level)?
+            .iter()
+            .map(|channel| channel.get(range.clone()))
+            .collect()
+    }
+
+    /// Downsamples every channel of length `len` to `ceil(len / 2)` elements.
+    /// Bucket boundaries are computed once and shared by all channels.
+    fn downsample(channels: &[Vec<T>], len: usize) -> Vec<Vec<T>> {
+        let buckets: Vec<Range<usize>> = (0..len)
+            .step_by(2)
+            .map(|start| start..(start + 2).min(len))
+            .collect();
+
+        channels
+            .iter()
+            .map(|channel| {
+                buckets
+                    .iter()
+                    .map(|bucket| MipMap1D::average(&channel[bucket.clone()]))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channels_match_single_mipmaps() {
+        let channels = vec![
+            vec![2, 4, 6, 8, 9],
+            vec![9, 7, 5, 3, 1],
+            vec![0, 0, 4, 4, 8],
+        ];
+        let mipmap = MultiMipMap::new(channels.clone());
+        let singles: Vec<_> = channels.into_iter().map(MipMap1D::new).collect();
+
+        assert_eq!(mipmap.num_levels(), singles[0].num_levels());
+        for level in 0..mipmap.num_levels() {
+            let res = mipmap.get_level(level).unwrap();
+            for (channel, single) in res.iter().zip(&singles) {
+                assert_eq!(channel, single.get_level(level).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_fails_on_nonexistent_level_or_range() {
+        let mipmap = MultiMipMap::new(vec![vec![2, 4, 6, 8, 9], vec![1, 3, 5, 7, 9]]);
+
+        assert_eq!(mipmap.get_level(mipmap.num_levels()), None);
+        assert_eq!(mipmap.get_range(mipmap.num_levels(), 0..1), None);
+        assert_eq!(mipmap.get_range(1, 2..4), None);
+    }
+
+    #[test]
+    fn test_interleaved_matches_separate_channels() {
+        let left = vec![2, 4, 6, 8, 9];
+        let right = vec![1, 3, 5, 7, 9];
+        let frames: Vec<_> = left
+            .iter()
+            .zip(&right)
+            .flat_map(|(l, r)| [*l, *r])
+            .collect();
+
+        let separate = MultiMipMap::new(vec![left, right]);
+        let interleaved = MultiMipMap::from_interleaved(&frames, 2);
+
+        assert_eq!(interleaved.data, separate.data);
+    }
+
+    #[test]
+    fn test_interleaved_single_frame() {
+        let mipmap = MultiMipMap::from_interleaved(&[1, 2, 3], 3);
+
+        assert_eq!(mipmap.num_levels(), 1);
+        assert_eq!(*mipmap.get_level(0).unwrap(), [vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_interleaved_partial_frame() {
+        MultiMipMap::from_interleaved(&[1, 2, 3], 2);
+    }
+
+    #[test]
+    fn test_no_channels() {
+        let mipmap = MultiMipMap::<f32>::new(vec![]);
+
+        assert_eq!(mipmap.num_channels(), 0);
+        assert_eq!(mipmap.num_levels(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mismatched_channels() {
+        MultiMipMap::new(vec![vec![1, 2, 3], vec![1, 2]]);
+    }
+}
@@ -0,0 +1,16 @@
+/// Describes how NaN values in float data are treated while downsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanPolicy {
+    /// A NaN anywhere in a bucket makes the whole bucket NaN.
+    /// This is the behaviour of plain averaging.
+    #[default]
+    Propagate,
+    /// NaNs are skipped and the rest of the pair is averaged.
+    /// A pair made only of NaNs stays NaN.
+    Skip,
+    /// NaNs are treated as missing samples.
+    /// Each coarse value is the mean of all valid source samples in its bucket,
+    /// so a mostly-NaN bucket does not outweigh a complete one.
+    /// A bucket without any valid samples is NaN.
+    Missing,
+}
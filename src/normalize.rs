@@ -0,0 +1,132 @@
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, Transform};
+
+/// Describes how [`MipMap1D::normalize`] rescales the samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// Maps the minimum of the samples to `low` and the maximum to `high`, e.g. `0.0` and `1.0`.
+    MinMax { low: f64, high: f64 },
+    /// Subtracts the mean and divides by the standard deviation of the samples.
+    ZScore,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Rescales the samples as `f64` according to `normalization`, e.g. to overlay several series
+    /// of different magnitudes on one chart. All levels are mapped consistently, so a normalized level
+    /// is the level of the normalized samples.
+    /// The minimum, maximum and mean are taken from the statistics of the finest level, so only
+    /// [`Normalization::ZScore`] scans it. NaNs are ignored, and constant or empty data is mapped to `low` or `0`.
+    /// After [`MipMap1D::prune_below`], the statistics of the finest remaining level are used.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Normalization};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 10]);
+    /// let unit = mipmap.normalize(Normalization::MinMax { low: 0.0, high: 1.0 });
+    /// assert_eq!(*unit.get_level(0).unwrap(), [0.0, 0.25, 0.5, 0.75, 1.0]);
+    /// assert_eq!(*unit.get_level(1).unwrap(), [0.125, 0.625, 1.0]);
+    ///
+    /// let z = mipmap.normalize(Normalization::ZScore);
+    /// assert_eq!(z.get_level(0).unwrap()[2], 0.0);
+    /// ```
+    pub fn normalize(&self, normalization: Normalization) -> MipMap1D<f64> {
+        let (scale, offset) = self.normalization(normalization);
+        // A negative scale swaps minima and maxima, so those reductions must be rebuilt.
+        let transform = match scale < 0.0 {
+            true => Transform::Arbitrary,
+            false => Transform::Affine,
+        };
+        self.map(transform, |x| x.to_f64().unwrap() * scale + offset)
+    }
+
+    /// Returns the scale and offset which normalize the samples.
+    fn normalization(&self, normalization: Normalization) -> (f64, f64) {
+        let level = self.min_level();
+        let Some(stats) = self.level_stats(level) else {
+            return match normalization {
+                Normalization::MinMax { low, .. } => (1.0, low),
+                Normalization::ZScore => (1.0, 0.0),
+            };
+        };
+
+        match normalization {
+            Normalization::MinMax { low, high } => {
+                let (min, max) = (stats.min.to_f64().unwrap(), stats.max.to_f64().unwrap());
+                let scale = match max > min {
+                    true => (high - low) / (max - min),
+                    false => 0.0,
+                };
+                (scale, low - min * scale)
+            }
+            Normalization::ZScore => {
+                let squares: f64 = self.get_level(level).unwrap().iter().fold(0.0, |sum, x| {
+                    let x = x.to_f64().unwrap();
+                    match x.is_nan() {
+                        true => sum,
+                        false => sum + (x - stats.mean) * (x - stats.mean),
+                    }
+                });
+                let deviation = (squares / stats.count as f64).sqrt();
+                let scale = match deviation > 0.0 {
+                    true => 1.0 / deviation,
+                    false => 0.0,
+                };
+                (scale, -stats.mean * scale)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Reduction;
+
+    #[test]
+    fn test_overlaid_series_share_range() {
+        let small: Vec<f32> = (0..100).map(|x| (x as f32 * 0.1).sin() * 1e-3).collect();
+        let large: Vec<i64> = (0..100).map(|x| (x * 7919) % 101 * 1_000_000).collect();
+        let mut large = MipMap1D::new(large);
+        large.rebuild_levels_with(Reduction::Max);
+
+        for normalized in [
+            MipMap1D::new(small).normalize(Normalization::MinMax {
+                low: -1.0,
+                high: 1.0,
+            }),
+            large.normalize(Normalization::MinMax {
+                low: -1.0,
+                high: 1.0,
+            }),
+        ] {
+            let stats = normalized.level_stats(0).unwrap();
+            assert!((stats.min + 1.0).abs() < 1e-9 && (stats.max - 1.0).abs() < 1e-9);
+            for level in 1..normalized.num_levels() {
+                let level = normalized.get_level(level).unwrap();
+                assert!(level.iter().all(|x| (-1.0..=1.0 + 1e-9).contains(x)));
+            }
+        }
+        // Flipping rebuilds the maxima, so the top is the flipped minimum.
+        let top = large.num_levels() - 1;
+        let flipped = large.normalize(Normalization::MinMax {
+            low: 1.0,
+            high: 0.0,
+        });
+        assert_eq!(*flipped.get_level(top).unwrap(), [1.0]);
+    }
+
+    #[test]
+    fn test_z_score() {
+        let mipmap = MipMap1D::new(vec![1.0, f64::NAN, 3.0, 5.0, 7.0]);
+        let z = mipmap.normalize(Normalization::ZScore);
+        let deviation = 5.0f64.sqrt();
+        assert!((z.get_level(0).unwrap()[0] + 3.0 / deviation).abs() < 1e-12);
+        assert!(z.get_level(0).unwrap()[1].is_nan());
+
+        let constant = MipMap1D::new(vec![4, 4, 4]).normalize(Normalization::ZScore);
+        assert_eq!(*constant.get_level(1).unwrap(), [0.0, 0.0]);
+        let empty = MipMap1D::<u8>::new(vec![]).normalize(Normalization::ZScore);
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Element types which can be read from `.npy` files, identified by their NumPy type code, e.g. `f8` for `f64`.
+pub trait NpyElement: Sized {
+    /// The kind of the type in NumPy: `f`, `i` or `u`.
+    const KIND: char;
+
+    /// Decodes an element from its bytes, in little-endian order if `little` is true.
+    fn decode(bytes: &[u8], little: bool) -> Self;
+}
+
+macro_rules! impl_npy_element {
+    ($kind:literal: $($t:ty),*) => {
+        $(impl NpyElement for $t {
+            const KIND: char = $kind;
+
+            fn decode(bytes: &[u8], little: bool) -> Self {
+                let bytes = bytes.try_into().unwrap();
+                match little {
+                    true => <$t>::from_le_bytes(bytes),
+                    false => <$t>::from_be_bytes(bytes),
+                }
+            }
+        })*
+    };
+}
+
+impl_npy_element!('f': f32, f64);
+impl_npy_element!('i': i8, i16, i32, i64);
+impl_npy_element!('u': u8, u16, u32, u64);
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone + NpyElement> MipMap1D<T> {
+    /// Reads a one-dimensional array from a `.npy` file, e.g. written by `numpy.save`.
+    /// The array must have exactly the element type `T`, in either byte order.
+    /// Files of another shape or element type are reported as [`io::ErrorKind::InvalidData`].
+    /// Example:
+    /// ```rust,no_run
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// // Written with `numpy.save("signal.npy", numpy.arange(5.0))`.
+    /// let mipmap = MipMap1D::<f64>::from_npy("signal.npy").unwrap();
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [0.5, 2.5, 4.0]);
+    /// ```
+    pub fn from_npy(path: impl AsRef<Path>) -> io::Result<Self> {
+        read_npy(BufReader::new(File::open(path)?)).map(Self::new)
+    }
+}
+
+/// Reads the header and the elements of a `.npy` file.
+fn read_npy<T: NpyElement>(mut reader: impl Read) -> io::Result<Vec<T>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut prefix = [0u8; 8];
+    reader.read_exact(&mut prefix)?;
+    if &prefix[..6] != MAGIC {
+        return Err(invalid("the file is not a .npy file".to_string()));
+    }
+    let header_len = match prefix[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => return Err(invalid(format!(".npy version {version} is not supported"))),
+    };
+    let header = read_bytes(&mut reader, header_len)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = value(&header, "descr")
+        .unwrap_or_default()
+        .trim_matches(['\'', '"']);
+    let size = size_of::<T>();
+    let little = match descr.chars().next() {
+        Some('<') => true,
+        Some('>') => false,
+        Some('|') if size == 1 => true,
+        _ => cfg!(target_endian = "little"),
+    };
+    let code = descr.trim_start_matches(['<', '>', '|', '=']);
+    let expected = format!("{}{size}", T::KIND);
+    if code != expected {
+        return Err(invalid(format!(
+            "the file stores {code}, expected {expected}"
+        )));
+    }
+    let shape = value(&header, "shape").unwrap_or_default();
+    let dims: Vec<&str> = shape
+        .trim_matches(['(', ')'])
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .collect();
+    let len = match dims[..] {
+        [len] => len.parse::<usize>().ok(),
+        _ => None,
+    };
+    let len = len.ok_or_else(|| {
+        invalid(format!(
+            "the array has shape {shape}, expected one dimension"
+        ))
+    })?;
+
+    let bytes = len
+        .checked_mul(size)
+        .ok_or_else(|| invalid(format!("the array of {len} elements is too large")))?;
+    let bytes = read_bytes(&mut reader, bytes)?;
+    Ok(bytes
+        .chunks_exact(size)
+        .map(|bytes| T::decode(bytes, little))
+        .collect())
+}
+
+/// Reads `len` bytes. The buffer grows with the data read, so a corrupted length does not allocate up front.
+fn read_bytes(reader: impl Read, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![];
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// Returns the value of given key in the header dictionary, e.g. `'<f8'` for `descr`.
+fn value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{key}'"))? + key.len() + 2;
+    let rest = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = match rest.starts_with('(') {
+        true => rest.find(')')? + 1,
+        false => rest.find([',', '}']).unwrap_or(rest.len()),
+    };
+    Some(rest[..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes a header and data as a `.npy` file of version 1.
+    fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn test_read_arrays() {
+        let data: Vec<u8> = [1.5f64, -2.0, 3.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let file = npy(
+            "{'descr': '<f8', 'fortran_order': False, 'shape': (3,), }\n",
+            &data,
+        );
+        assert_eq!(read_npy::<f64>(&file[..]).unwrap(), [1.5, -2.0, 3.0]);
+
+        let data: Vec<u8> = [7i32, -1].iter().flat_map(|x| x.to_be_bytes()).collect();
+        let file = npy(
+            "{'descr': '>i4', 'fortran_order': False, 'shape': (2,), }",
+            &data,
+        );
+        assert_eq!(read_npy::<i32>(&file[..]).unwrap(), [7, -1]);
+
+        let file = npy(
+            "{'descr': '|u1', 'fortran_order': False, 'shape': (2,), }",
+            &[4, 5],
+        );
+        let path = std::env::temp_dir().join(format!("mipmap-1d-{}.npy", std::process::id()));
+        std::fs::write(&path, file).unwrap();
+        let mipmap = MipMap1D::<u8>::from_npy(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(*mipmap.get_level(1).unwrap(), [4]);
+    }
+
+    #[test]
+    fn test_invalid_arrays() {
+        let error = |header: &str| {
+            read_npy::<f32>(&npy(header, &[0; 32])[..])
+                .err()
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            error("{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }"),
+            "the file stores f8, expected f4"
+        );
+        assert_eq!(
+            error("{'descr': '<f4', 'fortran_order': False, 'shape': (2, 2), }"),
+            "the array has shape (2, 2), expected one dimension"
+        );
+        assert_eq!(
+            error("{'descr': '<f4', 'fortran_order': False, 'shape': (4611686018427387904,), }"),
+            "the array of 4611686018427387904 elements is too large"
+        );
+        let truncated = read_npy::<f32>(&npy("{'descr': '<f4', 'shape': (9,), }", &[0; 32])[..]);
+        assert_eq!(
+            truncated.err().unwrap().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert!(read_npy::<f32>(&b"PK\x03\x04 not npy"[..]).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use core::error::Error;
+use core::fmt;
+
+use num_traits::{Bounded, Num};
+
+use crate::mipmap::is_integral;
+
+/// Describes how sums which do not fit into the sample type are handled, e.g. running totals of large integers.
+/// To avoid overflows altogether, sums can also be widened into a larger type,
+/// see [`CumulativeMipMap::widen`](crate::CumulativeMipMap::widen).
+/// Without a policy, [`CumulativeMipMap::new`](crate::CumulativeMipMap::new) saturates integer totals.
+/// Pairwise integer means never overflow, since they are computed without summing the samples in the sample type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// An overflowing sum makes construction fail with an [`OverflowError`].
+    #[default]
+    Checked,
+    /// An overflowing sum is clamped to the smallest or largest value of the type.
+    Saturate,
+}
+
+/// Error returned when a sum overflows with [`OverflowPolicy::Checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    /// Index of the source sample at which the sum overflowed.
+    pub index: usize,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sum overflowed at source sample {}", self.index)
+    }
+}
+
+impl Error for OverflowError {}
+
+/// Adds integers with saturation at the bounds of the type. Float sums are not changed.
+pub(crate) fn saturating_add<T: Num + Bounded + PartialOrd + Copy>(a: T, b: T) -> T {
+    if !is_integral::<T>() {
+        a + b
+    } else if b > T::zero() && a > T::max_value() - b {
+        T::max_value()
+    } else if b < T::zero() && a < T::min_value() - b {
+        T::min_value()
+    } else {
+        a + b
+    }
+}
+
+/// Subtracts integers with saturation at the bounds of the type. Float differences are not changed.
+pub(crate) fn saturating_sub<T: Num + Bounded + PartialOrd + Copy>(a: T, b: T) -> T {
+    if !is_integral::<T>() {
+        a - b
+    } else if b < T::zero() && a > T::max_value() + b {
+        T::max_value()
+    } else if b > T::zero() && a < T::min_value() + b {
+        T::min_value()
+    } else {
+        a - b
+    }
+}
@@ -0,0 +1,125 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::resample::reduce;
+use crate::{MipMap1D, MipMapConfig};
+
+/// Levels whose buckets overlap by half: every element of level `l > 0` combines two neighbouring
+/// buckets of level `l - 1` of a [`MipMap1D`], i.e. `2^l` source samples, and the next element starts
+/// one bucket later. Coarse levels look smoother than the blocky box filter of a plain mipmap,
+/// at the cost of about twice as many elements.
+/// Like in the mipmap, a shorter last bucket is combined with the same weight as a full one.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMapConfig, OverlappingLevels};
+///
+/// let source = vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0, 12.0, 14.0];
+/// let levels = OverlappingLevels::new(source, MipMapConfig::default());
+/// assert_eq!(*levels.get_level(1).unwrap(), [1.0, 3.0, 5.0, 7.0, 9.0, 11.0, 13.0]);
+/// assert_eq!(*levels.get_level(2).unwrap(), [3.0, 7.0, 11.0]);
+/// assert_eq!(levels.window(2, 1), Some(2..6));
+/// ```
+pub struct OverlappingLevels<T> {
+    levels: Vec<Vec<T>>,
+    source_len: usize,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> OverlappingLevels<T> {
+    /// Constructs the overlapping levels of `source`, combining buckets according to `config`.
+    pub fn new(source: Vec<T>, config: MipMapConfig) -> Self {
+        let source_len = source.len();
+        let mipmap = MipMap1D::from_config(source, config);
+
+        let mut levels = Vec::with_capacity(mipmap.num_levels());
+        levels.push(mipmap.get_level(0).unwrap().to_vec());
+        for level in 1..mipmap.num_levels() {
+            let below = mipmap.get_level(level - 1).unwrap();
+            let data = (0..below.len() - 1)
+                .map(|index| reduce(&[(&below[index], 1), (&below[index + 1], 1)], &config))
+                .collect();
+            levels.push(data);
+        }
+
+        Self { levels, source_len }
+    }
+}
+
+impl<T> OverlappingLevels<T> {
+    /// Returns the total number of levels, the same as that of the [`MipMap1D`] of the source.
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the data on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[T]> {
+        self.levels.get(level).map(Vec::as_slice)
+    }
+
+    /// Returns the source indices combined into the element `index` of given level.
+    /// If the level or the index is out of bounds, returns None
+    pub fn window(&self, level: usize, index: usize) -> Option<Range<usize>> {
+        if index >= self.get_level(level)?.len() {
+            return None;
+        }
+        let (start, width) = match level {
+            0 => (index, 1),
+            _ => (index << (level - 1), 1 << level),
+        };
+        Some(start..self.source_len.min(start + width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Reduction;
+
+    #[test]
+    fn test_matches_windows_of_source() {
+        let source: Vec<f64> = (0..64).map(|x| ((x * 7919) % 101 - 50) as f64).collect();
+        let mean = OverlappingLevels::new(source.clone(), MipMapConfig::default());
+        let max = OverlappingLevels::new(
+            source.clone(),
+            MipMapConfig {
+                reduction: Reduction::Max,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            mean.num_levels(),
+            MipMap1D::new(source.clone()).num_levels()
+        );
+        for level in 1..mean.num_levels() {
+            for (index, &value) in mean.get_level(level).unwrap().iter().enumerate() {
+                let window = &source[mean.window(level, index).unwrap()];
+                let sum: f64 = window.iter().sum();
+                assert!((value - sum / window.len() as f64).abs() < 1e-9);
+                let expected = window.iter().copied().fold(f64::MIN, f64::max);
+                assert_eq!(max.get_level(level).unwrap()[index], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_short_sources() {
+        let single = OverlappingLevels::new(vec![5.0], MipMapConfig::default());
+        assert_eq!(single.num_levels(), 1);
+        assert_eq!(single.window(0, 0), Some(0..1));
+        assert_eq!(single.window(0, 1), None);
+
+        let three = OverlappingLevels::new(vec![1.0, 2.0, 6.0], MipMapConfig::default());
+        assert_eq!(*three.get_level(1).unwrap(), [1.5, 4.0]);
+        // The lone last sample weighs as much as the first pair.
+        assert_eq!(*three.get_level(2).unwrap(), [3.75]);
+        assert_eq!(three.window(2, 0), Some(0..3));
+        assert_eq!(three.get_level(3), None);
+        assert_eq!(
+            OverlappingLevels::<u8>::new(vec![], MipMapConfig::default()).num_levels(),
+            1
+        );
+    }
+}
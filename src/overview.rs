@@ -0,0 +1,102 @@
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Creates the downsampled levels of given slice without keeping a copy of it,
+/// for callers which already own the source elsewhere.
+/// This takes about the same space as the source instead of twice as much.
+/// Level `0` is not stored, but can be delegated to the source with [`OverviewMipMap::get_level_or`].
+/// Example:
+/// ```rust
+/// use mipmap_1d::OverviewMipMap;
+///
+/// let source = vec![2, 4, 6, 8, 9];
+/// let overview = OverviewMipMap::new(&source);
+/// assert_eq!(overview.num_levels(), 4);
+/// assert_eq!(overview.get_level(0), None);
+/// assert_eq!(*overview.get_level(1).unwrap(), [3, 7, 9]);
+/// assert_eq!(*overview.get_level_or(0, &source).unwrap(), [2, 4, 6, 8, 9]);
+/// ```
+pub struct OverviewMipMap<T: Num + ToPrimitive + FromPrimitive> {
+    source_len: usize,
+    /// Levels starting from `1`, or None if the source has at most one element.
+    levels: Option<MipMap1D<T>>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> OverviewMipMap<T> {
+    pub fn new(source: &[T]) -> Self {
+        let levels = (source.len() > 1)
+            .then(|| MipMap1D::new(source.chunks(2).map(MipMap1D::average).collect()));
+
+        Self {
+            source_len: source.len(),
+            levels,
+        }
+    }
+
+    /// Returns the total number of downsampled levels, including the source.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        1 + self.levels.as_ref().map_or(0, MipMap1D::num_levels)
+    }
+
+    /// Returns the length of the source the levels were built from.
+    pub fn source_len(&self) -> usize {
+        self.source_len
+    }
+
+    /// Returns the data on given downsampled level.
+    /// If the level is `0` (which is not stored) or out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&[T]> {
+        self.levels.as_ref()?.get_level(level.checked_sub(1)?)
+    }
+
+    /// Same as [`OverviewMipMap::get_level`], but level `0` is taken from `source`.
+    ///
+    /// # Panics
+    /// Panics if `source` does not have the length of the source the levels were built from.
+    pub fn get_level_or<'a>(&'a self, level: usize, source: &'a [T]) -> Option<&'a [T]> {
+        assert_eq!(
+            source.len(),
+            self.source_len,
+            "The source must be the one the levels were built from"
+        );
+        match level {
+            0 => Some(source),
+            _ => self.get_level(level),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_full_mipmap() {
+        let source: Vec<f32> = (0..37).map(|x| (x as f32 * 0.4).cos()).collect();
+        let full = MipMap1D::new(source.clone());
+        let overview = OverviewMipMap::new(&source);
+
+        assert_eq!(overview.num_levels(), full.num_levels());
+        assert_eq!(overview.source_len(), 37);
+        for level in 0..=full.num_levels() {
+            assert_eq!(overview.get_level_or(level, &source), full.get_level(level));
+        }
+    }
+
+    #[test]
+    fn test_short_sources() {
+        let single = OverviewMipMap::new(&[5]);
+        assert_eq!(single.num_levels(), 1);
+        assert_eq!(single.get_level(1), None);
+        assert_eq!(single.get_level_or(0, &[5]), Some(&[5][..]));
+        assert_eq!(OverviewMipMap::<u8>::new(&[]).num_levels(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_other_source_panics() {
+        OverviewMipMap::new(&[1, 2, 3]).get_level_or(1, &[1, 2]);
+    }
+}
@@ -0,0 +1,110 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// All levels of a mipmap packed into one byte buffer, e.g. to upload them as a GPU buffer or a 1D texture array.
+/// Every level starts at a multiple of the alignment given on export, the gaps are filled with zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedLevels {
+    pub bytes: Vec<u8>,
+    /// Size of one element in bytes.
+    pub element_size: usize,
+    /// Byte range of every level within `bytes`.
+    pub levels: Vec<Range<usize>>,
+}
+
+impl PackedLevels {
+    /// Packs levels whose elements are encoded by `encode` into `element_size` bytes each.
+    ///
+    /// # Panics
+    /// Panics if `alignment` is zero.
+    pub(crate) fn pack<'a, T: 'a>(
+        levels: impl IntoIterator<Item = &'a [T]>,
+        element_size: usize,
+        alignment: usize,
+        mut encode: impl FnMut(&mut Vec<u8>, &[T]),
+    ) -> Self {
+        assert!(alignment > 0, "The alignment must be positive");
+        let mut packed = Self {
+            bytes: vec![],
+            element_size,
+            levels: vec![],
+        };
+        for level in levels {
+            let start = packed.bytes.len().next_multiple_of(alignment);
+            packed.bytes.resize(start, 0);
+            encode(&mut packed.bytes, level);
+            packed.levels.push(start..packed.bytes.len());
+        }
+        packed
+    }
+
+    /// Returns the number of elements on given level.
+    /// If the level is out of bounds, returns None
+    pub fn level_len(&self, level: usize) -> Option<usize> {
+        Some(self.levels.get(level)?.len() / self.element_size)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Packs all levels as little-endian `f32` values, which every GPU API can sample.
+    /// Every level starts at a multiple of `alignment` bytes, e.g. the minimal storage buffer offset alignment.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![2u8, 4, 6, 8, 9]);
+    /// let packed = mipmap.pack_f32(16);
+    /// assert_eq!(packed.levels, [0..20, 32..44, 48..56, 64..68]);
+    /// assert_eq!(packed.bytes[32..36], 3.0f32.to_le_bytes());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `alignment` is zero.
+    pub fn pack_f32(&self, alignment: usize) -> PackedLevels {
+        let levels = (0..self.num_levels()).filter_map(|level| self.get_level(level));
+        PackedLevels::pack(levels, size_of::<f32>(), alignment, |bytes, level| {
+            for value in level {
+                bytes.extend(value.to_f32().unwrap_or(f32::NAN).to_le_bytes());
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_are_aligned() {
+        let mipmap = MipMap1D::new((0..100).map(f64::from).collect());
+        let packed = mipmap.pack_f32(256);
+
+        assert_eq!(packed.levels.len(), mipmap.num_levels());
+        for (level, range) in packed.levels.iter().enumerate() {
+            assert_eq!(range.start % 256, 0);
+            let expected = mipmap.get_level(level).unwrap();
+            assert_eq!(packed.level_len(level), Some(expected.len()));
+            let chunk = &packed.bytes[range.start..range.start + 4];
+            assert_eq!(
+                f32::from_le_bytes(chunk.try_into().unwrap()),
+                expected[0] as f32
+            );
+        }
+        assert_eq!(packed.level_len(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_tight_packing() {
+        let packed = MipMap1D::new(vec![1, 3]).pack_f32(1);
+        let expected: Vec<u8> = [1.0f32, 3.0, 2.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        assert_eq!(packed.bytes, expected);
+        assert_eq!(packed.element_size, 4);
+    }
+}
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Describes how a source is extended up to the next power of two.
+/// Then every bucket of every level covers exactly `2^level` source samples, so the levels halve exactly,
+/// bucket boundaries of consecutive segments of equal length align, and the index math of e.g. a GPU shader
+/// is just shifts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding<T> {
+    /// Repeats the last sample, so the padding does not change minima, maxima or the trend at the end.
+    LastValue,
+    /// Appends the given value, e.g. zero for silence or NaN to mark the padding as missing.
+    Fill(T),
+}
+
+impl<T: Clone> Padding<T> {
+    /// Extends `source` up to the next power of two. An empty source stays empty.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::Padding;
+    ///
+    /// let mut source = vec![2, 4, 6, 8, 9];
+    /// Padding::Fill(0).pad(&mut source);
+    /// assert_eq!(source, [2, 4, 6, 8, 9, 0, 0, 0]);
+    /// ```
+    pub fn pad(&self, source: &mut Vec<T>) {
+        let Some(last) = source.last().cloned() else {
+            return;
+        };
+        let value = match self {
+            Padding::LastValue => last,
+            Padding::Fill(value) => value.clone(),
+        };
+        source.resize(source.len().next_power_of_two(), value);
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Same as [`MipMap1D::new`], but the source is first padded up to the next power of two.
+    /// The padding is part of level `0`, so [`MipMap1D::len`] includes it.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Padding};
+    ///
+    /// let last = MipMap1D::with_padding(vec![2.0, 4.0, 6.0, 8.0, 9.0], Padding::LastValue);
+    /// assert_eq!(*last.get_level(1).unwrap(), [3.0, 7.0, 9.0, 9.0]);
+    /// assert_eq!(*last.get_level(3).unwrap(), [7.0]);
+    ///
+    /// let zeros = MipMap1D::with_padding(vec![2.0, 4.0, 6.0, 8.0, 9.0], Padding::Fill(0.0));
+    /// assert_eq!(*zeros.get_level(1).unwrap(), [3.0, 7.0, 4.5, 0.0]);
+    /// ```
+    pub fn with_padding(mut source: Vec<T>, padding: Padding<T>) -> Self {
+        padding.pad(&mut source);
+        Self::new(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_halve_exactly() {
+        for len in [1usize, 2, 3, 64, 65, 1000] {
+            let mipmap = MipMap1D::with_padding((0..len).collect(), Padding::LastValue);
+            let padded = len.next_power_of_two();
+            assert_eq!(mipmap.len(), padded);
+            for level in 0..mipmap.num_levels() {
+                assert_eq!(mipmap.level_len(level), Some(padded >> level));
+            }
+        }
+        assert!(MipMap1D::<u8>::with_padding(vec![], Padding::Fill(0)).is_empty());
+    }
+
+    #[test]
+    fn test_segments_align() {
+        // Two padded segments of the same length form the levels of their concatenation.
+        let first = MipMap1D::with_padding(vec![1.0, 2.0, 3.0], Padding::Fill(f64::NAN));
+        let second = MipMap1D::with_padding(vec![4.0, 5.0, 6.0], Padding::Fill(f64::NAN));
+        let padded: Vec<_> = [&first, &second]
+            .iter()
+            .flat_map(|mipmap| mipmap.get_level(0).unwrap().to_vec())
+            .collect();
+        let whole = MipMap1D::new(padded);
+
+        let level = whole.get_level(1).unwrap();
+        assert_eq!(level[..1], first.get_level(1).unwrap()[..1]);
+        assert_eq!(level[2..3], second.get_level(1).unwrap()[..1]);
+        assert!(level[1].is_nan() && level[3].is_nan());
+    }
+}
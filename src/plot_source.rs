@@ -0,0 +1,114 @@
+use alloc::vec::Vec;
+use core::ops::{Range, RangeInclusive};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, Viewport};
+
+/// Source of the points of a mipmap for an interactive plot such as `egui_plot`, queried every frame with the visible bounds of the plot.
+/// The crate does not depend on egui: points are returned as `[x, y]` pairs, which convert into `PlotPoints` with `PlotPoints::from`.
+/// Source sample `i` is placed at `x = origin + i * spacing`, and a bucket at the middle of its samples.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, PlotSource};
+///
+/// let mipmap = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+/// // Samples taken every 0.5 seconds.
+/// let source = PlotSource::new(&mipmap).with_spacing(0.0, 0.5);
+/// // In egui: `let bounds = plot_ui.plot_bounds(); source.points(bounds.min()[0]..=bounds.max()[0], width)`.
+/// assert_eq!(source.points(0.0..=2.0, 3), [[0.25, 3.0], [1.25, 7.0], [2.0, 9.0]]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct PlotSource<'a, T: Num + ToPrimitive + FromPrimitive> {
+    mipmap: &'a MipMap1D<T>,
+    origin: f64,
+    spacing: f64,
+}
+
+impl<'a, T: Num + ToPrimitive + FromPrimitive + Copy> PlotSource<'a, T> {
+    /// Creates a source placing sample `i` at `x = i`.
+    pub fn new(mipmap: &'a MipMap1D<T>) -> Self {
+        Self {
+            mipmap,
+            origin: 0.0,
+            spacing: 1.0,
+        }
+    }
+
+    /// Places sample `i` at `x = origin + i * spacing`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `spacing` is not positive.
+    pub fn with_spacing(self, origin: f64, spacing: f64) -> Self {
+        assert!(spacing > 0.0, "spacing must be positive");
+        Self {
+            origin,
+            spacing,
+            ..self
+        }
+    }
+
+    /// Returns the points of the finest level which displays the visible `x_range` with at most `width` points,
+    /// e.g. the width of the plot in pixels.
+    /// The range is widened to the samples just outside of it, so lines continue to the edges of the plot.
+    pub fn points(&self, x_range: RangeInclusive<f64>, width: usize) -> Vec<[f64; 2]> {
+        let range = self.source_range(&x_range);
+        let slice = self.mipmap.query(&Viewport::new(range, width));
+        let len = self.mipmap.len();
+
+        slice
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let samples = slice.source_range(index);
+                let middle = (samples.start + samples.end.min(len) - 1) as f64 / 2.0;
+                [self.origin + middle * self.spacing, value.to_f64().unwrap()]
+            })
+            .collect()
+    }
+
+    /// Returns the source samples from the last one before `x_range` to the first one after it.
+    fn source_range(&self, x_range: &RangeInclusive<f64>) -> Range<usize> {
+        let index = |x: f64| (x - self.origin) / self.spacing;
+        let start = index(*x_range.start()).max(0.0) as usize;
+        let end = match index(*x_range.end()) {
+            x if x < 0.0 => 0,
+            x => (x as usize).saturating_add(2),
+        };
+        start..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_follow_zoom() {
+        let mipmap = MipMap1D::new((0..1000).map(|x| x as f64).collect());
+        let source = PlotSource::new(&mipmap);
+
+        let overview = source.points(-100.0..=2000.0, 100);
+        assert!(overview.len() <= 100);
+        assert_eq!(overview[0], [7.5, 7.5]);
+
+        let zoomed = source.points(10.5..=20.2, 100);
+        assert_eq!(zoomed.len(), 12);
+        assert_eq!((zoomed[0], zoomed[11]), ([10.0, 10.0], [21.0, 21.0]));
+    }
+
+    #[test]
+    fn test_spacing_and_empty_bounds() {
+        let mipmap = MipMap1D::new(vec![1, 3, 5, 7]);
+        let source = PlotSource::new(&mipmap).with_spacing(100.0, 10.0);
+
+        assert_eq!(
+            source.points(100.0..=130.0, 2),
+            [[105.0, 2.0], [125.0, 6.0]]
+        );
+        assert!(source.points(200.0..=300.0, 2).is_empty());
+        assert!(source.points(0.0..=50.0, 2).is_empty());
+    }
+}
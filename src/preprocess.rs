@@ -0,0 +1,202 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::is_nan;
+use crate::stats::compare;
+use crate::{MipMap1D, MipMapConfig};
+
+/// A signal conditioning step applied to the source in place before the levels are built,
+/// see [`MipMap1D::with_preprocessing`].
+/// Except for [`Preprocess::Clamp`] and [`Preprocess::Map`], values are computed as `f64`
+/// and converted back, so integers are rounded toward zero. NaNs are ignored and kept.
+pub enum Preprocess<T> {
+    /// Subtracts the mean of the samples, e.g. the DC offset of an audio signal.
+    RemoveMean,
+    /// Subtracts the least squares line through the samples, e.g. a sensor drift.
+    Detrend,
+    /// Limits the samples to the range between the two values.
+    Clamp(T, T),
+    /// Replaces every sample by the mean of it and up to `n - 1` preceding samples.
+    MovingAverage(usize),
+    /// Applies any function to every sample.
+    Map(Box<dyn Fn(T) -> T>),
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> Preprocess<T> {
+    /// Applies the step to the samples in place.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::Preprocess;
+    ///
+    /// let mut samples = vec![3.0, 5.0, 7.0, 9.0];
+    /// Preprocess::RemoveMean.apply(&mut samples);
+    /// assert_eq!(samples, [-3.0, -1.0, 1.0, 3.0]);
+    /// Preprocess::Clamp(-2.0, 2.0).apply(&mut samples);
+    /// assert_eq!(samples, [-2.0, -1.0, 1.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a computed value can not be represented in the sample type, e.g. a negative one in an unsigned type,
+    /// or if the window of [`Preprocess::MovingAverage`] is empty.
+    pub fn apply(&self, samples: &mut [T]) {
+        match self {
+            Preprocess::RemoveMean => {
+                let (sum, count) =
+                    valid(samples).fold((0.0, 0), |(sum, count), (_, x)| (sum + x, count + 1));
+                let mean = sum / count.max(1) as f64;
+                map_f64(samples, |_, x| x - mean);
+            }
+            Preprocess::Detrend => {
+                let (slope, intercept) = fit_line(samples);
+                map_f64(samples, |index, x| x - slope * index as f64 - intercept);
+            }
+            Preprocess::Clamp(low, high) => {
+                for x in samples.iter_mut() {
+                    if compare(x, low) == Some(Ordering::Less) {
+                        *x = low.clone();
+                    } else if compare(x, high) == Some(Ordering::Greater) {
+                        *x = high.clone();
+                    }
+                }
+            }
+            Preprocess::MovingAverage(width) => {
+                assert!(*width > 0, "The window must not be empty");
+                let mut window = VecDeque::with_capacity(*width);
+                let mut sum = 0.0;
+                map_f64(samples, |_, x| {
+                    if window.len() == *width {
+                        sum -= window.pop_front().unwrap();
+                    }
+                    window.push_back(x);
+                    sum += x;
+                    sum / window.len() as f64
+                });
+            }
+            Preprocess::Map(f) => {
+                for x in samples.iter_mut() {
+                    *x = f(x.clone());
+                }
+            }
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Same as [`MipMap1D::from_config`], but `steps` are first applied to the source in place in order,
+    /// so conditioning the signal does not need another copy of it.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, MipMapConfig, Preprocess};
+    ///
+    /// // A pattern of +1 and -1 on top of a drift of 2 per sample.
+    /// let drifting = vec![1.0, 1.0, 3.0, 7.0, 9.0, 9.0, 11.0, 15.0];
+    /// let steps = [Preprocess::Detrend, Preprocess::Map(Box::new(|x: f64| x.round()))];
+    /// let mipmap = MipMap1D::with_preprocessing(drifting, &steps, MipMapConfig::default());
+    /// assert_eq!(*mipmap.get_level(0).unwrap(), [1.0, -1.0, -1.0, 1.0, 1.0, -1.0, -1.0, 1.0]);
+    /// assert_eq!(*mipmap.get_level(2).unwrap(), [0.0, 0.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Preprocess::apply`].
+    pub fn with_preprocessing(
+        mut source: Vec<T>,
+        steps: &[Preprocess<T>],
+        config: MipMapConfig,
+    ) -> Self {
+        for step in steps {
+            step.apply(&mut source);
+        }
+        Self::from_config(source, config)
+    }
+}
+
+/// Returns the indices and values of the samples which are not NaN.
+fn valid<T: ToPrimitive>(samples: &[T]) -> impl Iterator<Item = (usize, f64)> + '_ {
+    samples
+        .iter()
+        .enumerate()
+        .filter(|(_, x)| !is_nan(*x))
+        .map(|(index, x)| (index, x.to_f64().unwrap()))
+}
+
+/// Returns the slope and intercept of the least squares line through the samples over their indices.
+fn fit_line<T: ToPrimitive>(samples: &[T]) -> (f64, f64) {
+    let (mut count, mut mean_index, mut mean_value) = (0.0, 0.0, 0.0);
+    for (index, x) in valid(samples) {
+        count += 1.0;
+        mean_index += (index as f64 - mean_index) / count;
+        mean_value += (x - mean_value) / count;
+    }
+    let (covariance, variance) =
+        valid(samples).fold((0.0, 0.0), |(covariance, variance), (index, x)| {
+            let offset = index as f64 - mean_index;
+            (
+                covariance + offset * (x - mean_value),
+                variance + offset * offset,
+            )
+        });
+
+    let slope = match variance > 0.0 {
+        true => covariance / variance,
+        false => 0.0,
+    };
+    (slope, mean_value - slope * mean_index)
+}
+
+/// Replaces every sample which is not NaN by `f` of its index and value.
+fn map_f64<T: ToPrimitive + FromPrimitive>(
+    samples: &mut [T],
+    mut f: impl FnMut(usize, f64) -> f64,
+) {
+    for (index, x) in samples.iter_mut().enumerate() {
+        if !is_nan(x) {
+            let value = f(index, x.to_f64().unwrap());
+            *x = T::from_f64(value).expect("The value can not be represented in the sample type");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_in_order() {
+        let source: Vec<f64> = (0..64)
+            .map(|x| 100.0 + 0.5 * x as f64 + [1.0, -1.0][x % 2])
+            .collect();
+        let steps = [Preprocess::Detrend, Preprocess::Clamp(-0.5, 0.5)];
+        let mipmap = MipMap1D::with_preprocessing(source, &steps, MipMapConfig::default());
+
+        let level = mipmap.get_level(0).unwrap();
+        assert!(level.iter().all(|x| x.abs() == 0.5));
+        assert_eq!(*mipmap.get_level(6).unwrap(), [0.0]);
+
+        let mut smoothed = vec![2.0, 4.0, f64::NAN, 6.0, 12.0];
+        Preprocess::MovingAverage(2).apply(&mut smoothed);
+        assert_eq!(smoothed[..2], [2.0, 3.0]);
+        assert!(smoothed[2].is_nan());
+        assert_eq!(smoothed[3..], [5.0, 9.0]);
+    }
+
+    #[test]
+    fn test_integers() {
+        let mut samples = vec![10i32, 11, 15, 17];
+        Preprocess::RemoveMean.apply(&mut samples);
+        // The mean 13.25 is subtracted, rounding toward zero.
+        assert_eq!(samples, [-3, -2, 1, 3]);
+        Preprocess::Map(Box::new(|x: i32| x.abs())).apply(&mut samples);
+        assert_eq!(samples, [3, 2, 1, 3]);
+        Preprocess::<u8>::Detrend.apply(&mut []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_unsigned_panics() {
+        Preprocess::RemoveMean.apply(&mut [1u8, 2, 3]);
+    }
+}
@@ -0,0 +1,232 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// A compact quantile sketch (t-digest) of the samples in a bucket.
+/// Samples are summarized by weighted centroids, which are kept small near the tails,
+/// so extreme quantiles stay accurate.
+#[derive(Debug, Clone, PartialEq)]
+struct TDigest {
+    /// `(mean, weight)` of every centroid, sorted by mean.
+    centroids: Vec<(f64, f64)>,
+}
+
+impl TDigest {
+    /// Creates a sketch of the samples, ignoring NaNs.
+    fn of(samples: impl IntoIterator<Item = f64>, compression: f64) -> Self {
+        let mut centroids: Vec<(f64, f64)> = samples
+            .into_iter()
+            .filter(|x| !x.is_nan())
+            .map(|x| (x, 1.0))
+            .collect();
+        centroids.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self::compress(centroids, compression)
+    }
+
+    /// Merges the sketches of two adjacent buckets.
+    fn merge(&self, other: &Self, compression: f64) -> Self {
+        let mut centroids = Vec::with_capacity(self.centroids.len() + other.centroids.len());
+        let (mut a, mut b) = (
+            self.centroids.iter().peekable(),
+            other.centroids.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if x.0 <= y.0 {
+                centroids.push(*a.next().unwrap());
+            } else {
+                centroids.push(*b.next().unwrap());
+            }
+        }
+        centroids.extend(a);
+        centroids.extend(b);
+
+        Self::compress(centroids, compression)
+    }
+
+    /// Merges neighbouring sorted centroids, as long as a centroid covers at most `4 * q * (1 - q) / compression`
+    /// of the total weight around its quantile `q`.
+    fn compress(centroids: Vec<(f64, f64)>, compression: f64) -> Self {
+        let total: f64 = centroids.iter().map(|c| c.1).sum();
+        let mut compressed: Vec<(f64, f64)> = Vec::new();
+        let mut before = 0.0;
+
+        for (mean, weight) in centroids {
+            if let Some(last) = compressed.last_mut() {
+                let merged = last.1 + weight;
+                let q = (before + merged / 2.0) / total;
+                if merged <= (4.0 * total * q * (1.0 - q) / compression).max(1.0) {
+                    last.0 += (mean - last.0) * weight / merged;
+                    last.1 = merged;
+                    continue;
+                }
+                before += last.1;
+            }
+            compressed.push((mean, weight));
+        }
+
+        Self {
+            centroids: compressed,
+        }
+    }
+
+    /// Estimates the `q`-th quantile by interpolating between the centroids.
+    fn quantile(&self, q: f64) -> Option<f64> {
+        let first = self.centroids.first()?;
+        let last = self.centroids.last()?;
+        let total: f64 = self.centroids.iter().map(|c| c.1).sum();
+        let target = q.clamp(0.0, 1.0) * total;
+
+        let mut center = first.1 / 2.0;
+        if target <= center {
+            return Some(first.0);
+        }
+
+        for pair in self.centroids.windows(2) {
+            let next = center + (pair[0].1 + pair[1].1) / 2.0;
+            if target <= next {
+                let t = (target - center) / (next - center);
+                return Some(pair[0].0 + (pair[1].0 - pair[0].0) * t);
+            }
+            center = next;
+        }
+
+        Some(last.0)
+    }
+}
+
+/// Quantile sketches of the source samples in every bucket, stored from a selected coarse level upward.
+/// Unlike means, quantiles show the tail of e.g. latency data at any zoom level.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let latencies: Vec<f64> = (1..=1000).map(f64::from).collect();
+/// let mipmap = MipMap1D::new(latencies);
+/// let sketches = mipmap.quantile_sketches(4, 100.0);
+/// let top = mipmap.num_levels() - 1;
+/// let p99 = sketches.quantile(top, 0, 0.99).unwrap();
+/// assert!((p99 - 990.0).abs() < 2.0);
+/// assert_eq!(sketches.quantile(0, 0, 0.5), None);
+/// ```
+pub struct QuantileLevels {
+    min_level: usize,
+    /// Sketches of the levels starting from `min_level`.
+    data: Vec<Vec<TDigest>>,
+}
+
+impl QuantileLevels {
+    /// Returns the total number of downsampled levels, including those without sketches.
+    pub fn num_levels(&self) -> usize {
+        self.min_level + self.data.len()
+    }
+
+    /// Returns the finest level which has sketches.
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Estimates the `q`-th quantile (from `0.0` to `1.0`) of the source samples in bucket `index` of given level.
+    /// If the level has no sketches, the level or the index is out of bounds, or the bucket only has NaNs, returns None
+    pub fn quantile(&self, level: usize, index: usize, q: f64) -> Option<f64> {
+        self.data
+            .get(level.checked_sub(self.min_level)?)?
+            .get(index)?
+            .quantile(q)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Computes quantile sketches for every bucket of the levels starting from `min_level`.
+    /// Higher `compression` keeps more centroids per sketch, trading memory for accuracy;
+    /// `100.0` is a reasonable default.
+    ///
+    /// # Panics
+    /// Panics if level `0` was dropped by [`MipMap1D::prune_below`].
+    pub fn quantile_sketches(&self, min_level: usize, compression: f64) -> QuantileLevels {
+        let min_level = min_level.min(self.num_levels() - 1);
+        let source = self.source();
+        let level = source
+            .chunks(1 << min_level)
+            .map(|bucket| TDigest::of(bucket.iter().map(|x| x.to_f64().unwrap()), compression))
+            .collect();
+        let mut sketches = QuantileLevels {
+            min_level,
+            data: vec![level],
+        };
+
+        while sketches.num_levels() < self.num_levels() {
+            let current = &sketches.data[sketches.data.len() - 1];
+            let mipmap = current
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a.merge(b, compression),
+                    _ => pair[0].clone(),
+                })
+                .collect();
+            sketches.data.push(mipmap);
+        }
+
+        sketches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_buckets_are_exact() {
+        let mipmap = MipMap1D::new(vec![5, 1, 3, 2, 4]);
+        let sketches = mipmap.quantile_sketches(0, 100.0);
+
+        assert_eq!(sketches.quantile(0, 2, 0.7), Some(3.0));
+        assert_eq!(sketches.quantile(3, 0, 0.0), Some(1.0));
+        assert_eq!(sketches.quantile(3, 0, 0.5), Some(3.0));
+        assert_eq!(sketches.quantile(3, 0, 1.0), Some(5.0));
+        assert_eq!(sketches.quantile(3, 1, 0.5), None);
+        assert_eq!(sketches.quantile(4, 0, 0.5), None);
+    }
+
+    #[test]
+    fn test_tail_quantiles_of_merged_sketches() {
+        // Exponentially distributed, latency-like samples with a long tail.
+        let mut state = 1u64;
+        let source: Vec<f64> = (0..20000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let u = (state >> 11) as f64 / (1u64 << 53) as f64;
+                -(1.0 - u).ln() * 10.0
+            })
+            .collect();
+        let mut sorted = source.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        let mipmap = MipMap1D::new(source);
+        let sketches = mipmap.quantile_sketches(3, 100.0);
+        let top = sketches.num_levels() - 1;
+
+        for q in [0.5, 0.9, 0.99, 0.999] {
+            let expected = sorted[(q * sorted.len() as f64) as usize];
+            let got = sketches.quantile(top, 0, q).unwrap();
+            assert!(
+                (got - expected).abs() / expected < 0.02,
+                "q = {q}: got {got}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nan_only_bucket() {
+        let mipmap = MipMap1D::new(vec![f64::NAN, f64::NAN, 1.0]);
+        let sketches = mipmap.quantile_sketches(1, 100.0);
+
+        assert_eq!(sketches.quantile(1, 0, 0.5), None);
+        assert_eq!(sketches.quantile(1, 1, 0.5), Some(1.0));
+        assert_eq!(sketches.quantile(2, 0, 0.5), Some(1.0));
+    }
+}
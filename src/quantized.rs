@@ -0,0 +1,160 @@
+use alloc::vec::Vec;
+
+use num_traits::float::FloatCore;
+use num_traits::{Bounded, FromPrimitive, Num, NumCast, ToPrimitive, Unsigned};
+
+use crate::MipMap1D;
+
+/// A level quantized to small unsigned integers, e.g. for shipping previews over constrained links.
+/// Finite values are mapped linearly onto the codes from `0` to `Q::max_value() - 1`,
+/// infinities are clamped to the end codes, and `Q::max_value()` marks NaNs.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new(vec![-1.0, 1.0, 3.0, f64::NAN]);
+/// let level = mipmap.quantize::<u8>(0).unwrap();
+/// assert_eq!(level.codes, [0, 127, 254, 255]);
+/// assert_eq!((level.offset, level.scale), (-1.0, 4.0 / 254.0));
+/// assert_eq!(level.dequantize(2), Some(3.0));
+/// assert_eq!(level.dequantize(3), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizedLevel<Q> {
+    pub codes: Vec<Q>,
+    /// Value of code `0`.
+    pub offset: f64,
+    /// Difference between the values of consecutive codes.
+    pub scale: f64,
+}
+
+impl<Q: Num + Unsigned + Bounded + NumCast + Copy> QuantizedLevel<Q> {
+    /// Reconstructs the approximate value at `index`.
+    /// If the index is out of bounds or the value is NaN, returns None
+    pub fn dequantize(&self, index: usize) -> Option<f64> {
+        let code = *self.codes.get(index)?;
+        if code == Q::max_value() {
+            return None;
+        }
+
+        Some(self.offset + code.to_f64()? * self.scale)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Quantizes given level to codes of type `Q` (usually `u8` or `u16`),
+    /// spreading its range of finite values over all codes but the NaN marker.
+    /// If the level is out of bounds, returns None
+    pub fn quantize<Q: Num + Unsigned + Bounded + NumCast + Copy>(
+        &self,
+        level: usize,
+    ) -> Option<QuantizedLevel<Q>> {
+        let values: Vec<f64> = self
+            .get_level(level)?
+            .iter()
+            .map(|x| x.to_f64().unwrap())
+            .collect();
+        let (min, max) = values
+            .iter()
+            .filter(|x| x.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &x| {
+                (min.min(x), max.max(x))
+            });
+
+        // Codes of 64 bits and more round up to a power of two which does not fit into Q,
+        // so the steps are reduced to the next smaller float below the NaN marker.
+        let mut steps = Q::max_value().to_f64().unwrap() - 1.0;
+        while Q::from(steps).is_none_or(|code| code == Q::max_value()) {
+            steps = f64::from_bits(steps.to_bits() - 1);
+        }
+        let (offset, scale) = if min < max {
+            (min, (max - min) / steps)
+        } else {
+            // All finite values are equal, or there are none.
+            (if min.is_finite() { min } else { 0.0 }, 1.0)
+        };
+
+        let codes = values
+            .iter()
+            .map(|x| {
+                if x.is_nan() {
+                    Q::max_value()
+                } else {
+                    let code = FloatCore::round((x - offset) / scale).clamp(0.0, steps);
+                    Q::from(code).unwrap()
+                }
+            })
+            .collect();
+
+        Some(QuantizedLevel {
+            codes,
+            offset,
+            scale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_error() {
+        let source: Vec<f64> = (0..1000).map(|x| (x as f64 * 0.01).sin() * 50.0).collect();
+        let mipmap = MipMap1D::new(source);
+
+        for level in 0..mipmap.num_levels() {
+            let original = mipmap.get_level(level).unwrap();
+            let level = mipmap.quantize::<u16>(level).unwrap();
+            for (i, x) in original.iter().enumerate() {
+                assert!((level.dequantize(i).unwrap() - x).abs() <= level.scale / 2.0 + 1e-9);
+            }
+        }
+        assert_eq!(mipmap.quantize::<u8>(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_constant_and_integer_levels() {
+        let mipmap = MipMap1D::new(vec![7, 7, 7]);
+        let level = mipmap.quantize::<u8>(1).unwrap();
+        assert_eq!(level.codes, [0, 0]);
+        assert_eq!(level.dequantize(1), Some(7.0));
+
+        let empty = MipMap1D::<f32>::new(vec![]).quantize::<u8>(0).unwrap();
+        assert_eq!((empty.codes.len(), empty.offset), (0, 0.0));
+    }
+
+    #[test]
+    fn test_infinities_are_clamped() {
+        let mipmap = MipMap1D::new(vec![f64::NEG_INFINITY, 1.0, 3.0, f64::INFINITY, f64::NAN]);
+        let level = mipmap.quantize::<u8>(0).unwrap();
+        assert_eq!(level.codes, [0, 0, 254, 254, 255]);
+        assert_eq!((level.offset, level.scale), (1.0, 2.0 / 254.0));
+
+        // Means with an infinite sample are infinite themselves, so no finite values are left.
+        let level = mipmap.quantize::<u16>(1).unwrap();
+        assert_eq!(level.codes, [0, 65534, 65535]);
+        assert_eq!((level.offset, level.scale), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_wide_codes() {
+        let mipmap = MipMap1D::new(vec![0.0, 1e300, f64::NAN]);
+        let level = mipmap.quantize::<u64>(0).unwrap();
+        let last = u64::MAX - 2047;
+        assert_eq!(level.codes, [0, last, u64::MAX]);
+        assert_eq!(level.dequantize(1), Some(1e300));
+
+        let level = mipmap.quantize::<u128>(0).unwrap();
+        assert_eq!(level.codes, [0, u128::MAX - (1 << 75) + 1, u128::MAX]);
+        assert_eq!(level.dequantize(2), None);
+    }
+
+    #[test]
+    fn test_all_nan_level() {
+        let mipmap = MipMap1D::new(vec![f32::NAN; 5]);
+        let level = mipmap.quantize::<u8>(1).unwrap();
+        assert_eq!(level.codes, [255; 3]);
+        assert_eq!(level.dequantize(0), None);
+    }
+}
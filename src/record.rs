@@ -0,0 +1,316 @@
+use alloc::{vec, vec::Vec};
+use core::time::Duration;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::sum::CompensatedSum;
+use crate::MipMap1D;
+
+/// Samples which can be combined into a single coarser sample, e.g. structs downsampled field by field.
+/// [`RecordMipMap`] works with any implementation, including non-numeric samples like colors or unit-tagged quantities.
+/// [`MipMap1D`] is not generic over this trait, since its NaN policies, rounding and statistics need numeric values.
+/// It is implemented for all primitive numeric types, which are averaged like in [`MipMap1D`]
+/// (integers exactly, rounding toward zero), and for [`Duration`].
+/// With the `derive` feature, it can be derived with `#[derive(Downsample)]`,
+/// choosing a strategy for each field with `#[downsample(...)]`
+/// (one of [`mean`], [`min`], [`max`], [`first`], [`last`] or [`sum`]; defaults to `mean`).
+/// Example:
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```rust,ignore")]
+/// use mipmap_1d::{Downsample, RecordMipMap};
+///
+/// #[derive(Downsample, Clone, Debug, PartialEq)]
+/// struct Sample {
+///     temp: f32,
+///     #[downsample(max)]
+///     pressure: f32,
+/// }
+///
+/// let data = vec![
+///     Sample { temp: 20.0, pressure: 1.0 },
+///     Sample { temp: 22.0, pressure: 3.0 },
+///     Sample { temp: 24.0, pressure: 2.0 },
+/// ];
+/// let mipmap = RecordMipMap::new(data);
+/// assert_eq!(mipmap.num_levels(), 3);
+/// assert_eq!(
+///     *mipmap.get_level(1).unwrap(),
+///     [Sample { temp: 21.0, pressure: 3.0 }, Sample { temp: 24.0, pressure: 2.0 }]
+/// );
+/// ```
+pub trait Downsample: Sized {
+    /// Combines a bucket of one or two consecutive samples into one.
+    fn downsample(bucket: &[Self]) -> Self;
+}
+
+/// Creates several downsampled versions of given vector of [`Downsample`] samples.
+pub struct RecordMipMap<T: Downsample> {
+    data: Vec<Vec<T>>,
+}
+
+impl<T: Downsample> RecordMipMap<T> {
+    pub fn new(source: Vec<T>) -> Self {
+        let mut data = vec![source];
+
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1].chunks(2).map(T::downsample).collect();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the data on given level.
+    /// Level `0` returns the source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<T>> {
+        self.data.get(level)
+    }
+}
+
+macro_rules! impl_downsample_for_primitives {
+    ($($type:ty),*) => {
+        $(
+            impl Downsample for $type {
+                fn downsample(bucket: &[Self]) -> Self {
+                    MipMap1D::average(bucket)
+                }
+            }
+        )*
+    };
+}
+
+impl_downsample_for_primitives!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+/// Durations, e.g. per-request latencies, are averaged in integer nanoseconds, rounding toward zero.
+/// Example:
+/// ```rust
+/// use std::time::Duration;
+/// use mipmap_1d::RecordMipMap;
+///
+/// let latencies = vec![Duration::from_millis(10), Duration::from_millis(15), Duration::from_nanos(7)];
+/// let mipmap = RecordMipMap::new(latencies);
+/// assert_eq!(mipmap.get_level(1).unwrap()[0], Duration::from_micros(12_500));
+/// assert_eq!(mipmap.get_level(2).unwrap()[0], Duration::from_nanos(6_250_003));
+/// ```
+impl Downsample for Duration {
+    fn downsample(bucket: &[Self]) -> Self {
+        let nanos: u128 = bucket.iter().map(Duration::as_nanos).sum();
+        let mean = nanos / bucket.len() as u128;
+        Duration::new((mean / 1_000_000_000) as u64, (mean % 1_000_000_000) as u32)
+    }
+}
+
+/// Averages the values, using compensated summation so the error does not grow with the number of values.
+///
+/// # Panics
+/// Panics if there are no values.
+pub fn mean<T: Num + ToPrimitive + FromPrimitive>(values: impl IntoIterator<Item = T>) -> T {
+    let mut sum = CompensatedSum::new();
+    let mut count = 0;
+    for x in values {
+        sum.add(x.to_f64().unwrap());
+        count += 1;
+    }
+    assert!(count > 0, "Can not average an empty bucket");
+
+    T::from_f64(sum.value() / count as f64).unwrap()
+}
+
+/// Returns the smallest of the values.
+///
+/// # Panics
+/// Panics if there are no values.
+pub fn min<T: PartialOrd>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .reduce(|acc, x| if x < acc { x } else { acc })
+        .expect("Can not downsample an empty bucket")
+}
+
+/// Returns the largest of the values.
+///
+/// # Panics
+/// Panics if there are no values.
+pub fn max<T: PartialOrd>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .reduce(|acc, x| if x > acc { x } else { acc })
+        .expect("Can not downsample an empty bucket")
+}
+
+/// Returns the first of the values.
+///
+/// # Panics
+/// Panics if there are no values.
+pub fn first<T>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .next()
+        .expect("Can not downsample an empty bucket")
+}
+
+/// Returns the last of the values.
+///
+/// # Panics
+/// Panics if there are no values.
+pub fn last<T>(values: impl IntoIterator<Item = T>) -> T {
+    values
+        .into_iter()
+        .last()
+        .expect("Can not downsample an empty bucket")
+}
+
+/// Adds the values up, using compensated summation for floats.
+pub fn sum<T: Num + Copy>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().collect::<CompensatedSum<T>>().value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Range {
+        low: i32,
+        high: i32,
+    }
+
+    impl Downsample for Range {
+        fn downsample(bucket: &[Self]) -> Self {
+            Self {
+                low: min(bucket.iter().map(|x| x.low)),
+                high: max(bucket.iter().map(|x| x.high)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_strategies() {
+        let values = [3, 1, 2];
+
+        assert_eq!(mean(values), 2);
+        assert_eq!(min(values), 1);
+        assert_eq!(max(values), 3);
+        assert_eq!(first(values), 3);
+        assert_eq!(last(values), 2);
+        assert_eq!(sum(values), 6);
+    }
+
+    #[test]
+    fn test_wide_float_buckets() {
+        let values = vec![0.1f32; 1_000_000];
+
+        assert!((sum(values.iter().copied()) - 100_000.0).abs() < 0.01);
+        assert_eq!(mean(values), 0.1);
+    }
+
+    #[test]
+    fn test_record_mipmap() {
+        let data = vec![
+            Range { low: 1, high: 2 },
+            Range { low: -1, high: 0 },
+            Range { low: 5, high: 7 },
+        ];
+        let mipmap = RecordMipMap::new(data);
+
+        assert_eq!(mipmap.num_levels(), 3);
+        assert_eq!(
+            *mipmap.get_level(1).unwrap(),
+            [Range { low: -1, high: 2 }, Range { low: 5, high: 7 }]
+        );
+        assert_eq!(*mipmap.get_level(2).unwrap(), [Range { low: -1, high: 7 }]);
+        assert_eq!(mipmap.get_level(3), None);
+    }
+
+    #[test]
+    fn test_primitives_match_mipmap() {
+        let data = vec![2.5, -1.0, 7.0, 0.25, 3.0];
+        let records = RecordMipMap::new(data.clone());
+        let mipmap = MipMap1D::new(data);
+
+        assert_eq!(records.num_levels(), mipmap.num_levels());
+        for level in 0..records.num_levels() {
+            assert_eq!(
+                records.get_level(level).map(Vec::as_slice),
+                mipmap.get_level(level)
+            );
+        }
+        assert_eq!(u64::downsample(&[u64::MAX, u64::MAX - 1]), u64::MAX - 1);
+        assert_eq!(i8::downsample(&[-3, -4]), -3);
+    }
+
+    #[test]
+    fn test_durations() {
+        let long = Duration::new(u64::MAX, 999_999_999);
+        assert_eq!(Duration::downsample(&[long, long]), long);
+        assert_eq!(
+            Duration::downsample(&[Duration::from_nanos(1), Duration::from_nanos(2)]),
+            Duration::from_nanos(1)
+        );
+        assert_eq!(
+            Duration::downsample(&[Duration::new(1, 999_999_999), Duration::new(2, 1)]),
+            Duration::new(2, 0)
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    mod derive {
+        use crate::{Downsample, RecordMipMap};
+
+        #[derive(Downsample, Clone, Debug, PartialEq)]
+        pub struct Reading {
+            /// Averaged by default.
+            pub temp: f64,
+            #[downsample(min)]
+            low: i32,
+            #[downsample(max)]
+            pub(crate) high: i32,
+            #[downsample(first)]
+            start: u64,
+            #[downsample(last)]
+            end: u64,
+            #[downsample(sum)]
+            count: u32,
+        }
+
+        #[test]
+        fn test_derived_strategies() {
+            let reading = |temp, low, high, start, end| Reading {
+                temp,
+                low,
+                high,
+                start,
+                end,
+                count: 1,
+            };
+            let data = vec![
+                reading(1.0, 3, 4, 0, 1),
+                reading(2.0, 1, 2, 1, 2),
+                reading(4.0, 5, 6, 2, 3),
+            ];
+            let mipmap = RecordMipMap::new(data);
+
+            let top = &mipmap.get_level(2).unwrap()[0];
+            assert_eq!(
+                *top,
+                Reading {
+                    temp: 2.75,
+                    low: 1,
+                    high: 6,
+                    start: 0,
+                    end: 3,
+                    count: 3,
+                }
+            );
+        }
+    }
+}
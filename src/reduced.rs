@@ -0,0 +1,113 @@
+use alloc::borrow::Cow;
+use alloc::{vec, vec::Vec};
+
+use crate::{MipMap1D, NanPolicy, Rounding};
+
+/// Creates several downsampled versions of given `f64` vector, storing the levels starting from `depth` as `f32`.
+/// Coarse overview levels rarely need 15 significant digits, so this halves their memory.
+/// Levels are computed in `f64` and only rounded when stored, so the error does not accumulate across levels.
+/// Example:
+/// ```rust
+/// use mipmap_1d::ReducedPrecisionMipMap;
+///
+/// let mipmap = ReducedPrecisionMipMap::new(vec![0.1, 0.2, 0.4, 0.8, 1.6], 2);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [0.15000000000000002, 0.6000000000000001, 1.6]);
+/// assert_eq!(mipmap.get(2, 0), Some(0.375f32 as f64));
+/// assert_eq!(mipmap.get(3, 0), Some(0.98750001192092896));
+/// ```
+pub struct ReducedPrecisionMipMap {
+    /// Levels below `depth`.
+    fine: MipMap1D<f64>,
+    /// Levels starting from `depth`.
+    coarse: Vec<Vec<f32>>,
+}
+
+impl ReducedPrecisionMipMap {
+    /// Constructs the levels, storing those starting from `depth` as `f32`.
+    /// Level `0` is always stored as `f64`.
+    pub fn new(source: Vec<f64>, depth: usize) -> Self {
+        let fine =
+            MipMap1D::build_limited(source, NanPolicy::Propagate, Rounding::default(), depth);
+
+        let mut coarse = vec![];
+        let mut last = fine.get_level(fine.num_levels() - 1).unwrap().to_vec();
+        while last.len() > 1 {
+            last = last.chunks(2).map(MipMap1D::average).collect();
+            coarse.push(last.iter().map(|&x| x as f32).collect());
+        }
+
+        Self { fine, coarse }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.fine.num_levels() + self.coarse.len()
+    }
+
+    /// Returns the first level stored as `f32`, which is [`ReducedPrecisionMipMap::num_levels`] if there are none.
+    pub fn depth(&self) -> usize {
+        self.fine.num_levels()
+    }
+
+    /// Returns the data on given level, widening it to `f64` if it is stored as `f32`.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Cow<'_, [f64]>> {
+        match level.checked_sub(self.depth()) {
+            None => self.fine.get_level(level).map(Cow::Borrowed),
+            Some(level) => {
+                let level = self.coarse.get(level)?;
+                Some(Cow::Owned(level.iter().map(|&x| f64::from(x)).collect()))
+            }
+        }
+    }
+
+    /// Returns the element at `index` of given level, widened to `f64`.
+    /// If the level or the index is out of bounds, returns None
+    pub fn get(&self, level: usize, index: usize) -> Option<f64> {
+        match level.checked_sub(self.depth()) {
+            None => self.fine.get_level(level)?.get(index).copied(),
+            Some(level) => self.coarse.get(level)?.get(index).map(|&x| f64::from(x)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_full_precision() {
+        let source: Vec<f64> = (0..77).map(|x| (x as f64 * 0.3).sin() * 1e3).collect();
+        let full = MipMap1D::new(source.clone());
+        let reduced = ReducedPrecisionMipMap::new(source, 3);
+
+        assert_eq!(reduced.num_levels(), full.num_levels());
+        assert_eq!(reduced.depth(), 3);
+        for level in 0..full.num_levels() {
+            let expected = full.get_level(level).unwrap();
+            let got = reduced.get_level(level).unwrap();
+            assert_eq!(got.len(), expected.len());
+            for (&a, &b) in got.iter().zip(expected) {
+                match level < 3 {
+                    true => assert_eq!(a, b),
+                    false => assert_eq!(a, b as f32 as f64),
+                }
+            }
+            assert!(matches!(got, Cow::Borrowed(_)) == (level < 3));
+        }
+        assert_eq!(reduced.get_level(full.num_levels()), None);
+        assert_eq!(reduced.get(3, 100), None);
+    }
+
+    #[test]
+    fn test_short_sources() {
+        let single = ReducedPrecisionMipMap::new(vec![1.5], 0);
+        assert_eq!((single.num_levels(), single.depth()), (1, 1));
+        assert_eq!(single.get(0, 0), Some(1.5));
+
+        let shallow = ReducedPrecisionMipMap::new(vec![1.0, 2.0], 10);
+        assert_eq!((shallow.num_levels(), shallow.depth()), (2, 2));
+    }
+}
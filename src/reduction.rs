@@ -0,0 +1,17 @@
+/// Describes how a bucket of two elements is combined into one element of the next level.
+/// NaNs are handled according to the [`NanPolicy`](crate::NanPolicy) of the mipmap:
+/// they win with [`NanPolicy::Propagate`](crate::NanPolicy::Propagate) and are ignored otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// Averages the elements, rounding integer means according to the [`Rounding`](crate::Rounding) of the mipmap.
+    #[default]
+    Mean,
+    /// Keeps the smallest element, e.g. for the lower edge of an envelope.
+    Min,
+    /// Keeps the largest element, e.g. for the upper edge of an envelope.
+    Max,
+    /// Keeps the first element, i.e. decimates the data.
+    First,
+    /// Keeps the last element.
+    Last,
+}
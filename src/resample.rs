@@ -0,0 +1,294 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::{is_integral, is_nan, rounds_up};
+use crate::stats::compare;
+use crate::{MipMap1D, MipMapConfig, NanPolicy, Reduction, Rounding};
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Resamples the source to exactly `n` points, e.g. for a chart with a fixed number of pixels.
+    /// Every point summarizes an equal span of about `len / n` source samples with the reduction of the mipmap.
+    /// It is computed from the elements overlapping the span on the coarsest level whose elements are not longer than it,
+    /// where means are weighted by the number of source samples of an element inside the span.
+    /// If `n` is larger than the source, samples are repeated. An empty source has no points.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![0.0, 3.0, 6.0, 9.0, 12.0, 15.0]);
+    /// assert_eq!(mipmap.downsample_to(3), [1.5, 7.5, 13.5]);
+    /// // Each point covers one and a half elements of level 1.
+    /// assert_eq!(mipmap.downsample_to(2), [3.5, 11.5]);
+    /// assert_eq!(mipmap.downsample_to(4), [0.0, 4.5, 9.0, 13.5]);
+    /// assert_eq!(mipmap.downsample_to(8).len(), 8);
+    /// ```
+    pub fn downsample_to(&self, n: usize) -> Vec<T> {
+        let len = self.len();
+        if len == 0 {
+            return vec![];
+        }
+        let level = match len / n.max(1) {
+            0 => 0,
+            span => span.ilog2() as usize,
+        };
+        let level = level.clamp(self.min_level(), self.num_levels() - 1);
+        let data = self.get_level(level).unwrap();
+
+        (0..n)
+            .map(|point| {
+                // Computed in u128, so `point * len` does not overflow.
+                let bound = |point: usize| (point as u128 * len as u128 / n as u128) as usize;
+                let start = bound(point);
+                let end = bound(point + 1).max(start + 1);
+                let elements: Vec<_> = ((start >> level)..=((end - 1) >> level))
+                    .map(|index| {
+                        let from = start.max(index << level);
+                        let to = end.min((index + 1) << level);
+                        (&data[index], to - from)
+                    })
+                    .collect();
+                reduce(&elements, &self.config())
+            })
+            .collect()
+    }
+
+    /// Combines every element of given level with up to `window - 1` preceding ones with `aggregate`,
+    /// e.g. a moving average as a trend overlay at the zoom of the level.
+    /// NaNs and integer means are handled according to the configuration of the mipmap.
+    /// If the level is out of bounds or was pruned, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Reduction};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0, 1.0]);
+    /// assert_eq!(mipmap.rolling(0, 3, Reduction::Mean).unwrap(), [2.0, 3.0, 4.0, 6.0, 7.666666666666667, 6.0]);
+    /// assert_eq!(mipmap.rolling(1, 2, Reduction::Max).unwrap(), [3.0, 7.0, 7.0]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `window` is zero.
+    pub fn rolling(&self, level: usize, window: usize, aggregate: Reduction) -> Option<Vec<T>> {
+        assert!(window > 0, "window must be positive");
+        let data = self.get_level(level)?;
+        let config = MipMapConfig {
+            reduction: aggregate,
+            ..self.config()
+        };
+
+        let values = (0..data.len()).map(|end| {
+            let start = (end + 1).saturating_sub(window);
+            let elements: Vec<_> = data[start..=end].iter().map(|x| (x, 1)).collect();
+            reduce(&elements, &config)
+        });
+        Some(values.collect())
+    }
+}
+
+/// Reduces every `factor` consecutive samples of `source` to one with given reduction, without building a mipmap,
+/// e.g. for a one-shot decimation or for the levels of a custom pyramid. The last bucket may be shorter.
+/// Means are computed and rounded like those of [`MipMap1D::new`], and NaNs are propagated.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{resample, Reduction};
+///
+/// let source = [2, 4, 6, 8, 9, 1, 3];
+/// assert_eq!(resample::downsample(&source, 3, Reduction::Mean), [4, 6, 3]);
+/// assert_eq!(resample::downsample(&source, 3, Reduction::Max), [6, 9, 3]);
+/// assert_eq!(resample::downsample(&source, 2, Reduction::Mean), [3, 7, 5, 3]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `factor` is zero.
+pub fn downsample<T: Num + ToPrimitive + FromPrimitive + Clone>(
+    source: &[T],
+    factor: usize,
+    reduction: Reduction,
+) -> Vec<T> {
+    assert!(factor > 0, "factor must be positive");
+    let config = MipMapConfig {
+        reduction,
+        ..Default::default()
+    };
+
+    source
+        .chunks(factor)
+        .map(|bucket| {
+            let elements: Vec<_> = bucket.iter().map(|x| (x, 1)).collect();
+            reduce(&elements, &config)
+        })
+        .collect()
+}
+
+/// Combines elements together with the number of source samples each of them contributes.
+pub(crate) fn reduce<T: Num + ToPrimitive + FromPrimitive + Clone>(
+    elements: &[(&T, usize)],
+    config: &MipMapConfig,
+) -> T {
+    let propagate = config.nan_policy == NanPolicy::Propagate;
+    if let Some((nan, _)) = elements.iter().find(|(x, _)| propagate && is_nan::<T>(x)) {
+        return (*nan).clone();
+    }
+    let mut valid = elements.iter().filter(|(x, _)| !is_nan::<T>(x));
+    let first = elements[0].0;
+
+    let value = match config.reduction {
+        Reduction::Mean => return weighted_mean(valid, config.rounding).unwrap_or(first.clone()),
+        Reduction::Min => valid.fold(None, |min, (x, _)| extreme::<T>(min, x, Ordering::Less)),
+        Reduction::Max => valid.fold(None, |max, (x, _)| extreme::<T>(max, x, Ordering::Greater)),
+        Reduction::First => valid.next().map(|(x, _)| *x),
+        Reduction::Last => valid.next_back().map(|(x, _)| *x),
+    };
+    value.unwrap_or(first).clone()
+}
+
+/// Keeps the current extreme, unless `value` is smaller (for `Ordering::Less`) or larger.
+fn extreme<'a, T: Num + ToPrimitive>(
+    current: Option<&'a T>,
+    value: &'a T,
+    order: Ordering,
+) -> Option<&'a T> {
+    match current {
+        Some(current) if compare(value, current) != Some(order) => Some(current),
+        _ => Some(value),
+    }
+}
+
+/// Computes the mean of values weighted by their number of samples, or None if there are no samples.
+/// Integers which fit into i64 are summed exactly and rounded according to `rounding`, other values as `f64`.
+pub(crate) fn weighted_mean<'a, T: Num + ToPrimitive + FromPrimitive + 'a>(
+    elements: impl Iterator<Item = &'a (&'a T, usize)> + Clone,
+    rounding: Rounding,
+) -> Option<T> {
+    let total: usize = elements.clone().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    if is_integral::<T>() {
+        let sum: Option<i128> = elements
+            .clone()
+            .map(|(x, weight)| Some(i128::from(x.to_i64()?) * *weight as i128))
+            .sum();
+        if let Some(sum) = sum {
+            let total = total as i128;
+            let (floor, remainder) = (sum.div_euclid(total), sum.rem_euclid(total));
+            let up = match rounding {
+                _ if remainder == 0 => false,
+                Rounding::HalfEven | Rounding::HalfAway if 2 * remainder != total => {
+                    2 * remainder > total
+                }
+                _ => rounds_up(rounding, floor < 0, floor & 1 == 1),
+            };
+            return T::from_i128(floor + i128::from(up));
+        }
+    }
+
+    let sum: f64 = elements
+        .map(|(x, weight)| x.to_f64().unwrap() * *weight as f64)
+        .sum();
+    T::from_f64(sum / total as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_lengths() {
+        let mipmap = MipMap1D::new((0..1024).map(|x| x as f64).collect());
+        for n in [0, 1, 7, 100, 333, 1023, 1024, 1500] {
+            let points = mipmap.downsample_to(n);
+            assert_eq!(points.len(), n);
+            // Means of consecutive values grow with the position.
+            assert!(points.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+        assert_eq!(mipmap.downsample_to(1), [511.5]);
+        assert_eq!(mipmap.downsample_to(1024), *mipmap.get_level(0).unwrap());
+        assert!(MipMap1D::<u8>::new(vec![]).downsample_to(5).is_empty());
+    }
+
+    #[test]
+    fn test_reductions_and_rounding() {
+        let source = vec![1u8, 200, 3, 4, 250, 6, 7];
+        let max = MipMap1D::from_config(
+            source.clone(),
+            MipMapConfig {
+                reduction: Reduction::Max,
+                ..Default::default()
+            },
+        );
+        assert_eq!(max.downsample_to(3), [200, 4, 250]);
+
+        let floor = MipMap1D::with_rounding(source.clone(), Rounding::Floor);
+        let half_even = MipMap1D::with_rounding(source, Rounding::HalfEven);
+        // The last point averages the mean 128 of 250 and 6 twice and 7 once, i.e. 87.67.
+        assert_eq!(floor.downsample_to(3)[2], 87);
+        assert_eq!(half_even.downsample_to(3)[2], 88);
+    }
+
+    #[test]
+    fn test_downsample_matches_levels() {
+        let source: Vec<i32> = (0..37).map(|x| (x * 7919) % 101 - 50).collect();
+        for reduction in [
+            Reduction::Mean,
+            Reduction::Min,
+            Reduction::Max,
+            Reduction::Last,
+        ] {
+            let mut mipmap = MipMap1D::new(source.clone());
+            mipmap.rebuild_levels_with(reduction);
+            let level = downsample(&source, 2, reduction);
+            assert_eq!(level, mipmap.get_level(1).unwrap());
+            assert_eq!(
+                downsample(&level, 2, reduction),
+                mipmap.get_level(2).unwrap()
+            );
+        }
+
+        assert!(downsample(&[1.0, f64::NAN, 3.0], 3, Reduction::Max)[0].is_nan());
+        assert!(downsample::<u8>(&[], 4, Reduction::Mean).is_empty());
+    }
+
+    #[test]
+    fn test_rolling_matches_windows() {
+        let mipmap = MipMap1D::new((0..100).map(|x| ((x * 7919) % 101) as i64).collect());
+        let level = mipmap.get_level(2).unwrap();
+        let windows = |window: usize| {
+            (0..level.len()).map(move |end| &level[(end + 1).saturating_sub(window)..=end])
+        };
+
+        let means: Vec<_> = windows(4)
+            .map(|w| w.iter().sum::<i64>() / w.len() as i64)
+            .collect();
+        assert_eq!(mipmap.rolling(2, 4, Reduction::Mean).unwrap(), means);
+        let minima: Vec<_> = windows(7).map(|w| *w.iter().min().unwrap()).collect();
+        assert_eq!(mipmap.rolling(2, 7, Reduction::Min).unwrap(), minima);
+        let firsts: Vec<_> = windows(30).map(|w| w[0]).collect();
+        assert_eq!(mipmap.rolling(2, 30, Reduction::First).unwrap(), firsts);
+        assert_eq!(mipmap.rolling(2, 1, Reduction::Max).unwrap(), level);
+        assert_eq!(
+            mipmap.rolling(mipmap.num_levels(), 2, Reduction::Mean),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nan_policies() {
+        let source = vec![1.0, f64::NAN, 3.0, 5.0, 7.0];
+        assert!(MipMap1D::new(source.clone()).downsample_to(2)[0].is_nan());
+
+        let skip = MipMap1D::from_config(
+            source,
+            MipMapConfig {
+                nan_policy: NanPolicy::Skip,
+                ..Default::default()
+            },
+        );
+        assert_eq!(skip.downsample_to(2), [1.0, 5.0]);
+        // A span of only NaNs keeps them.
+        assert!(skip.downsample_to(5)[1].is_nan());
+    }
+}
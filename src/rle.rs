@@ -0,0 +1,231 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::viewport::{bucket_range, Viewport};
+use crate::MipMap1D;
+
+/// Creates several downsampled versions of given vector, storing every level as runs of equal elements.
+/// Levels are computed from the runs of the level below, so mostly constant data with occasional steps
+/// (e.g. device states or setpoints) takes space proportional to the number of steps.
+/// The levels are equal to those of [`MipMap1D`].
+/// Example:
+/// ```rust
+/// use mipmap_1d::RunLengthMipMap;
+///
+/// let mut source = vec![3; 1000];
+/// source.extend([8; 24]);
+/// let mipmap = RunLengthMipMap::new(&source);
+/// assert_eq!(mipmap.num_levels(), 11);
+/// assert_eq!(mipmap.num_runs(0), Some(2));
+/// assert_eq!(mipmap.get(3, 124), Some(3));
+/// assert_eq!(mipmap.get(3, 125), Some(8));
+/// assert_eq!(mipmap.runs(4).unwrap().collect::<Vec<_>>(), [(&3, 0..62), (&5, 62..63), (&8, 63..64)]);
+/// ```
+pub struct RunLengthMipMap<T> {
+    /// Values of the runs of every level, and the index past the end of every run.
+    levels: Vec<(Vec<T>, Vec<usize>)>,
+}
+
+/// The runs of a level which cover a viewport.
+#[derive(Debug, PartialEq)]
+pub struct RunSlice<T> {
+    /// The level the runs come from.
+    pub level: usize,
+    /// Index of the first covered element within the level.
+    pub start: usize,
+    /// Values and lengths of the runs, clipped to the viewport.
+    pub runs: Vec<(T, usize)>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> RunLengthMipMap<T> {
+    pub fn new(source: &[T]) -> Self {
+        let mut level = (vec![], vec![]);
+        for (i, value) in source.iter().enumerate() {
+            push_run(&mut level, value.clone(), i + 1);
+        }
+
+        let mut levels = vec![level];
+        while levels[levels.len() - 1]
+            .1
+            .last()
+            .is_some_and(|&len| len > 1)
+        {
+            let next = downsample(&levels[levels.len() - 1]);
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the number of elements on given level.
+    /// If the level is out of bounds, returns None
+    pub fn len(&self, level: usize) -> Option<usize> {
+        let (_, ends) = self.levels.get(level)?;
+        Some(ends.last().copied().unwrap_or(0))
+    }
+
+    /// Returns the number of runs stored for given level.
+    /// If the level is out of bounds, returns None
+    pub fn num_runs(&self, level: usize) -> Option<usize> {
+        Some(self.levels.get(level)?.0.len())
+    }
+
+    /// Returns the element at `index` of given level.
+    /// If the level or the index is out of bounds, returns None
+    pub fn get(&self, level: usize, index: usize) -> Option<T> {
+        let (values, ends) = self.levels.get(level)?;
+        values
+            .get(ends.partition_point(|&end| end <= index))
+            .cloned()
+    }
+
+    /// Iterates over the runs of given level, with the range of elements every run covers.
+    /// If the level is out of bounds, returns None
+    pub fn runs(&self, level: usize) -> Option<impl Iterator<Item = (&T, Range<usize>)>> {
+        let (values, ends) = self.levels.get(level)?;
+        let starts = core::iter::once(0).chain(ends.iter().copied());
+        Some(
+            values
+                .iter()
+                .zip(starts.zip(ends).map(|(start, &end)| start..end)),
+        )
+    }
+
+    /// Decodes the data on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<Vec<T>> {
+        let runs = self.runs(level)?;
+        Some(
+            runs.flat_map(|(value, range)| core::iter::repeat_n(value.clone(), range.len()))
+                .collect(),
+        )
+    }
+
+    /// Returns the runs of the finest level which displays the viewport with at most `viewport.max_points` points,
+    /// see [`MipMap1D::query`].
+    pub fn query(&self, viewport: &Viewport) -> RunSlice<T> {
+        let len = self.len(0).unwrap();
+        let range = viewport.range.start.min(len)..viewport.range.end.min(len);
+        let level = viewport.level_for(&range).min(self.num_levels() - 1);
+        let buckets = bucket_range(&range, level);
+
+        let runs = self
+            .runs(level)
+            .unwrap()
+            .filter_map(|(value, run)| {
+                let start = run.start.max(buckets.start);
+                let end = run.end.min(buckets.end);
+                (start < end).then(|| (value.clone(), end - start))
+            })
+            .collect();
+        RunSlice {
+            level,
+            start: buckets.start,
+            runs,
+        }
+    }
+}
+
+/// Appends a run ending at `end`, extending the last run if it has the same value.
+fn push_run<T: PartialEq>((values, ends): &mut (Vec<T>, Vec<usize>), value: T, end: usize) {
+    if values.last() == Some(&value) {
+        *ends.last_mut().unwrap() = end;
+    } else {
+        values.push(value);
+        ends.push(end);
+    }
+}
+
+/// Computes the runs of the next level, handling all pairs inside a run at once.
+fn downsample<T: Num + ToPrimitive + FromPrimitive + Clone>(
+    (values, ends): &(Vec<T>, Vec<usize>),
+) -> (Vec<T>, Vec<usize>) {
+    let len = ends.last().copied().unwrap_or(0);
+    let mut next = (vec![], vec![]);
+    let (mut run, mut position) = (0, 0);
+
+    while position < len {
+        while ends[run] <= position {
+            run += 1;
+        }
+        let same = (ends[run] - position) / 2;
+        if same > 0 {
+            let value = MipMap1D::average(&[values[run].clone(), values[run].clone()]);
+            push_run(&mut next, value, (position >> 1) + same);
+            position += 2 * same;
+        } else {
+            let pair = match position + 1 < len {
+                true => vec![values[run].clone(), values[run + 1].clone()],
+                false => vec![values[run].clone()],
+            };
+            push_run(&mut next, MipMap1D::average(&pair), (position >> 1) + 1);
+            position += 2;
+        }
+    }
+
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_mipmap() {
+        let source: Vec<i32> = (0..300).map(|x| [0, 0, 7, -3][(x / 13) % 4]).collect();
+        let mipmap = MipMap1D::new(source.clone());
+        let rle = RunLengthMipMap::new(&source);
+
+        assert_eq!(rle.num_levels(), mipmap.num_levels());
+        for level in 0..mipmap.num_levels() {
+            let expected = mipmap.get_level(level).unwrap();
+            assert_eq!(rle.get_level(level).unwrap(), expected);
+            assert_eq!(rle.len(level), Some(expected.len()));
+            for (index, value) in expected.iter().enumerate() {
+                assert_eq!(rle.get(level, index), Some(*value));
+            }
+            assert_eq!(rle.get(level, expected.len()), None);
+        }
+        assert_eq!(rle.num_runs(0), Some(18));
+        assert_eq!(rle.get_level(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_query_matches_mipmap() {
+        let source: Vec<f64> = (0..100).map(|x| if x < 60 { 1.0 } else { 4.0 }).collect();
+        let mipmap = MipMap1D::new(source.clone());
+        let rle = RunLengthMipMap::new(&source);
+
+        for viewport in [
+            Viewport::new(10..90, 8),
+            Viewport::new(0..200, 1),
+            Viewport::new(5..5, 3),
+        ] {
+            let expected = mipmap.query(&viewport);
+            let slice = rle.query(&viewport);
+            assert_eq!((slice.level, slice.start), (expected.level, expected.start));
+            let decoded: Vec<f64> = slice
+                .runs
+                .iter()
+                .flat_map(|&(value, len)| std::iter::repeat_n(value, len))
+                .collect();
+            assert_eq!(decoded, expected.data);
+        }
+    }
+
+    #[test]
+    fn test_short_sources() {
+        let empty = RunLengthMipMap::<u8>::new(&[]);
+        assert_eq!((empty.num_levels(), empty.len(0)), (1, Some(0)));
+        let single = RunLengthMipMap::new(&[5u8]);
+        assert_eq!((single.num_levels(), single.get(0, 0)), (1, Some(5)));
+    }
+}
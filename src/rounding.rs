@@ -0,0 +1,20 @@
+/// Describes how integer means which fall between two integers are rounded.
+/// The mean of two integers is either an integer or exactly halfway between two of them,
+/// so the half-way modes only differ in how that tie is broken.
+/// Float means are never rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    /// Rounds toward zero, e.g. `2.5` to `2` and `-2.5` to `-2`.
+    /// This is the behaviour of integer division.
+    #[default]
+    TowardZero,
+    /// Rounds toward negative infinity, e.g. `2.5` to `2` and `-2.5` to `-3`.
+    Floor,
+    /// Rounds toward positive infinity, e.g. `2.5` to `3` and `-2.5` to `-2`.
+    Ceil,
+    /// Rounds ties to the even neighbour, e.g. `2.5` to `2` and `3.5` to `4`.
+    /// Repeated averaging does not drift in either direction.
+    HalfEven,
+    /// Rounds ties away from zero, e.g. `2.5` to `3` and `-2.5` to `-3`.
+    HalfAway,
+}
@@ -0,0 +1,138 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::viewport::bucket_range;
+use crate::{BucketStats, MipMap1D, StatsMipMap1D, Viewport};
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Returns the `(x, y)` coordinates of the finest level which displays the source `range`
+    /// with at most `resolution` points, e.g. for plotters' `LineSeries::new`.
+    /// The crate does not depend on plotters, so the coordinates are plain tuples.
+    /// The x coordinate of a bucket is the middle of the source indices it covers.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0, 9.0]);
+    /// let series: Vec<(f64, f64)> = mipmap.series(0..5, 3).collect();
+    /// assert_eq!(series, [(0.5, 3.0), (2.5, 7.0), (4.0, 9.0)]);
+    /// ```
+    pub fn series(
+        &self,
+        range: Range<usize>,
+        resolution: usize,
+    ) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let len = self.len();
+        let slice = self.query(&Viewport::new(range, resolution));
+
+        slice.data.iter().enumerate().map(move |(index, value)| {
+            let samples = slice.source_range(index);
+            (middle(&samples, len), value.to_f64().unwrap())
+        })
+    }
+}
+
+impl<T: Num + ToPrimitive + Copy> StatsMipMap1D<T> {
+    /// Same as [`MipMap1D::series`], but returns the means of the buckets.
+    /// Buckets without valid samples are skipped.
+    pub fn mean_series(
+        &self,
+        range: Range<usize>,
+        resolution: usize,
+    ) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.buckets(range, resolution)
+            .map(|(x, stats)| (x, stats.mean))
+    }
+
+    /// Returns the outline of the envelope between the minima and maxima of the buckets,
+    /// e.g. for plotters' `Polygon::new`: the maxima from left to right, then the minima from right to left.
+    /// Buckets without valid samples are skipped.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::StatsMipMap1D;
+    ///
+    /// let mipmap = StatsMipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// let outline = mipmap.envelope_series(0..5, 3);
+    /// assert_eq!(outline[..3], [(0.5, 4.0), (2.5, 8.0), (4.0, 9.0)]);
+    /// assert_eq!(outline[3..], [(4.0, 9.0), (2.5, 6.0), (0.5, 2.0)]);
+    /// ```
+    pub fn envelope_series(&self, range: Range<usize>, resolution: usize) -> Vec<(f64, f64)> {
+        let buckets: Vec<_> = self.buckets(range, resolution).collect();
+        let maxima = buckets
+            .iter()
+            .map(|(x, stats)| (*x, stats.max.to_f64().unwrap()));
+        let minima = buckets
+            .iter()
+            .rev()
+            .map(|(x, stats)| (*x, stats.min.to_f64().unwrap()));
+        maxima.chain(minima).collect()
+    }
+
+    /// Returns the x coordinates and the valid buckets of the finest level which displays the source `range`
+    /// with at most `resolution` buckets.
+    fn buckets(
+        &self,
+        range: Range<usize>,
+        resolution: usize,
+    ) -> impl Iterator<Item = (f64, BucketStats<T>)> + '_ {
+        let len = self.get_level(0).unwrap().len();
+        let range = range.start.min(len)..range.end.min(len);
+        let level = Viewport::new(range.clone(), resolution)
+            .level_for(&range)
+            .min(self.num_levels() - 1);
+        let buckets = bucket_range(&range, level);
+
+        self.get_level(level).unwrap()[buckets.clone()]
+            .iter()
+            .zip(buckets)
+            .filter(|(stats, _)| stats.count > 0)
+            .map(move |(stats, bucket)| {
+                let samples = (bucket << level)..((bucket + 1) << level);
+                (middle(&samples, len), *stats)
+            })
+    }
+}
+
+/// Returns the middle of the source indices of a bucket, of which only those below `len` exist.
+fn middle(samples: &Range<usize>, len: usize) -> f64 {
+    (samples.start + samples.end.min(len) - 1) as f64 / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_resolution() {
+        let mipmap = MipMap1D::new((0..100).map(|x| x as f64).collect());
+        let series: Vec<_> = mipmap.series(10..90, 20).collect();
+
+        assert_eq!(series.len(), 11);
+        // Means of consecutive values lie on the diagonal.
+        assert!(series.iter().all(|(x, y)| x == y));
+        assert_eq!(mipmap.series(200..300, 20).count(), 0);
+    }
+
+    #[test]
+    fn test_envelope_skips_empty_buckets() {
+        let mipmap = StatsMipMap1D::new(vec![1.0, 3.0, f64::NAN, f64::NAN, -1.0, 5.0]);
+
+        let means: Vec<_> = mipmap.mean_series(0..6, 3).collect();
+        assert_eq!(means, [(0.5, 2.0), (4.5, 2.0)]);
+        assert_eq!(
+            mipmap.envelope_series(0..6, 3),
+            [(0.5, 3.0), (4.5, 5.0), (4.5, -1.0), (0.5, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_clone_only_samples() {
+        use crate::fixtures::CloneInt;
+
+        let mipmap = MipMap1D::new([2, 4, 6, 8, 9].into_iter().map(CloneInt).collect());
+        let series: Vec<_> = mipmap.series(0..5, 3).collect();
+        assert_eq!(series, [(0.5, 3.0), (2.5, 7.0), (4.0, 9.0)]);
+    }
+}
@@ -0,0 +1,160 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use num_traits::{Float, FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, NanPolicy, Rounding};
+
+/// Maintains an independent mipmap for every key of `(key, value)` records,
+/// e.g. one series per host of a dashboard.
+/// All mipmaps share the same configuration.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMapSet;
+///
+/// let records = vec![("a", 2), ("b", 1), ("a", 4), ("a", 6), ("b", 3)];
+/// let set: MipMapSet<_, _> = records.into_iter().collect();
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(*set.get("a").unwrap().get_level(1).unwrap(), [3, 6]);
+///
+/// let levels = set.levels_within(["a", "b", "c"], 1);
+/// assert_eq!(levels, [Some(&[4][..]), Some(&[2][..]), None]);
+/// ```
+pub struct MipMapSet<K, T: Num + ToPrimitive + FromPrimitive> {
+    mipmaps: HashMap<K, MipMap1D<T>>,
+    nan_policy: NanPolicy,
+}
+
+impl<K: Eq + Hash, T: Num + ToPrimitive + FromPrimitive + Copy> MipMapSet<K, T> {
+    pub fn new() -> Self {
+        Self {
+            mipmaps: HashMap::new(),
+            nan_policy: NanPolicy::Propagate,
+        }
+    }
+
+    /// Appends a value to the mipmap of given key, creating the mipmap if needed.
+    pub fn push(&mut self, key: K, value: T) {
+        let nan_policy = self.nan_policy;
+        self.mipmaps
+            .entry(key)
+            .or_insert_with(|| MipMap1D::build(vec![], nan_policy, Rounding::default()))
+            .push(value);
+    }
+
+    /// Returns the mipmap of given key.
+    /// If there are no records with this key, returns None
+    pub fn get<Q>(&self, key: &Q) -> Option<&MipMap1D<T>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.mipmaps.get(key)
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.mipmaps.len()
+    }
+
+    /// Returns true if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.mipmaps.is_empty()
+    }
+
+    /// Iterates over all keys and their mipmaps, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &MipMap1D<T>)> {
+        self.mipmaps.iter()
+    }
+
+    /// Returns the finest level with at most `max_points` elements for each of given keys, in the same order.
+    /// Keys without records get None
+    pub fn levels_within<'a, Q>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Q>,
+        max_points: usize,
+    ) -> Vec<Option<&[T]>>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized + 'a,
+    {
+        keys.into_iter()
+            .map(|key| {
+                let mipmap = self.mipmaps.get(key)?;
+                mipmap.get_level(mipmap.level_within(max_points))
+            })
+            .collect()
+    }
+}
+
+impl<K: Eq + Hash, T: Float + FromPrimitive> MipMapSet<K, T> {
+    /// Same as [`MipMapSet::new`], but NaN values of every mipmap are handled according to `policy`.
+    pub fn with_nan_policy(policy: NanPolicy) -> Self {
+        Self {
+            mipmaps: HashMap::new(),
+            nan_policy: policy,
+        }
+    }
+}
+
+impl<K: Eq + Hash, T: Num + ToPrimitive + FromPrimitive + Copy> Default for MipMapSet<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, T: Num + ToPrimitive + FromPrimitive + Copy> Extend<(K, T)> for MipMapSet<K, T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, records: I) {
+        for (key, value) in records {
+            self.push(key, value);
+        }
+    }
+}
+
+impl<K: Eq + Hash, T: Num + ToPrimitive + FromPrimitive + Copy> FromIterator<(K, T)>
+    for MipMapSet<K, T>
+{
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(records: I) -> Self {
+        let mut set = Self::new();
+        set.extend(records);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_independent_mipmaps() {
+        let mut set = MipMapSet::new();
+        set.extend([(1, 2), (2, 9), (1, 4), (1, 6), (1, 8), (1, 9)]);
+
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.get(&1).unwrap().num_levels(), 4);
+        assert_eq!(*set.get(&1).unwrap().get_level(1).unwrap(), [3, 7, 9]);
+        assert_eq!(*set.get(&2).unwrap().get_level(0).unwrap(), [9]);
+        assert!(set.get(&3).is_none());
+    }
+
+    #[test]
+    fn test_shared_nan_policy() {
+        let mut set = MipMapSet::with_nan_policy(NanPolicy::Skip);
+        set.extend([("a", 1.0), ("b", f64::NAN), ("a", f64::NAN), ("b", 3.0)]);
+
+        assert_eq!(*set.get("a").unwrap().get_level(1).unwrap(), [1.0]);
+        assert_eq!(*set.get("b").unwrap().get_level(1).unwrap(), [3.0]);
+    }
+
+    #[test]
+    fn test_levels_within() {
+        let set: MipMapSet<String, i32> = [("a".to_string(), 1), ("a".to_string(), 3)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.levels_within(["a"], 2), [Some(&[1, 3][..])]);
+        assert_eq!(set.levels_within(["a"], 1), [Some(&[2][..])]);
+        assert!(MipMapSet::<&str, i32>::default().is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, Range};
+
+/// A level of a mipmap, which shares the buffer of the mipmap instead of borrowing it.
+/// Returned by [`MipMap1D::level_arc`](crate::MipMap1D::level_arc).
+/// The level does not change when the mipmap is modified afterwards, and cloning it only copies a pointer.
+#[derive(Clone)]
+pub struct SharedLevel<T> {
+    buffer: Arc<Vec<T>>,
+    range: Range<usize>,
+}
+
+impl<T> SharedLevel<T> {
+    pub(crate) fn new(buffer: Arc<Vec<T>>, range: Range<usize>) -> Self {
+        Self { buffer, range }
+    }
+}
+
+impl<T> Deref for SharedLevel<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.buffer[self.range.clone()]
+    }
+}
+
+impl<T> AsRef<[T]> for SharedLevel<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T: PartialEq> PartialEq for SharedLevel<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SharedLevel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_levels_are_shared_across_threads() {
+        let mipmap = MipMap1D::new((0..1000).collect::<Vec<u32>>());
+        let copy = mipmap.clone();
+        let level = mipmap.level_arc(3).unwrap();
+
+        let sum = std::thread::spawn(move || level.iter().sum::<u32>())
+            .join()
+            .unwrap();
+        assert_eq!(sum, copy.get_level(3).unwrap().iter().sum::<u32>());
+        assert!(mipmap.level_arc(mipmap.num_levels()).is_none());
+    }
+
+    #[test]
+    fn test_clone_is_detached_on_push() {
+        let mut mipmap = MipMap1D::new(vec![1.0, 3.0]);
+        let copy = mipmap.clone();
+        mipmap.push(5.0);
+
+        assert_eq!(*copy.get_level(1).unwrap(), [2.0]);
+        assert_eq!(*mipmap.get_level(1).unwrap(), [2.0, 5.0]);
+        assert_eq!(
+            copy.level_arc(0).unwrap(),
+            copy.level_arc(0).unwrap().clone()
+        );
+    }
+}
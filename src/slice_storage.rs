@@ -0,0 +1,147 @@
+use alloc::{vec, vec::Vec};
+
+use crate::LevelStorage;
+
+/// A [`LevelStorage`] which places all levels in a caller-provided buffer instead of the global heap,
+/// e.g. in a frame arena or a static pool. Only the bookkeeping of a few words per level is allocated.
+/// The buffer must have room for all levels, see [`SliceStorage::required_len`].
+/// Appending to a level which is not the coarsest one moves the coarser levels by one element.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, SliceStorage};
+///
+/// let mut pool = [0; 64];
+/// let source = vec![2, 4, 6, 8, 9];
+/// let len = SliceStorage::<i32>::required_len(source.len());
+/// let mipmap = MipMap1D::with_storage(source, SliceStorage::new(&mut pool[..len]));
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [3, 7, 9]);
+/// drop(mipmap);
+/// assert_eq!(pool[..len], [2, 4, 6, 8, 9, 3, 7, 9, 5, 9, 7]);
+/// ```
+pub struct SliceStorage<'a, T> {
+    buffer: &'a mut [T],
+    /// Start of every level in the buffer, followed by the end of the coarsest level.
+    offsets: Vec<usize>,
+}
+
+impl<'a, T> SliceStorage<'a, T> {
+    /// Creates an empty storage using `buffer`, whose initial contents are overwritten.
+    pub fn new(buffer: &'a mut [T]) -> Self {
+        Self {
+            buffer,
+            offsets: vec![0],
+        }
+    }
+
+    /// Returns the number of elements of all levels of a mipmap of `source_len` elements.
+    pub fn required_len(source_len: usize) -> usize {
+        let mut len = source_len;
+        let mut total = len;
+        while len > 1 {
+            len = len.div_ceil(2);
+            total += len;
+        }
+        total
+    }
+
+    /// Returns the number of elements used by all levels.
+    pub fn used(&self) -> usize {
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    /// Reserves room for one more element at `position`, moving the elements after it.
+    ///
+    /// # Panics
+    /// Panics if the buffer is full.
+    fn insert(&mut self, position: usize, value: T) {
+        let used = self.used();
+        assert!(used < self.buffer.len(), "The buffer is full");
+        self.buffer[position..=used].rotate_right(1);
+        self.buffer[position] = value;
+    }
+}
+
+impl<T> LevelStorage<T> for SliceStorage<'_, T> {
+    fn num_levels(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn level(&self, level: usize) -> Option<&[T]> {
+        let end = *self.offsets.get(level + 1)?;
+        Some(&self.buffer[self.offsets[level]..end])
+    }
+
+    fn level_mut(&mut self, level: usize) -> Option<&mut [T]> {
+        let end = *self.offsets.get(level + 1)?;
+        Some(&mut self.buffer[self.offsets[level]..end])
+    }
+
+    /// # Panics
+    /// Panics if the buffer is full.
+    fn push_level(&mut self, level: Vec<T>) {
+        let used = self.used();
+        assert!(
+            used + level.len() <= self.buffer.len(),
+            "The buffer is full"
+        );
+        let len = level.len();
+        for (slot, value) in self.buffer[used..].iter_mut().zip(level) {
+            *slot = value;
+        }
+        self.offsets.push(used + len);
+    }
+
+    /// # Panics
+    /// Panics if the buffer is full.
+    fn push(&mut self, level: usize, value: T) {
+        self.insert(self.offsets[level + 1], value);
+        for offset in &mut self.offsets[level + 1..] {
+            *offset += 1;
+        }
+    }
+
+    fn truncate(&mut self, levels: usize) {
+        self.offsets.truncate(levels + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_matches_default_storage() {
+        let source: Vec<f32> = (0..45).map(|x| x as f32 * 0.5).collect();
+        let mut pool = vec![0.0; SliceStorage::<f32>::required_len(60)];
+        let mut default = MipMap1D::new(source.clone());
+        let mut pooled = MipMap1D::with_storage(source, SliceStorage::new(&mut pool));
+
+        for x in 0..15 {
+            default.push(x as f32);
+            pooled.push(x as f32);
+        }
+        assert_eq!(pooled.num_levels(), default.num_levels());
+        for level in 0..default.num_levels() {
+            assert_eq!(pooled.get_level(level), default.get_level(level));
+        }
+    }
+
+    #[test]
+    fn test_required_len() {
+        assert_eq!(SliceStorage::<u8>::required_len(0), 0);
+        assert_eq!(SliceStorage::<u8>::required_len(1), 1);
+        assert_eq!(SliceStorage::<u8>::required_len(8), 15);
+
+        let mut pool = [0u8; 15];
+        let mipmap = MipMap1D::with_storage(vec![1; 8], SliceStorage::new(&mut pool));
+        assert_eq!(mipmap.num_levels(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_full_buffer_panics() {
+        let mut pool = [0u8; 14];
+        MipMap1D::with_storage(vec![1; 8], SliceStorage::new(&mut pool));
+    }
+}
@@ -0,0 +1,77 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::{MipMap1D, Viewport};
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Returns the bars of a terminal sparkline, e.g. for ratatui's `Sparkline::data`,
+    /// which displays the source `range` in at most `width` columns.
+    /// The crate does not depend on ratatui, so the bars are returned as plain numbers.
+    /// The level is chosen from `width`, so it follows the terminal as it is resized.
+    /// Values are scaled linearly so that the lowest one becomes `0` and the highest one `max`;
+    /// if they are all the same, they become `max`. NaN values become `0`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::new(vec![-1.0, 1.0, 3.0, 5.0, 6.0, 8.0, 9.0, 11.0]);
+    /// assert_eq!(mipmap.sparkline(0..8, 4, 100), [0, 40, 70, 100]);
+    /// assert_eq!(mipmap.sparkline(0..8, 8, 12), [0, 2, 4, 6, 7, 9, 10, 12]);
+    /// ```
+    pub fn sparkline(&self, range: Range<usize>, width: usize, max: u64) -> Vec<u64> {
+        let values: Vec<f64> = self
+            .query(&Viewport::new(range, width))
+            .data
+            .iter()
+            .map(|value| value.to_f64().unwrap())
+            .collect();
+        let valid = values.iter().filter(|x| !x.is_nan());
+        let low = valid.clone().fold(f64::INFINITY, |a, &b| a.min(b));
+        let high = valid.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+
+        values
+            .iter()
+            .map(|&x| match x {
+                x if x.is_nan() => 0,
+                _ if low == high => max,
+                x => ((x - low) / (high - low) * max as f64 + 0.5) as u64,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparkline_follows_width() {
+        let mipmap = MipMap1D::new((0..1000u32).collect());
+
+        for width in [80, 120, 300] {
+            let bars = mipmap.sparkline(0..1000, width, 8);
+            assert!(bars.len() <= width && bars.len() > width / 2);
+            assert_eq!((bars[0], *bars.last().unwrap()), (0, 8));
+        }
+    }
+
+    #[test]
+    fn test_constant_and_nan_values() {
+        let constant = MipMap1D::new(vec![3, 3, 3]);
+        assert_eq!(constant.sparkline(0..3, 10, 5), [5, 5, 5]);
+
+        let gaps = MipMap1D::new(vec![1.0, f64::NAN, 3.0]);
+        assert_eq!(gaps.sparkline(0..3, 10, 4), [0, 0, 4]);
+        assert!(gaps.sparkline(5..9, 10, 4).is_empty());
+    }
+
+    #[test]
+    fn test_clone_only_samples() {
+        use crate::fixtures::CloneInt;
+
+        let mipmap = MipMap1D::new([1, 3, 5, 7].into_iter().map(CloneInt).collect());
+        assert_eq!(mipmap.sparkline(0..4, 4, 6), [0, 2, 4, 6]);
+    }
+}
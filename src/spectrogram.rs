@@ -0,0 +1,196 @@
+use std::f64::consts::PI;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Coarse frequency summaries of the source samples in every bucket of selected levels,
+/// e.g. a spectrogram strip below a zoomed-out waveform.
+/// Every bucket stores the energies of a few equally wide frequency bands from DC to the Nyquist frequency,
+/// computed with an FFT of its samples (the last bucket of a level is zero-padded).
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// // Alternates every sample, i.e. the highest possible frequency.
+/// let data: Vec<f64> = (0..64).map(|x| if x % 2 == 0 { 1.0 } else { -1.0 }).collect();
+/// let mipmap = MipMap1D::new(data);
+/// let spectrogram = mipmap.spectrogram(&[4], 4);
+/// let bands = spectrogram.bands(4, 0).unwrap();
+/// assert!(bands[3] > 0.0);
+/// assert!(bands[..3].iter().all(|&energy| energy < 1e-9));
+/// assert_eq!(spectrogram.bands(3, 0), None);
+/// ```
+pub struct SpectrogramLevels {
+    bands: usize,
+    /// Selected levels together with their energies, stored as `energies[index * bands + band]`.
+    data: Vec<(usize, Vec<f64>)>,
+}
+
+impl SpectrogramLevels {
+    /// Returns the number of bands of every bucket.
+    pub fn num_bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Returns the levels which have band energies.
+    pub fn levels(&self) -> impl Iterator<Item = usize> + '_ {
+        self.data.iter().map(|(level, _)| *level)
+    }
+
+    /// Returns the band energies of bucket `index` on given level, from the lowest frequencies to the highest.
+    /// If the level was not selected, or the index is out of bounds, returns None
+    pub fn bands(&self, level: usize, index: usize) -> Option<&[f64]> {
+        let (_, energies) = self.data.iter().find(|(selected, _)| *selected == level)?;
+        energies.get(index * self.bands..(index + 1) * self.bands)
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Computes `bands` band energies for every bucket of given levels.
+    /// Levels out of bounds are ignored.
+    /// The energy of a band is the sum of the squared magnitudes of its FFT bins, divided by the bucket size.
+    ///
+    /// # Panics
+    /// Panics if `bands` is zero.
+    pub fn spectrogram(&self, levels: &[usize], bands: usize) -> SpectrogramLevels {
+        assert!(bands > 0, "A spectrogram must have at least one band");
+
+        let source: Vec<f64> = self
+            .get_level(0)
+            .unwrap()
+            .iter()
+            .map(|x| x.to_f64().unwrap())
+            .collect();
+
+        let data = levels
+            .iter()
+            .filter(|&&level| level < self.num_levels())
+            .map(|&level| {
+                let size = 1 << level;
+                let energies = source
+                    .chunks(size)
+                    .flat_map(|bucket| band_energies(bucket, size, bands))
+                    .collect();
+                (level, energies)
+            })
+            .collect();
+
+        SpectrogramLevels { bands, data }
+    }
+}
+
+/// Computes the band energies of a bucket, zero-padded to `size` samples.
+fn band_energies(bucket: &[f64], size: usize, bands: usize) -> Vec<f64> {
+    let mut re = bucket.to_vec();
+    re.resize(size, 0.0);
+    let mut im = vec![0.0; size];
+    fft(&mut re, &mut im);
+
+    let bins = size / 2 + 1;
+    let mut energies = vec![0.0; bands];
+    for bin in 0..bins {
+        energies[bin * bands / bins] += (re[bin] * re[bin] + im[bin] * im[bin]) / size as f64;
+    }
+
+    energies
+}
+
+/// Computes the discrete Fourier transform in place with the iterative radix-2 algorithm.
+/// The length must be a power of two.
+fn fft(re: &mut [f64], im: &mut [f64]) {
+    let n = re.len();
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let signal = [1.0, 2.0, -1.0, 0.5, 3.0, 0.0, -2.0, 1.0];
+        let (mut re, mut im) = (signal.to_vec(), vec![0.0; 8]);
+        fft(&mut re, &mut im);
+
+        for k in 0..8 {
+            let (mut dft_re, mut dft_im) = (0.0, 0.0);
+            for (t, x) in signal.iter().enumerate() {
+                let angle = -2.0 * PI * (k * t) as f64 / 8.0;
+                dft_re += x * angle.cos();
+                dft_im += x * angle.sin();
+            }
+            assert!((re[k] - dft_re).abs() < 1e-9);
+            assert!((im[k] - dft_im).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_tone_lands_in_its_band() {
+        // A tone at 3/16 of the sample rate, i.e. in the second quarter of the way to the Nyquist frequency.
+        let data: Vec<f64> = (0..256)
+            .map(|x| (2.0 * PI * x as f64 * 3.0 / 16.0).sin())
+            .collect();
+        let mipmap = MipMap1D::new(data);
+        let spectrogram = mipmap.spectrogram(&[5, 8, 20], 4);
+
+        assert_eq!(spectrogram.levels().collect::<Vec<_>>(), [5, 8]);
+        for level in [5, 8] {
+            let bands = spectrogram.bands(level, 0).unwrap();
+            let loudest = (0..4).max_by(|&a, &b| bands[a].total_cmp(&bands[b]));
+            assert_eq!(loudest, Some(1));
+        }
+        assert_eq!(spectrogram.bands(5, 8), None);
+    }
+
+    #[test]
+    fn test_energy_is_preserved() {
+        // By Parseval's theorem, the energies of a real signal add up to its energy
+        // once the bins mirrored above the Nyquist frequency are included.
+        let data = vec![1.0, -2.0, 0.5, 0.0, 3.0];
+        let mipmap = MipMap1D::new(data.clone());
+        let spectrogram = mipmap.spectrogram(&[3], 1);
+
+        let (mut re, mut im) = (data.clone(), vec![0.0; 3]);
+        re.resize(8, 0.0);
+        im.resize(8, 0.0);
+        fft(&mut re, &mut im);
+        let half: f64 = (0..=4).map(|k| re[k] * re[k] + im[k] * im[k]).sum::<f64>() / 8.0;
+        assert!((spectrogram.bands(3, 0).unwrap()[0] - half).abs() < 1e-9);
+
+        let energy: f64 = data.iter().map(|x| x * x).sum();
+        let full: f64 = (0..8).map(|k| re[k] * re[k] + im[k] * im[k]).sum::<f64>() / 8.0;
+        assert!((full - energy).abs() < 1e-9);
+    }
+}
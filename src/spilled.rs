@@ -0,0 +1,309 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use num_traits::Num;
+
+use crate::archive::Header;
+use crate::bytes::{as_bytes_mut, Pod};
+use crate::viewport::{bucket_range, LevelSlice, Viewport};
+use crate::ArchiveError;
+
+/// Number of elements read from the file at once.
+const PAGE_LEN: usize = 4096;
+
+/// Levels of a mipmap saved with [`MipMap1D::save`](crate::MipMap1D::save), for datasets larger than memory.
+/// Coarse levels are kept in memory, so zooming out never waits for the disk,
+/// while fine levels are read from the file in pages of 4096 elements when they are accessed.
+/// The most recently read pages are cached.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, SpilledMipMap, Viewport};
+///
+/// let path = std::env::temp_dir().join("mipmap-1d-spilled-doctest.bin");
+/// MipMap1D::new((0..100_000).collect::<Vec<u32>>()).save(&path).unwrap();
+///
+/// // Keep levels of up to 200 elements in memory, and cache up to 8 pages of the others.
+/// let spilled = SpilledMipMap::<u32>::open(&path, 200, 8).unwrap();
+/// assert!(!spilled.is_resident(0));
+/// let viewport = Viewport::new(0..100_000, 500);
+/// // Shown at once, while the finer level is being read.
+/// assert_eq!(spilled.preview(&viewport).data.len(), 196);
+/// assert_eq!(spilled.query(&viewport).unwrap().data.len(), 391);
+/// assert_eq!(spilled.read(0, 7..10).unwrap(), [7, 8, 9]);
+/// # drop(spilled);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub struct SpilledMipMap<T> {
+    file: Mutex<File>,
+    /// Byte range of every level within the file.
+    ranges: Vec<Range<usize>>,
+    /// Levels kept in memory, None for levels read from the file.
+    resident: Vec<Option<Vec<T>>>,
+    min_level: usize,
+    /// Recently read pages, the most recent one last.
+    cache: Mutex<Vec<Page<T>>>,
+    cache_capacity: usize,
+}
+
+/// A page of elements read from the file, by level and page index.
+type Page<T> = ((usize, usize), Arc<[T]>);
+
+/// The part of a level of a [`SpilledMipMap`] which covers a viewport, read from memory or the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpilledSlice<T> {
+    /// The level the data comes from.
+    pub level: usize,
+    /// Index of the first element of `data` within the level.
+    pub start: usize,
+    pub data: Vec<T>,
+}
+
+impl<T: Num + Pod> SpilledMipMap<T> {
+    /// Opens a file written by [`MipMap1D::save`](crate::MipMap1D::save),
+    /// reading all levels with at most `max_resident` elements, and at least the coarsest one, into memory.
+    /// Up to `cache_pages` pages of the other levels are cached.
+    /// An archive which is invalid or stores another element type is reported
+    /// as [`io::ErrorKind::InvalidData`] wrapping an [`ArchiveError`].
+    pub fn open(
+        path: impl AsRef<Path>,
+        max_resident: usize,
+        cache_pages: usize,
+    ) -> io::Result<Self> {
+        let invalid = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+
+        // Reads a growing prefix of the file until it holds the whole header.
+        let mut prefix_len = 4096;
+        let header = loop {
+            let mut prefix = vec![];
+            (&mut file).take(prefix_len).read_to_end(&mut prefix)?;
+            file.rewind()?;
+            match Header::parse::<T>(&prefix) {
+                Err(ArchiveError::Truncated) if prefix_len < file_len => prefix_len *= 2,
+                header => break header.map_err(invalid)?,
+            }
+        };
+        let valid = header
+            .levels
+            .iter()
+            .all(|range| range.end as u64 <= file_len && range.len() % size_of::<T>() == 0);
+        if !valid {
+            return Err(invalid(ArchiveError::Truncated));
+        }
+
+        let mut spilled = Self {
+            file: Mutex::new(file),
+            resident: vec![None; header.levels.len()],
+            ranges: header.levels,
+            min_level: header.min_level,
+            cache: Mutex::new(vec![]),
+            cache_capacity: cache_pages,
+        };
+        for level in spilled.min_level..spilled.num_levels() {
+            let len = spilled.len(level).unwrap();
+            if len <= max_resident || level == spilled.num_levels() - 1 {
+                spilled.resident[level] = Some(spilled.read_at(level, 0..len)?);
+            }
+        }
+        Ok(spilled)
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns the finest level which was not pruned before saving.
+    pub fn min_level(&self) -> usize {
+        self.min_level
+    }
+
+    /// Returns the number of elements on given level.
+    /// If the level is out of bounds or was pruned, returns None
+    pub fn len(&self, level: usize) -> Option<usize> {
+        if level < self.min_level {
+            return None;
+        }
+        Some(self.ranges.get(level)?.len() / size_of::<T>())
+    }
+
+    /// Returns true if given level is kept in memory.
+    /// If the level is out of bounds, returns false.
+    pub fn is_resident(&self, level: usize) -> bool {
+        matches!(self.resident.get(level), Some(Some(_)))
+    }
+
+    /// Returns the elements of given level within `range`, reading the pages which are not cached.
+    /// The range is clipped to the level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level is out of bounds or was pruned.
+    pub fn read(&self, level: usize, range: Range<usize>) -> io::Result<Vec<T>> {
+        let len = self.len(level).expect("The level is out of bounds");
+        let range = range.start.min(len)..range.end.min(len);
+        if let Some(Some(data)) = self.resident.get(level) {
+            return Ok(data[range].to_vec());
+        }
+
+        let mut data = Vec::with_capacity(range.len());
+        for page in range.start / PAGE_LEN..range.end.div_ceil(PAGE_LEN) {
+            let elements = self.page(level, page)?;
+            let first = page * PAGE_LEN;
+            let (start, end) = (
+                range.start.max(first) - first,
+                range.end.min(first + elements.len()) - first,
+            );
+            data.extend_from_slice(&elements[start..end]);
+        }
+        Ok(data)
+    }
+
+    /// Returns the data of the finest level which displays the viewport with at most `viewport.max_points` points,
+    /// reading it from the file if needed.
+    /// The part of the viewport beyond the source data is ignored.
+    pub fn query(&self, viewport: &Viewport) -> io::Result<SpilledSlice<T>> {
+        let (range, level) = self.clip(viewport);
+        let buckets = self.buckets(&range, level);
+        Ok(SpilledSlice {
+            level,
+            start: buckets.start,
+            data: self.read(level, buckets)?,
+        })
+    }
+
+    /// Same as [`SpilledMipMap::query`], but only uses the levels kept in memory, so it never reads the file.
+    /// The result may be coarser than the viewport allows; it can be shown until the query is done.
+    pub fn preview(&self, viewport: &Viewport) -> LevelSlice<'_, T> {
+        let (range, level) = self.clip(viewport);
+        let level = (level..self.num_levels())
+            .find(|&level| self.is_resident(level))
+            .unwrap_or(self.num_levels() - 1);
+        let data = self.resident[level]
+            .as_deref()
+            .expect("The coarsest level is always resident");
+        let buckets = self.buckets(&range, level);
+
+        LevelSlice {
+            level,
+            start: buckets.start,
+            data: &data[buckets],
+        }
+    }
+
+    /// Returns the cached pages by level and page index, the least recently used one first.
+    pub fn cached_pages(&self) -> Vec<(usize, usize)> {
+        let cache = self.cache.lock().unwrap();
+        cache.iter().map(|&(page, _)| page).collect()
+    }
+
+    /// Restricts the viewport to the source data and returns the level to display it at.
+    fn clip(&self, viewport: &Viewport) -> (Range<usize>, usize) {
+        let len = self.len(self.min_level).unwrap() << self.min_level;
+        let range = viewport.range.start.min(len)..viewport.range.end.min(len);
+        let level = viewport
+            .level_for(&range)
+            .clamp(self.min_level, self.num_levels() - 1);
+        (range, level)
+    }
+
+    /// Returns the buckets of given level covering the source `range`, restricted to the level.
+    fn buckets(&self, range: &Range<usize>, level: usize) -> Range<usize> {
+        let (buckets, len) = (bucket_range(range, level), self.len(level).unwrap());
+        buckets.start.min(len)..buckets.end.min(len)
+    }
+
+    /// Returns a page of given level, reading it if it is not cached.
+    fn page(&self, level: usize, page: usize) -> io::Result<Arc<[T]>> {
+        let mut cache = self.cache.lock().unwrap();
+        let data = match cache
+            .iter()
+            .position(|(cached, _)| *cached == (level, page))
+        {
+            Some(position) => cache.remove(position).1,
+            None => {
+                let len = self.len(level).unwrap();
+                self.read_at(level, page * PAGE_LEN..len.min((page + 1) * PAGE_LEN))?
+                    .into()
+            }
+        };
+        cache.push(((level, page), Arc::clone(&data)));
+        if cache.len() > self.cache_capacity {
+            cache.remove(0);
+        }
+        Ok(data)
+    }
+
+    /// Reads elements of given level from the file.
+    fn read_at(&self, level: usize, range: Range<usize>) -> io::Result<Vec<T>> {
+        let mut data = vec![T::zero(); range.len()];
+        let mut file = self.file.lock().unwrap();
+        let offset = self.ranges[level].start + range.start * size_of::<T>();
+        file.seek(SeekFrom::Start(offset as u64))?;
+        file.read_exact(as_bytes_mut(&mut data))?;
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("mipmap-1d-{name}-{}.bin", std::process::id()))
+    }
+
+    #[test]
+    fn test_levels_match_mipmap() {
+        let full = MipMap1D::new((0..20_000).map(|x| (x * 7919 % 1009) as i64).collect());
+        let mut mipmap = full.clone();
+        mipmap.prune_below(1);
+        let path = temp_path("spilled");
+        mipmap.save(&path).unwrap();
+        let spilled = SpilledMipMap::<i64>::open(&path, 3000, 2).unwrap();
+
+        assert_eq!(spilled.num_levels(), mipmap.num_levels());
+        assert_eq!((spilled.min_level(), spilled.len(0)), (1, None));
+        assert!(!spilled.is_resident(2) && spilled.is_resident(3));
+        for level in 1..mipmap.num_levels() {
+            let expected = mipmap.get_level(level).unwrap();
+            assert_eq!(spilled.read(level, 0..usize::MAX).unwrap(), expected);
+        }
+        assert_eq!(
+            spilled.read(1, 4090..4100).unwrap(),
+            mipmap.get_level(1).unwrap()[4090..4100]
+        );
+
+        let viewport = Viewport::new(1000..9000, 4000);
+        let slice = full.query(&viewport);
+        let queried = spilled.query(&viewport).unwrap();
+        assert_eq!(
+            (queried.level, queried.start, &queried.data[..]),
+            (slice.level, slice.start, slice.data)
+        );
+        assert_eq!(spilled.preview(&viewport).level, 3);
+        drop(spilled);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_pages_are_cached() {
+        let path = temp_path("pages");
+        MipMap1D::new(vec![3u8; 10_000]).save(&path).unwrap();
+        let spilled = SpilledMipMap::<u8>::open(&path, 0, 2).unwrap();
+        let wrong_type = SpilledMipMap::<u16>::open(&path, 0, 2).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(spilled.read(0, 4000..4200).unwrap(), [3; 200]);
+        assert_eq!(spilled.cached_pages(), [(0, 0), (0, 1)]);
+        spilled.read(0, 100..101).unwrap();
+        spilled.read(1, 0..1).unwrap();
+        assert_eq!(spilled.cached_pages(), [(0, 0), (1, 0)]);
+        assert_eq!(wrong_type.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,144 @@
+use core::cmp::Ordering;
+
+use num_traits::{Num, ToPrimitive};
+
+use crate::mipmap::is_integral;
+use crate::sum::CompensatedSum;
+
+/// Summary statistics of a whole level, ignoring NaN values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelStats<T> {
+    pub min: T,
+    pub max: T,
+    pub mean: f64,
+    /// Number of non-NaN elements on the level.
+    pub count: usize,
+}
+
+/// Statistics of a level as they are maintained while elements are appended, from which its
+/// [`LevelStats`] are computed. Integers are summed exactly and other values with compensation,
+/// instead of updating a running mean with a division per element.
+#[derive(Debug, Clone)]
+pub(crate) struct StatsSum<T> {
+    min: T,
+    max: T,
+    sum: Sum,
+    count: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Sum {
+    Integer(i128),
+    /// Sum of values which are not integers, or of integers whose exact sum overflowed.
+    Float(CompensatedSum<f64>),
+}
+
+impl Sum {
+    /// Adds a value, whose integer value is given if it is an integer.
+    fn add<T: ToPrimitive>(&mut self, integer: Option<i128>, value: &T) {
+        if let (Self::Integer(sum), Some(x)) = (&*self, integer) {
+            if let Some(sum) = sum.checked_add(x) {
+                *self = Self::Integer(sum);
+                return;
+            }
+        }
+        if let Self::Integer(sum) = *self {
+            *self = Self::Float([sum as f64].into_iter().collect());
+        }
+        if let Self::Float(sum) = self {
+            sum.add(value.to_f64().unwrap());
+        }
+    }
+
+    fn value(&self) -> f64 {
+        match self {
+            Self::Integer(sum) => *sum as f64,
+            Self::Float(sum) => sum.value(),
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + Clone> StatsSum<T> {
+    /// Computes the statistics of the values, returning None if all of them are NaN.
+    pub(crate) fn of<'a>(values: impl IntoIterator<Item = &'a T>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut stats = None;
+        for value in values {
+            match &mut stats {
+                Some(stats) => Self::add(stats, value.clone()),
+                None => stats = Self::first(value.clone()),
+            }
+        }
+        stats
+    }
+
+    /// Adds a value to the statistics, which may be absent if there were no values so far.
+    pub(crate) fn with(stats: Option<Self>, value: T) -> Option<Self> {
+        match stats {
+            Some(mut stats) => {
+                stats.add(value);
+                Some(stats)
+            }
+            None => Self::first(value),
+        }
+    }
+
+    /// Returns the integer value of an integer, which is never NaN, so only other values are checked.
+    fn integer(value: &T) -> Result<Option<i128>, ()> {
+        match is_integral::<T>() {
+            true => Ok(value.to_i128()),
+            false if value.to_f64().unwrap().is_nan() => Err(()),
+            false => Ok(None),
+        }
+    }
+
+    fn first(value: T) -> Option<Self> {
+        let mut sum = Sum::Integer(0);
+        sum.add(Self::integer(&value).ok()?, &value);
+        Some(Self {
+            min: value.clone(),
+            max: value,
+            sum,
+            count: 1,
+        })
+    }
+
+    fn add(&mut self, value: T) {
+        let Ok(integer) = Self::integer(&value) else {
+            return;
+        };
+        self.sum.add(integer, &value);
+        self.count += 1;
+        if compare(&value, &self.min) == Some(Ordering::Less) {
+            self.min = value;
+        } else if compare(&value, &self.max) == Some(Ordering::Greater) {
+            self.max = value;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> LevelStats<T> {
+        LevelStats {
+            min: self.min.clone(),
+            max: self.max.clone(),
+            mean: self.sum.value() / self.count as f64,
+            count: self.count,
+        }
+    }
+}
+
+/// Compares two values without requiring the type to be ordered.
+/// Integers are compared exactly, even 128-bit ones which do not fit into f64.
+pub(crate) fn compare<T: Num + ToPrimitive>(a: &T, b: &T) -> Option<Ordering> {
+    if is_integral::<T>() {
+        if let (Some(a), Some(b)) = (a.to_i128(), b.to_i128()) {
+            return Some(a.cmp(&b));
+        }
+        if let (Some(a), Some(b)) = (a.to_u128(), b.to_u128()) {
+            return Some(a.cmp(&b));
+        }
+    }
+
+    a.to_f64()?.partial_cmp(&b.to_f64()?)
+}
@@ -0,0 +1,141 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+use num_traits::{Num, ToPrimitive};
+
+use crate::stats::compare;
+
+/// Summary of the source samples in a bucket, ignoring NaN values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketStats<T> {
+    pub min: T,
+    pub max: T,
+    pub mean: f64,
+    /// Number of non-NaN source samples in the bucket.
+    /// If it is zero, all other fields are NaN.
+    pub count: usize,
+}
+
+impl<T: Num + ToPrimitive + Copy> BucketStats<T> {
+    /// Creates the summary of a single source sample.
+    pub fn of(value: T) -> Self {
+        let x = value.to_f64().unwrap();
+        Self {
+            min: value,
+            max: value,
+            mean: x,
+            count: usize::from(!x.is_nan()),
+        }
+    }
+
+    /// Combines the summaries of two buckets into the exact summary of their union.
+    pub fn merge(&self, other: &Self) -> Self {
+        if other.count == 0 {
+            return *self;
+        }
+        if self.count == 0 {
+            return *other;
+        }
+
+        let count = self.count + other.count;
+        Self {
+            min: if compare(&other.min, &self.min) == Some(Ordering::Less) {
+                other.min
+            } else {
+                self.min
+            },
+            max: if compare(&other.max, &self.max) == Some(Ordering::Greater) {
+                other.max
+            } else {
+                self.max
+            },
+            mean: self.mean + (other.mean - self.mean) * other.count as f64 / count as f64,
+            count,
+        }
+    }
+}
+
+/// Creates several downsampled versions of given vector, where every element summarizes its bucket
+/// with the minimum, maximum, mean and count of the source samples.
+/// The summaries are merged exactly, so a single structure serves envelope rendering, re-aggregation and tooltips.
+/// Example:
+/// ```rust
+/// use mipmap_1d::StatsMipMap1D;
+///
+/// let mipmap = StatsMipMap1D::new(vec![2, 4, 6, 8, 9]);
+/// assert_eq!(mipmap.num_levels(), 4);
+/// let top = mipmap.get_level(3).unwrap()[0];
+/// assert_eq!((top.min, top.max, top.count), (2, 9, 5));
+/// assert_eq!(top.mean, 5.8);
+/// ```
+pub struct StatsMipMap1D<T> {
+    data: Vec<Vec<BucketStats<T>>>,
+}
+
+impl<T: Num + ToPrimitive + Copy> StatsMipMap1D<T> {
+    pub fn new(source: Vec<T>) -> Self {
+        let mut data = vec![source.into_iter().map(BucketStats::of).collect::<Vec<_>>()];
+
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a.merge(b),
+                    _ => pair[0],
+                })
+                .collect();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the bucket summaries on given level.
+    /// Level `0` summarizes every source sample on its own.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<BucketStats<T>>> {
+        self.data.get(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summaries_match_source() {
+        let source: Vec<f64> = (0..45).map(|x| (x as f64 * 0.7).cos() * 10.0).collect();
+        let mipmap = StatsMipMap1D::new(source.clone());
+
+        for level in 0..mipmap.num_levels() {
+            for (i, stats) in mipmap.get_level(level).unwrap().iter().enumerate() {
+                let bucket = &source[i << level..((i + 1) << level).min(source.len())];
+                let mean = bucket.iter().sum::<f64>() / bucket.len() as f64;
+
+                assert_eq!(stats.count, bucket.len());
+                assert_eq!(stats.min, bucket.iter().copied().fold(f64::MAX, f64::min));
+                assert_eq!(stats.max, bucket.iter().copied().fold(f64::MIN, f64::max));
+                assert!((stats.mean - mean).abs() < 1e-9);
+            }
+        }
+        assert_eq!(mipmap.get_level(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_nans_are_ignored() {
+        let mipmap = StatsMipMap1D::new(vec![f64::NAN, f64::NAN, 3.0]);
+
+        let pairs = mipmap.get_level(1).unwrap();
+        assert_eq!(pairs[0].count, 0);
+        assert!(pairs[0].mean.is_nan());
+
+        let top = mipmap.get_level(2).unwrap()[0];
+        assert_eq!((top.min, top.max, top.mean, top.count), (3.0, 3.0, 3.0, 1));
+    }
+}
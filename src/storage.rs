@@ -0,0 +1,348 @@
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use core::ops::{Index, IndexMut, Range};
+
+/// Storage of the levels of a mipmap, so that they can be kept in memory-mapped files,
+/// shared memory or custom arenas while reusing the reduction logic of [`MipMap1D`](crate::MipMap1D).
+/// Levels are appended from the finest to the coarsest one. After construction, elements are only appended
+/// to the end of a level, overwritten in place through [`LevelStorage::level_mut`], or dropped with whole levels.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// // Every level in its own vector.
+/// let mipmap = MipMap1D::with_storage(vec![2, 4, 6, 8, 9], Vec::<Vec<i32>>::new());
+/// assert_eq!(*mipmap.get_level(2).unwrap(), [5, 9]);
+/// ```
+pub trait LevelStorage<T> {
+    /// Returns the number of levels.
+    fn num_levels(&self) -> usize;
+
+    /// Returns given level.
+    /// If the level is out of bounds, returns None
+    fn level(&self, level: usize) -> Option<&[T]>;
+
+    /// Returns given level for modification.
+    /// If the level is out of bounds, returns None
+    fn level_mut(&mut self, level: usize) -> Option<&mut [T]>;
+
+    /// Appends a coarser level.
+    fn push_level(&mut self, level: Vec<T>);
+
+    /// Appends an element to given level.
+    fn push(&mut self, level: usize, value: T);
+
+    /// Drops all levels starting from `levels`, keeping the finer ones in place.
+    /// The storage must stay appendable: pushing to the remaining levels and appending new levels afterwards
+    /// has to behave as if the dropped levels had never existed.
+    fn truncate(&mut self, levels: usize);
+}
+
+/// The default [`LevelStorage`], which keeps all levels of a mipmap in one contiguous buffer,
+/// with the range of every level stored separately.
+/// Levels are laid out from the finest to the coarsest one.
+/// To append to a level in amortized constant time, levels may be followed by spare capacity,
+/// which is filled with copies of existing elements and is never exposed.
+/// The buffer is shared between clones and copied on the first write, so cloning is cheap.
+#[derive(Clone)]
+pub struct VecStorage<T> {
+    buffer: Arc<Vec<T>>,
+    /// Start of every level in the buffer. Level `i` may grow up to the start of level `i + 1`.
+    offsets: Vec<usize>,
+    lens: Vec<usize>,
+}
+
+impl<T: Clone> VecStorage<T> {
+    pub(crate) fn new(source: Vec<T>) -> Self {
+        Self {
+            lens: vec![source.len()],
+            offsets: vec![0],
+            buffer: Arc::new(source),
+        }
+    }
+
+    /// Returns the number of levels.
+    pub(crate) fn len(&self) -> usize {
+        self.lens.len()
+    }
+
+    /// Returns given level, or None if it is out of bounds.
+    pub(crate) fn get(&self, level: usize) -> Option<&[T]> {
+        let range = self.range(level)?;
+        Some(&self.buffer[range])
+    }
+
+    /// Returns the position of given level in [`VecStorage::buffer`], or None if it is out of bounds.
+    pub(crate) fn range(&self, level: usize) -> Option<Range<usize>> {
+        let start = *self.offsets.get(level)?;
+        Some(start..start + self.lens[level])
+    }
+
+    /// Returns the buffer holding all levels, including their spare capacity.
+    pub(crate) fn buffer(&self) -> &[T] {
+        &self.buffer
+    }
+
+    /// Returns a handle to the buffer, which keeps it alive after the levels are modified or dropped.
+    pub(crate) fn share(&self) -> Arc<Vec<T>> {
+        Arc::clone(&self.buffer)
+    }
+
+    /// Returns the number of bytes allocated on the heap, including spare capacity.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.buffer.capacity() * size_of::<T>()
+            + (self.offsets.capacity() + self.lens.capacity()) * size_of::<usize>()
+    }
+
+    /// Releases the spare capacity after the coarsest level.
+    pub(crate) fn shrink_to_fit(&mut self) {
+        Arc::make_mut(&mut self.buffer).shrink_to_fit();
+    }
+
+    /// Empties all levels below `level`, releasing their memory. The remaining levels keep their indices.
+    pub(crate) fn clear_below(&mut self, level: usize) {
+        let Some(&start) = self.offsets.get(level) else {
+            return;
+        };
+        self.buffer = Arc::new(self.buffer[start..].to_vec());
+        for offset in &mut self.offsets[level..] {
+            *offset -= start;
+        }
+        self.offsets[..level].fill(0);
+        self.lens[..level].fill(0);
+    }
+
+    /// Removes the last element of given level.
+    #[cfg(test)]
+    pub(crate) fn pop(&mut self, level: usize) {
+        self.lens[level] -= 1;
+    }
+}
+
+impl<T> Default for VecStorage<T> {
+    fn default() -> Self {
+        Self {
+            buffer: Arc::new(vec![]),
+            offsets: vec![],
+            lens: vec![],
+        }
+    }
+}
+
+impl<T: Clone> LevelStorage<T> for VecStorage<T> {
+    fn num_levels(&self) -> usize {
+        self.len()
+    }
+
+    fn level(&self, level: usize) -> Option<&[T]> {
+        self.get(level)
+    }
+
+    fn level_mut(&mut self, level: usize) -> Option<&mut [T]> {
+        let range = self.range(level)?;
+        Some(&mut Arc::make_mut(&mut self.buffer)[range])
+    }
+
+    fn push_level(&mut self, level: Vec<T>) {
+        self.offsets.push(self.buffer.len());
+        self.lens.push(level.len());
+        Arc::make_mut(&mut self.buffer).extend(level);
+    }
+
+    // Moves the coarser levels if the level has no spare capacity left.
+    fn push(&mut self, level: usize, value: T) {
+        let end = self.offsets[level] + self.lens[level];
+        let next = self.offsets.get(level + 1).copied();
+        let buffer = Arc::make_mut(&mut self.buffer);
+
+        if end < next.unwrap_or(buffer.len()) {
+            buffer[end] = value;
+        } else if next.is_none() {
+            buffer.push(value);
+        } else {
+            // Doubles the capacity of the level, so that moving the following levels is amortized.
+            let spare = self.lens[level].max(1);
+            buffer.splice(end..end, core::iter::repeat_n(value.clone(), spare));
+            for offset in &mut self.offsets[level + 1..] {
+                *offset += spare;
+            }
+            buffer[end] = value;
+        }
+        self.lens[level] += 1;
+    }
+
+    // Also drops the spare capacity of the new coarsest level, which has to end the buffer.
+    fn truncate(&mut self, levels: usize) {
+        if levels >= self.len() {
+            return;
+        }
+        let end = match levels {
+            0 => 0,
+            _ => self.offsets[levels - 1] + self.lens[levels - 1],
+        };
+        Arc::make_mut(&mut self.buffer).truncate(end);
+        self.offsets.truncate(levels);
+        self.lens.truncate(levels);
+    }
+}
+
+impl<T: Clone> Index<usize> for VecStorage<T> {
+    type Output = [T];
+
+    fn index(&self, level: usize) -> &[T] {
+        let range = self.range(level).expect("The level is out of bounds");
+        &self.buffer[range]
+    }
+}
+
+impl<T: Clone> IndexMut<usize> for VecStorage<T> {
+    fn index_mut(&mut self, level: usize) -> &mut [T] {
+        self.level_mut(level).expect("The level is out of bounds")
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for VecStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && (0..self.len()).all(|level| self[level] == other[level])
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq<Vec<Vec<T>>> for VecStorage<T> {
+    fn eq(&self, other: &Vec<Vec<T>>) -> bool {
+        self.len() == other.len() && (0..self.len()).all(|level| self[level] == other[level])
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for VecStorage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|level| &self[level]))
+            .finish()
+    }
+}
+
+/// Stores every level in its own vector.
+impl<T> LevelStorage<T> for Vec<Vec<T>> {
+    fn num_levels(&self) -> usize {
+        self.len()
+    }
+
+    fn level(&self, level: usize) -> Option<&[T]> {
+        self.get(level).map(Vec::as_slice)
+    }
+
+    fn level_mut(&mut self, level: usize) -> Option<&mut [T]> {
+        self.get_mut(level).map(Vec::as_mut_slice)
+    }
+
+    fn push_level(&mut self, level: Vec<T>) {
+        Vec::push(self, level);
+    }
+
+    fn push(&mut self, level: usize, value: T) {
+        self[level].push(value);
+    }
+
+    fn truncate(&mut self, levels: usize) {
+        Vec::truncate(self, levels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_to_inner_levels() {
+        let mut levels = VecStorage::new(vec![1, 2, 3]);
+        levels.push_level(vec![10]);
+        levels.push_level(vec![]);
+
+        for x in 4..=10 {
+            levels.push(0, x);
+        }
+        levels.push(1, 11);
+        levels.push(2, 20);
+        levels.push(1, 12);
+
+        assert_eq!(levels, vec![(1..=10).collect(), vec![10, 11, 12], vec![20]]);
+        assert_eq!(levels.get(3), None);
+        assert!(levels.buffer().len() >= 14);
+    }
+
+    #[test]
+    fn test_compact_after_push_levels() {
+        let mut levels = VecStorage::new(vec![1.0, 2.0]);
+        levels.push_level(vec![1.5]);
+
+        assert_eq!(levels.buffer(), [1.0, 2.0, 1.5]);
+        assert_eq!(levels.range(1), Some(2..3));
+    }
+
+    #[test]
+    fn test_clones_share_buffer_until_written() {
+        let mut levels = VecStorage::new(vec![1, 2]);
+        levels.push_level(vec![1]);
+        let copy = levels.clone();
+        assert!(Arc::ptr_eq(&levels.share(), &copy.share()));
+
+        levels[1][0] = 5;
+        assert!(!Arc::ptr_eq(&levels.share(), &copy.share()));
+        assert_eq!(copy, vec![vec![1, 2], vec![1]]);
+        assert_eq!(levels, vec![vec![1, 2], vec![5]]);
+    }
+
+    #[test]
+    fn test_truncate_and_clear() {
+        let mut levels = VecStorage::new(vec![1, 2, 3, 4]);
+        levels.push_level(vec![5, 6]);
+        levels.push_level(vec![7]);
+        levels.push(0, 8);
+
+        levels.truncate(2);
+        assert_eq!(levels, vec![vec![1, 2, 3, 4, 8], vec![5, 6]]);
+        levels.truncate(1);
+        levels.push(0, 9);
+        levels.push_level(vec![10]);
+        assert_eq!(levels, vec![vec![1, 2, 3, 4, 8, 9], vec![10]]);
+        assert_eq!(levels.buffer(), [1, 2, 3, 4, 8, 9, 10]);
+        levels.clear_below(1);
+        assert_eq!(levels, vec![vec![], vec![10]]);
+        assert_eq!(levels.buffer(), [10]);
+    }
+
+    #[test]
+    fn test_custom_storage_matches_default() {
+        let source: Vec<i64> = (0..37).map(|x| x * x % 11).collect();
+        let mut default = crate::MipMap1D::new(source.clone());
+        let mut custom = crate::MipMap1D::with_storage(source, Vec::<Vec<i64>>::new());
+
+        for x in 0..20 {
+            default.push(x);
+            custom.push(x);
+        }
+        assert_eq!(custom.num_levels(), default.num_levels());
+        for level in 0..=default.num_levels() {
+            assert_eq!(custom.get_level(level), default.get_level(level));
+        }
+        assert_eq!(custom.level_stats(2), default.level_stats(2));
+
+        // Rebuilding truncates the storage to the source, which stays appendable.
+        default.rebuild_levels_with(crate::Reduction::Max);
+        custom.rebuild_levels_with(crate::Reduction::Max);
+        for x in 0..20 {
+            default.push(x);
+            custom.push(x);
+        }
+        for level in 0..=default.num_levels() {
+            assert_eq!(custom.get_level(level), default.get_level(level));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_empty_storage_panics() {
+        crate::MipMap1D::with_storage(vec![1, 2], vec![vec![1]]);
+    }
+}
@@ -0,0 +1,184 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::resample::reduce;
+use crate::{MipMapConfig, Reduction};
+
+/// An overview of a growing series which never has more than a fixed number of points,
+/// e.g. a strip chart covering a whole measurement run. Whenever appending a sample would exceed the budget,
+/// adjacent points are merged, doubling the number of samples every point covers.
+/// The points are the same as the level of a [`MipMap1D`](crate::MipMap1D) rebuilt with the reduction
+/// whose buckets cover as many samples, but only `O(budget + log len)` elements are stored.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{Reduction, StripChart};
+///
+/// let mut chart = StripChart::new(4, Reduction::Max);
+/// chart.extend([2, 4, 6, 8]);
+/// assert_eq!(chart.points(), [2, 4, 6, 8]);
+/// chart.push(9);
+/// assert_eq!((chart.points(), chart.scale()), (vec![4, 8, 9], 2));
+/// chart.extend([1, 3, 5, 7]);
+/// assert_eq!((chart.points(), chart.scale()), (vec![8, 9, 7], 4));
+/// ```
+pub struct StripChart<T> {
+    budget: usize,
+    config: MipMapConfig,
+    /// Completed points, each covering `2^level` samples.
+    points: Vec<T>,
+    /// Pending elements of the finer levels, from which the next point is assembled.
+    pending: Vec<Option<T>>,
+    level: usize,
+    len: usize,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> StripChart<T> {
+    /// Creates an empty chart of at most `budget` points combining samples with `reduction`.
+    ///
+    /// # Panics
+    /// Panics if `budget` is zero.
+    pub fn new(budget: usize, reduction: Reduction) -> Self {
+        assert!(budget > 0, "The budget must be positive");
+        Self {
+            budget,
+            config: MipMapConfig {
+                reduction,
+                ..Default::default()
+            },
+            points: vec![],
+            pending: vec![],
+            level: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends a sample, merging points if the budget would be exceeded.
+    /// This takes amortized `O(log len)` steps.
+    pub fn push(&mut self, value: T) {
+        self.len += 1;
+        let mut carry = value;
+        for level in 0..self.level {
+            match self.pending[level].take() {
+                Some(first) => carry = self.combine(&first, &carry),
+                None => {
+                    self.pending[level] = Some(carry);
+                    return self.fit();
+                }
+            }
+        }
+        self.points.push(carry);
+        self.fit();
+    }
+
+    /// Appends several samples.
+    pub fn extend(&mut self, values: impl IntoIterator<Item = T>) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Returns the points from the oldest one. The last point may cover fewer samples.
+    pub fn points(&self) -> Vec<T> {
+        let mut points = self.points.clone();
+        points.extend(self.partial());
+        points
+    }
+
+    /// Returns the number of samples covered by every point, i.e. `2^level`.
+    pub fn scale(&self) -> usize {
+        1 << self.level
+    }
+
+    /// Returns the level of a [`MipMap1D`](crate::MipMap1D) of all samples which has the same points.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Returns the maximum number of points.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Returns the number of appended samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no samples were appended.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Merges adjacent points until the points, including the partial one, fit into the budget.
+    fn fit(&mut self) {
+        while self.points.len() + usize::from(self.pending.iter().any(Option::is_some))
+            > self.budget
+        {
+            let points = core::mem::take(&mut self.points);
+            let pairs = points.chunks_exact(2);
+            let lone = pairs.remainder().first().cloned();
+            self.points = pairs.map(|pair| self.combine(&pair[0], &pair[1])).collect();
+            self.pending.push(lone);
+            self.level += 1;
+        }
+    }
+
+    /// Assembles the point of the samples not covered by a completed point yet, like the last
+    /// element of a mipmap level: a lone element is carried up unchanged.
+    fn partial(&self) -> Option<T> {
+        self.pending
+            .iter()
+            .fold(None, |later, first| match (first, later) {
+                (Some(first), Some(later)) => Some(self.combine(first, &later)),
+                (Some(first), None) => Some(first.clone()),
+                (None, later) => later,
+            })
+    }
+
+    fn combine(&self, first: &T, second: &T) -> T {
+        reduce(&[(first, 1), (second, 1)], &self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MipMap1D;
+
+    #[test]
+    fn test_matches_mipmap_levels() {
+        for (budget, reduction) in [
+            (16, Reduction::Mean),
+            (7, Reduction::Max),
+            (1, Reduction::Last),
+        ] {
+            let mut chart = StripChart::new(budget, reduction);
+            let mut source = vec![];
+            for x in 0..1000i64 {
+                let sample = (x * 7919) % 101 - 50;
+                chart.push(sample);
+                source.push(sample);
+                assert!(chart.points().len() <= budget);
+
+                if x % 37 == 0 {
+                    let mut mipmap = MipMap1D::new(source.clone());
+                    mipmap.rebuild_levels_with(reduction);
+                    assert_eq!(chart.points(), mipmap.get_level(chart.level()).unwrap());
+                }
+            }
+            assert_eq!(chart.len(), 1000);
+            assert!(chart.points().len() > budget / 2);
+        }
+    }
+
+    #[test]
+    fn test_empty_and_small() {
+        let mut chart = StripChart::new(2, Reduction::Mean);
+        assert!(chart.is_empty());
+        assert!(chart.points().is_empty());
+        chart.extend([1.0, 3.0, 8.0]);
+        assert_eq!((chart.points(), chart.scale()), (vec![2.0, 8.0], 2));
+        assert_eq!(chart.budget(), 2);
+    }
+}
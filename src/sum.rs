@@ -0,0 +1,61 @@
+use num_traits::Num;
+
+/// Running sum with Kahan compensation, which keeps the rounding error of float sums
+/// independent of the number of values. Integer sums are unaffected.
+#[derive(Debug, Clone)]
+pub(crate) struct CompensatedSum<T> {
+    sum: T,
+    /// Low-order part lost by the previous addition.
+    compensation: T,
+}
+
+impl<T: Num + Clone> CompensatedSum<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            sum: T::zero(),
+            compensation: T::zero(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, value: T) {
+        let value = value - self.compensation.clone();
+        let sum = self.sum.clone() + value.clone();
+        self.compensation = (sum.clone() - self.sum.clone()) - value;
+        self.sum = sum;
+    }
+
+    pub(crate) fn value(&self) -> T {
+        self.sum.clone()
+    }
+}
+
+impl<T: Num + Clone> FromIterator<T> for CompensatedSum<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        let mut sum = Self::new();
+        for value in values {
+            sum.add(value);
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_float_sums() {
+        let values = vec![0.1f32; 1_000_000];
+
+        let naive: f32 = values.iter().sum();
+        let compensated: CompensatedSum<f32> = values.iter().copied().collect();
+        assert!((naive - 100_000.0).abs() > 100.0);
+        assert!((compensated.value() - 100_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_integer_sums_are_exact() {
+        let sum: CompensatedSum<u64> = (1..=100).collect();
+        assert_eq!(sum.value(), 5050);
+    }
+}
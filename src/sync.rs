@@ -0,0 +1,113 @@
+use std::sync::{Arc, PoisonError, RwLock, RwLockWriteGuard};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// A mipmap shared by a thread appending live data and any number of threads rendering it.
+/// Readers take snapshots, which are consistent copies of the whole mipmap, in constant time:
+/// the levels are shared with the writer until its next append.
+/// Clones share the same mipmap.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, SharedMipMap, Viewport};
+///
+/// let shared = SharedMipMap::new(MipMap1D::new(vec![2.0, 4.0]));
+/// let writer = shared.clone();
+/// std::thread::spawn(move || writer.extend([6.0, 8.0, 9.0])).join().unwrap();
+///
+/// let snapshot = shared.snapshot();
+/// assert_eq!(snapshot.query(&Viewport::new(0..5, 2)).data, [5.0, 9.0]);
+/// ```
+pub struct SharedMipMap<T: Num + ToPrimitive + FromPrimitive> {
+    mipmap: Arc<RwLock<MipMap1D<T>>>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive> Clone for SharedMipMap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            mipmap: Arc::clone(&self.mipmap),
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> SharedMipMap<T> {
+    pub fn new(mipmap: MipMap1D<T>) -> Self {
+        Self {
+            mipmap: Arc::new(RwLock::new(mipmap)),
+        }
+    }
+
+    /// Appends a sample. Readers wait only while the levels are updated.
+    /// The first append after a snapshot was taken copies the levels, unless the snapshot was dropped already,
+    /// so readers should hold snapshots only while rendering a frame.
+    pub fn push(&self, value: T) {
+        self.write().push(value);
+    }
+
+    /// Appends several samples at once, so readers never see only a part of them.
+    pub fn extend(&self, values: impl IntoIterator<Item = T>) {
+        self.write().extend(values);
+    }
+
+    /// Returns a copy of the mipmap, which does not change with further appends.
+    pub fn snapshot(&self) -> MipMap1D<T> {
+        self.mipmap
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the number of source samples.
+    pub fn len(&self) -> usize {
+        self.mipmap
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if there are no source samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MipMap1D<T>> {
+        // A writer panicking during an append, e.g. in the iterator, still leaves a valid mipmap.
+        self.mipmap.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshots_are_consistent() {
+        let shared = SharedMipMap::new(MipMap1D::new(vec![]));
+        let writer = shared.clone();
+        let handle = std::thread::spawn(move || {
+            for chunk in (0..10_000u32).collect::<Vec<_>>().chunks(100) {
+                writer.extend(chunk.iter().copied());
+            }
+        });
+
+        while !handle.is_finished() {
+            let snapshot = shared.snapshot();
+            assert_eq!(snapshot.get_level(0).unwrap().len() % 100, 0);
+            assert!(snapshot.verify().is_ok());
+        }
+        handle.join().unwrap();
+        assert_eq!(shared.len(), 10_000);
+    }
+
+    #[test]
+    fn test_snapshot_is_detached() {
+        let shared = SharedMipMap::new(MipMap1D::new(vec![1, 3]));
+        let snapshot = shared.snapshot();
+        shared.push(5);
+
+        assert_eq!(*snapshot.get_level(1).unwrap(), [2]);
+        assert_eq!(*shared.snapshot().get_level(1).unwrap(), [2, 5]);
+        assert!(!shared.is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+use num_traits::{Float, FromPrimitive};
+
+use crate::MipMap1D;
+
+impl<T: Float + FromPrimitive> MipMap1D<T> {
+    /// Checks whether both mipmaps have the same shape, and all elements differ by at most `epsilon`,
+    /// e.g. in regression tests of custom strategies.
+    /// The traits of the `approx` crate are not implemented, so its `assert_abs_diff_eq!` can not be used.
+    /// NaNs are only equal to NaNs at the same position.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let a = MipMap1D::new(vec![0.1, 0.2, 0.3]);
+    /// let b = MipMap1D::new(vec![0.1, 0.2, 0.30000001]);
+    /// assert!(a.abs_diff_eq(&b, 1e-6));
+    /// assert!(!a.abs_diff_eq(&b, 1e-9));
+    /// ```
+    pub fn abs_diff_eq(&self, other: &Self, epsilon: T) -> bool {
+        self.all_elements(other, |a, b| (a - b).abs() <= epsilon)
+    }
+
+    /// Checks whether both mipmaps have the same shape, and all elements differ by at most `epsilon`,
+    /// or by at most `max_relative` times the larger of their magnitudes.
+    /// NaNs are only equal to NaNs at the same position.
+    pub fn relative_eq(&self, other: &Self, epsilon: T, max_relative: T) -> bool {
+        self.all_elements(other, |a, b| {
+            let difference = (a - b).abs();
+            difference <= epsilon || difference <= a.abs().max(b.abs()) * max_relative
+        })
+    }
+
+    /// Checks `eq` for every pair of elements at the same position,
+    /// treating equal values (e.g. infinities) and NaN pairs as equal.
+    fn all_elements(&self, other: &Self, eq: impl Fn(T, T) -> bool) -> bool {
+        self.num_levels() == other.num_levels()
+            && (0..self.num_levels()).all(|level| {
+                let (a, b) = (
+                    self.get_level(level).unwrap(),
+                    other.get_level(level).unwrap(),
+                );
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|(&a, &b)| a == b || (a.is_nan() && b.is_nan()) || eq(a, b))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_tolerance() {
+        let a = MipMap1D::new(vec![1e6, 2e6, f64::NAN, f64::INFINITY]);
+        let b = MipMap1D::new(vec![1e6 + 1.0, 2e6, f64::NAN, f64::INFINITY]);
+
+        assert!(!a.abs_diff_eq(&b, 0.1));
+        assert!(a.relative_eq(&b, 0.1, 1e-5));
+        assert!(!a.relative_eq(&b, 0.1, 1e-7));
+    }
+
+    #[test]
+    fn test_different_shapes() {
+        let a = MipMap1D::new(vec![1.0, 2.0]);
+        let b = MipMap1D::new(vec![1.0, 2.0, 3.0]);
+
+        assert!(!a.abs_diff_eq(&b, 10.0));
+        assert!(!b.relative_eq(&a, 10.0, 10.0));
+        assert!(a.abs_diff_eq(&a, 0.0));
+    }
+}
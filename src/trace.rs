@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// An event reported to the hook installed with [`set_hook`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// A level was generated during construction or [`MipMap1D::rebuild_levels_with`](crate::MipMap1D::rebuild_levels_with).
+    LevelBuilt {
+        level: usize,
+        len: usize,
+        elapsed: Duration,
+    },
+    /// All levels of a mipmap were generated.
+    Built {
+        source_len: usize,
+        levels: usize,
+        elapsed: Duration,
+    },
+    /// A sample was appended with [`MipMap1D::push`](crate::MipMap1D::push), updating `levels` levels.
+    Pushed { levels: usize },
+    /// A viewport was answered from `level` with `points` elements by [`MipMap1D::query`](crate::MipMap1D::query).
+    Queried { level: usize, points: usize },
+}
+
+static HOOK: RwLock<Option<fn(&TraceEvent)>> = RwLock::new(None);
+
+/// Installs a function receiving the events of all mipmaps, e.g. to forward them to a profiler or a logging framework.
+/// The crate does not depend on `tracing`, so no spans are created; the hook can emit them instead.
+/// Passing None removes the hook. Without a hook, events are not even constructed.
+/// Example:
+/// ```rust
+/// use mipmap_1d::trace::{self, TraceEvent};
+/// use mipmap_1d::MipMap1D;
+///
+/// trace::set_hook(Some(|event| {
+///     if let TraceEvent::Built { levels, elapsed, .. } = event {
+///         println!("built {levels} levels in {elapsed:?}");
+///     }
+/// }));
+/// MipMap1D::new(vec![1.0; 1000]);
+/// trace::set_hook(None);
+/// ```
+pub fn set_hook(hook: Option<fn(&TraceEvent)>) {
+    *HOOK.write().unwrap() = hook;
+}
+
+/// Reports the event created by `event` to the installed hook, if any.
+pub(crate) fn emit(event: impl FnOnce() -> TraceEvent) {
+    if let Some(hook) = *HOOK.read().unwrap() {
+        hook(&event());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{MipMap1D, Viewport};
+
+    static EVENTS: Mutex<Vec<TraceEvent>> = Mutex::new(vec![]);
+
+    #[test]
+    fn test_events_are_reported() {
+        set_hook(Some(|event| EVENTS.lock().unwrap().push(event.clone())));
+        let mut mipmap = MipMap1D::new(vec![1u8; 777]);
+        mipmap.push(2);
+        mipmap.query(&Viewport::new(0..778, 10));
+        set_hook(None);
+
+        // Other tests may run concurrently, so only the events of this mipmap are checked.
+        let events = EVENTS.lock().unwrap();
+        let built = events.iter().find(|event| {
+            matches!(
+                event,
+                TraceEvent::Built {
+                    source_len: 777,
+                    ..
+                }
+            )
+        });
+        assert!(matches!(built, Some(TraceEvent::Built { levels: 11, .. })));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            TraceEvent::LevelBuilt {
+                level: 1,
+                len: 389,
+                ..
+            }
+        )));
+        assert!(events.contains(&TraceEvent::Pushed { levels: 11 }));
+        assert!(events.contains(&TraceEvent::Queried {
+            level: 7,
+            points: 7
+        }));
+    }
+}
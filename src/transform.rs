@@ -0,0 +1,27 @@
+use crate::Reduction;
+
+/// Describes a function applied to the samples of a mipmap by [`MipMap1D::map_in_place`](crate::MipMap1D::map_in_place)
+/// or [`MipMap1D::map`](crate::MipMap1D::map), which decides whether the levels can be mapped directly
+/// or must be rebuilt from the mapped source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// `f(x) = a * x + b` with `a >= 0`, e.g. a gain and offset calibration or a unit conversion.
+    /// It commutes with every reduction, so the levels are mapped directly.
+    Affine,
+    /// Any function which never decreases, e.g. a square root or a clamp.
+    /// It commutes with every reduction but [`Reduction::Mean`].
+    Monotonic,
+    /// Any other function. It only commutes with [`Reduction::First`] and [`Reduction::Last`].
+    Arbitrary,
+}
+
+impl Transform {
+    /// Returns true if mapping the elements of a bucket and reducing them gives the mapped reduction.
+    pub(crate) fn commutes_with(self, reduction: Reduction) -> bool {
+        match self {
+            Transform::Affine => true,
+            Transform::Monotonic => reduction != Reduction::Mean,
+            Transform::Arbitrary => matches!(reduction, Reduction::First | Reduction::Last),
+        }
+    }
+}
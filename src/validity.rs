@@ -0,0 +1,123 @@
+use num_traits::{Float, FromPrimitive};
+
+use crate::{MipMap1D, NanPolicy, Rounding};
+
+/// A level as values with a validity bitmap, as returned by [`MipMap1D::level_with_validity`].
+/// This is the memory layout of an Arrow primitive array, but the crate does not depend on an Arrow implementation:
+/// the buffers have to be wrapped into its array type by the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidityLevel<T> {
+    /// The elements of the level. Null slots hold NaN.
+    pub values: Vec<T>,
+    /// Bitmap with a set bit for every valid element, in least-significant bit order.
+    /// None if there are no nulls, as Arrow allows.
+    pub validity: Option<Vec<u8>>,
+    pub null_count: usize,
+}
+
+impl<T: Float + FromPrimitive> MipMap1D<T> {
+    /// Builds a mipmap from values with a validity bitmap, e.g. the buffers of an Arrow primitive array.
+    /// `validity` is a bitmap with a set bit for every valid element, in least-significant bit order, starting at bit `offset`.
+    /// Nulls are treated as missing samples, using [`NanPolicy::Missing`].
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// // The third element is null.
+    /// let mipmap = MipMap1D::from_validity(&[2.0, 4.0, 0.0, 8.0], Some((&[0b1011][..], 0)));
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [3.0, 8.0]);
+    /// assert_eq!(*mipmap.get_level(2).unwrap(), [14.0 / 3.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bitmap is shorter than `offset + values.len()` bits.
+    pub fn from_validity(values: &[T], validity: Option<(&[u8], usize)>) -> Self {
+        let source = values
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| match validity {
+                Some((bits, offset)) if !is_set(bits, offset + index) => T::nan(),
+                _ => value,
+            })
+            .collect();
+        Self::build(source, NanPolicy::Missing, Rounding::default())
+    }
+
+    /// Returns given level as values with a validity bitmap, where NaN elements are null.
+    /// With [`NanPolicy::Missing`], these are the buckets without any valid samples.
+    /// If the level is out of bounds, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mipmap = MipMap1D::from_validity(&[1.0, 2.0, 3.0], Some((&[0b011][..], 0)));
+    /// let level = mipmap.level_with_validity(1).unwrap();
+    /// assert_eq!(level.null_count, 1);
+    /// assert_eq!(level.validity, Some(vec![0b01]));
+    /// ```
+    pub fn level_with_validity(&self, level: usize) -> Option<ValidityLevel<T>> {
+        let values = self.get_level(level)?.to_vec();
+        let mut validity = vec![0u8; values.len().div_ceil(8)];
+        for (index, value) in values.iter().enumerate() {
+            if !value.is_nan() {
+                validity[index / 8] |= 1 << (index % 8);
+            }
+        }
+
+        let null_count = values.iter().filter(|value| value.is_nan()).count();
+        Some(ValidityLevel {
+            values,
+            validity: (null_count > 0).then_some(validity),
+            null_count,
+        })
+    }
+}
+
+/// Returns the bit at `index` of a bitmap in least-significant bit order.
+fn is_set(bits: &[u8], index: usize) -> bool {
+    bits[index / 8] & (1 << (index % 8)) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nulls_are_missing_samples() {
+        let values: Vec<f64> = (0..20).map(f64::from).collect();
+        // Every third element is null, and the bitmap starts at bit 3.
+        let mut bits = vec![0u8; 3 + 20usize.div_ceil(8)];
+        for index in (0..20usize).filter(|index| !index.is_multiple_of(3)) {
+            bits[(index + 3) / 8] |= 1 << ((index + 3) % 8);
+        }
+        let nulls: Vec<f64> = values
+            .iter()
+            .map(|&x| {
+                if (x as usize).is_multiple_of(3) {
+                    f64::NAN
+                } else {
+                    x
+                }
+            })
+            .collect();
+
+        let mipmap = MipMap1D::from_validity(&values, Some((&bits, 3)));
+        let expected = MipMap1D::with_nan_policy(nulls, NanPolicy::Missing);
+        for level in 1..expected.num_levels() {
+            assert_eq!(mipmap.get_level(level), expected.get_level(level));
+        }
+        assert_eq!(mipmap.level_with_validity(0).unwrap().null_count, 7);
+        assert_eq!(MipMap1D::from_validity(&values, None).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_export_without_nulls() {
+        let mipmap = MipMap1D::from_validity(&[1.0f32, 2.0, 3.0, 4.0], None);
+        let level = mipmap.level_with_validity(1).unwrap();
+
+        assert_eq!(level.values, [1.5, 3.5]);
+        assert_eq!((level.validity, level.null_count), (None, 0));
+        assert!(mipmap.level_with_validity(3).is_none());
+    }
+}
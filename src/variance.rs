@@ -0,0 +1,157 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// Count, mean and sum of squared deviations of the source samples in a bucket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Moments {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Moments {
+    fn of(value: f64) -> Self {
+        Self {
+            count: 1,
+            mean: value,
+            m2: 0.0,
+        }
+    }
+
+    /// Merges the moments of two adjacent buckets with the parallel variance formula.
+    fn merge(&self, other: &Self) -> Self {
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+
+        Self {
+            count,
+            mean: self.mean + delta * weight,
+            m2: self.m2 + other.m2 + delta * delta * self.count as f64 * weight,
+        }
+    }
+}
+
+/// Per-bucket variance of the source samples, with the same levels as a [`MipMap1D`] of the source.
+/// Lets charts render mean ± σ bands at any zoom level.
+/// Levels are merged exactly, so every bucket has the variance of all source samples it covers.
+/// Example:
+/// ```rust
+/// use mipmap_1d::MipMap1D;
+///
+/// let mipmap = MipMap1D::new(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+/// let variance = mipmap.variance_levels();
+/// assert_eq!(variance.variance(1, 0), Some(1.0));
+/// assert_eq!(variance.variance(3, 0), Some(4.0));
+/// assert_eq!(variance.variance(2, 1), Some(2.75));
+/// ```
+pub struct VarianceLevels {
+    data: Vec<Vec<Moments>>,
+}
+
+impl VarianceLevels {
+    /// Constructs the levels from the source samples.
+    fn new(source: &[f64]) -> Self {
+        let mut data = vec![source.iter().map(|&x| Moments::of(x)).collect::<Vec<_>>()];
+
+        while data[data.len() - 1].len() > 1 {
+            let mipmap = data[data.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => a.merge(b),
+                    _ => pair[0],
+                })
+                .collect();
+            data.push(mipmap);
+        }
+
+        Self { data }
+    }
+
+    /// Returns the total number of downsampled levels.
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the population variance of the source samples in bucket `index` of given level.
+    /// If the level or the index is out of bounds, returns None
+    pub fn variance(&self, level: usize, index: usize) -> Option<f64> {
+        let moments = self.data.get(level)?.get(index)?;
+        Some(moments.m2 / moments.count as f64)
+    }
+
+    /// Returns the population standard deviation of the source samples in bucket `index` of given level.
+    /// If the level or the index is out of bounds, returns None
+    #[cfg(feature = "std")]
+    pub fn stddev(&self, level: usize, index: usize) -> Option<f64> {
+        self.variance(level, index).map(f64::sqrt)
+    }
+
+    /// Returns the standard deviation of every bucket on given level, parallel to the level of the mipmap.
+    /// If the level is out of bounds, returns None
+    #[cfg(feature = "std")]
+    pub fn stddev_level(&self, level: usize) -> Option<Vec<f64>> {
+        let len = self.data.get(level)?.len();
+        (0..len).map(|index| self.stddev(level, index)).collect()
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Computes the variance of the source samples in every bucket.
+    pub fn variance_levels(&self) -> VarianceLevels {
+        let source: Vec<f64> = self
+            .get_level(0)
+            .unwrap()
+            .iter()
+            .map(|x| x.to_f64().unwrap())
+            .collect();
+
+        VarianceLevels::new(&source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Computes the variance directly from the samples.
+    fn naive_variance(samples: &[f64]) -> f64 {
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64
+    }
+
+    #[test]
+    fn test_matches_naive_variance() {
+        let source: Vec<f64> = (0..37)
+            .map(|x| (x as f64 * 1.3).sin() * 10.0 + x as f64)
+            .collect();
+        let variance = MipMap1D::new(source.clone()).variance_levels();
+
+        for level in 0..variance.num_levels() {
+            let size = 1 << level;
+            for (index, bucket) in source.chunks(size).enumerate() {
+                let expected = naive_variance(bucket);
+                let got = variance.variance(level, index).unwrap();
+                assert!(
+                    (got - expected).abs() < 1e-9,
+                    "level {level}, index {index}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_single_samples_have_no_variance() {
+        let variance = MipMap1D::new(vec![3, 7, 9]).variance_levels();
+
+        assert_eq!(variance.stddev_level(0).unwrap(), [0.0, 0.0, 0.0]);
+        assert_eq!(variance.variance(1, 0), Some(4.0));
+        assert_eq!(variance.variance(1, 1), Some(0.0));
+        assert_eq!(variance.variance(1, 2), None);
+        assert_eq!(variance.stddev_level(3), None);
+    }
+}
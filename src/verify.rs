@@ -0,0 +1,157 @@
+use core::error::Error;
+use core::fmt;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::is_nan;
+use crate::MipMap1D;
+
+/// Describes the first inconsistency found by [`MipMap1D::verify`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyError<T> {
+    /// The level does not have `ceil(len / 2)` elements of the level below.
+    Length {
+        level: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// The element at `index` of the level differs from the reduction of its bucket on the level below.
+    Value {
+        level: usize,
+        index: usize,
+        expected: T,
+        got: T,
+    },
+    /// The coarsest level has more than one element, although more levels are allowed.
+    Incomplete { levels: usize },
+}
+
+impl<T: fmt::Debug> fmt::Display for VerifyError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length {
+                level,
+                expected,
+                got,
+            } => write!(f, "level {level} has {got} elements, expected {expected}"),
+            Self::Value {
+                level,
+                index,
+                expected,
+                got,
+            } => write!(
+                f,
+                "element {index} of level {level} is {got:?}, expected {expected:?}"
+            ),
+            Self::Incomplete { levels } => {
+                write!(
+                    f,
+                    "the coarsest of {levels} levels has more than one element"
+                )
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> Error for VerifyError<T> {}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Recomputes every level from the one below, using the configured NaN policy and rounding,
+    /// and returns the first inconsistency.
+    /// NaNs are equal to each other.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let mut mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// mipmap.push(1);
+    /// assert_eq!(mipmap.verify(), Ok(()));
+    /// ```
+    pub fn verify(&self) -> Result<(), VerifyError<T>> {
+        let levels = self.num_levels();
+        for level in self.min_level() + 1..levels {
+            let (below, current) = (
+                self.get_level(level - 1).unwrap(),
+                self.get_level(level).unwrap(),
+            );
+            if current.len() != below.len().div_ceil(2) {
+                return Err(VerifyError::Length {
+                    level,
+                    expected: below.len().div_ceil(2),
+                    got: current.len(),
+                });
+            }
+
+            for (index, got) in current.iter().enumerate() {
+                let (expected, _) = self.reduce(level - 1, index);
+                if expected != *got && !(is_nan(&expected) && is_nan(got)) {
+                    return Err(VerifyError::Value {
+                        level,
+                        index,
+                        expected,
+                        got: got.clone(),
+                    });
+                }
+            }
+        }
+
+        if !self.is_complete() {
+            return Err(VerifyError::Incomplete { levels });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{NanPolicy, Rounding};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_consistent_mipmaps() {
+        let nan = f64::NAN;
+        for policy in [NanPolicy::Propagate, NanPolicy::Skip, NanPolicy::Missing] {
+            let mipmap = MipMap1D::with_nan_policy(vec![1.0, nan, nan, 4.0, 5.0], policy);
+            assert_eq!(mipmap.verify(), Ok(()));
+        }
+        assert_eq!(
+            MipMap1D::with_rounding(vec![1, 2, 3], Rounding::Ceil).verify(),
+            Ok(())
+        );
+        assert_eq!(MipMap1D::<u8>::new(vec![]).verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_first_mismatch() {
+        let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+        let mut tampered = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+        tampered.data_mut()[1][1] = 0;
+
+        assert_eq!(mipmap.verify(), Ok(()));
+        let error = tampered.verify().unwrap_err();
+        assert_eq!(
+            error,
+            VerifyError::Value {
+                level: 1,
+                index: 1,
+                expected: 7,
+                got: 0
+            }
+        );
+        assert_eq!(error.to_string(), "element 1 of level 1 is 0, expected 7");
+
+        tampered.data_mut()[1][1] = 7;
+        tampered.data_mut().pop(2);
+        assert_eq!(
+            tampered.verify(),
+            Err(VerifyError::Length {
+                level: 2,
+                expected: 2,
+                got: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::{Num, ToPrimitive};
+
+use crate::viewport::bucket_range;
+use crate::{StatsMipMap1D, Viewport};
+
+/// Layout of the vertex data exported by [`StatsMipMap1D::envelope_vertices`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexLayout {
+    /// One `(x, ymin, ymax)` vertex per bucket, e.g. for instanced rendering of vertical bars.
+    Interleaved,
+    /// Two `(x, y)` vertices per bucket, first at the minimum and then at the maximum,
+    /// which draw the filled envelope as a triangle strip.
+    TriangleStrip,
+}
+
+/// Vertex data of the envelope of a viewport, ready to be copied into a vertex buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvelopeVertices {
+    /// The level the buckets come from.
+    pub level: usize,
+    pub layout: VertexLayout,
+    /// Vertex components in the order given by `layout`.
+    /// The x coordinate of a bucket is the first source index it covers.
+    pub data: Vec<f32>,
+}
+
+impl EnvelopeVertices {
+    /// Returns the number of vertices.
+    pub fn vertex_count(&self) -> usize {
+        match self.layout {
+            VertexLayout::Interleaved => self.data.len() / 3,
+            VertexLayout::TriangleStrip => self.data.len() / 2,
+        }
+    }
+}
+
+impl<T: Num + ToPrimitive + Copy> StatsMipMap1D<T> {
+    /// Exports the minimum and maximum of the buckets of the finest level which displays the viewport
+    /// with at most `viewport.max_points` buckets, see [`MipMap1D::query`](crate::MipMap1D::query).
+    /// Buckets without valid samples have NaN coordinates.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{StatsMipMap1D, VertexLayout, Viewport};
+    ///
+    /// let mipmap = StatsMipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// let viewport = Viewport::new(0..5, 3);
+    /// let vertices = mipmap.envelope_vertices(&viewport, VertexLayout::Interleaved);
+    /// assert_eq!(vertices.level, 1);
+    /// assert_eq!(vertices.data, [0.0, 2.0, 4.0, 2.0, 6.0, 8.0, 4.0, 9.0, 9.0]);
+    /// let strip = mipmap.envelope_vertices(&viewport, VertexLayout::TriangleStrip);
+    /// assert_eq!(strip.vertex_count(), 6);
+    /// assert_eq!(strip.data[..4], [0.0, 2.0, 0.0, 4.0]);
+    /// ```
+    pub fn envelope_vertices(&self, viewport: &Viewport, layout: VertexLayout) -> EnvelopeVertices {
+        let len = self.get_level(0).unwrap().len();
+        let range = viewport.range.start.min(len)..viewport.range.end.min(len);
+        let level = viewport.level_for(&range).min(self.num_levels() - 1);
+        let buckets = bucket_range(&range, level);
+
+        let mut data = vec![];
+        for (index, stats) in self.get_level(level).unwrap()[buckets.clone()]
+            .iter()
+            .enumerate()
+        {
+            let x = ((buckets.start + index) << level) as f32;
+            let (min, max) = match stats.count {
+                0 => (f32::NAN, f32::NAN),
+                _ => (stats.min.to_f32().unwrap(), stats.max.to_f32().unwrap()),
+            };
+            match layout {
+                VertexLayout::Interleaved => data.extend([x, min, max]),
+                VertexLayout::TriangleStrip => data.extend([x, min, x, max]),
+            }
+        }
+
+        EnvelopeVertices {
+            level,
+            layout,
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vertices_follow_buckets() {
+        let source: Vec<f32> = (0..64).map(|x| (x as f32 * 0.3).sin()).collect();
+        let mipmap = StatsMipMap1D::new(source);
+        let vertices =
+            mipmap.envelope_vertices(&Viewport::new(10..50, 8), VertexLayout::Interleaved);
+
+        assert_eq!(vertices.level, 3);
+        assert_eq!(vertices.vertex_count(), 6);
+        for vertex in vertices.data.chunks(3) {
+            let bucket = mipmap.get_level(3).unwrap()[vertex[0] as usize >> 3];
+            assert_eq!(vertex[1..], [bucket.min, bucket.max]);
+        }
+    }
+
+    #[test]
+    fn test_empty_buckets_and_viewports() {
+        let mipmap = StatsMipMap1D::new(vec![f64::NAN, f64::NAN, 1.0]);
+        let strip = mipmap.envelope_vertices(&Viewport::new(0..3, 2), VertexLayout::TriangleStrip);
+        assert!(strip.data[1].is_nan());
+        assert_eq!(strip.data[4..], [2.0, 1.0, 2.0, 1.0]);
+
+        let empty = mipmap.envelope_vertices(&Viewport::new(5..9, 2), VertexLayout::Interleaved);
+        assert_eq!(empty.vertex_count(), 0);
+    }
+}
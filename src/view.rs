@@ -0,0 +1,111 @@
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::viewport::bucket_range;
+use crate::{LevelSlice, MipMap1D, Viewport};
+
+/// A window of source indices of a mipmap, created by [`MipMap1D::view`].
+/// It borrows the levels of the mipmap, so creating a view does not copy or rebuild anything.
+/// The buckets at the edges of the window may also cover samples outside of it.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{MipMap1D, Viewport};
+///
+/// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9, 1, 3, 5]);
+/// let view = mipmap.view(2..6);
+/// assert_eq!(*view.get_level(0).unwrap(), [6, 8, 9, 1]);
+/// assert_eq!(*view.get_level(1).unwrap(), [7, 5]);
+/// assert_eq!(view.query(&Viewport::new(0..100, 1)).data, [4]);
+/// ```
+pub struct MipMapView<'a, T: Num + ToPrimitive + FromPrimitive> {
+    mipmap: &'a MipMap1D<T>,
+    range: Range<usize>,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Returns a view restricted to the source `range`.
+    /// The part of the range beyond the source data is ignored.
+    pub fn view(&self, range: Range<usize>) -> MipMapView<'_, T> {
+        let len = self.len();
+        MipMapView {
+            mipmap: self,
+            range: range.start.min(len)..range.end.min(len),
+        }
+    }
+}
+
+impl<'a, T: Num + ToPrimitive + FromPrimitive + Clone> MipMapView<'a, T> {
+    /// Returns the source indices of the window.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the total number of downsampled levels of the mipmap.
+    pub fn num_levels(&self) -> usize {
+        self.mipmap.num_levels()
+    }
+
+    /// Returns the part of given level covering the window.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&'a [T]> {
+        let data = self.mipmap.get_level(level)?;
+        Some(&data[bucket_range(&self.range, level)])
+    }
+
+    /// Returns the finest level which covers the window with at most `max_points` elements.
+    /// Pruned levels are skipped. If even the coarsest level is larger, returns the coarsest level.
+    pub fn level_within(&self, max_points: usize) -> usize {
+        (self.mipmap.min_level()..self.num_levels())
+            .find(|&level| self.get_level(level).unwrap().len() <= max_points)
+            .unwrap_or(self.num_levels() - 1)
+    }
+
+    /// Same as [`MipMap1D::query`], but the viewport is restricted to the window.
+    pub fn query(&self, viewport: &Viewport) -> LevelSlice<'a, T> {
+        let start = viewport.range.start.clamp(self.range.start, self.range.end);
+        let end = viewport.range.end.clamp(start, self.range.end);
+        self.mipmap
+            .query(&Viewport::new(start..end, viewport.max_points))
+    }
+
+    /// Returns a view of the intersection of this window and `range`.
+    pub fn view(&self, range: Range<usize>) -> Self {
+        let start = range.start.clamp(self.range.start, self.range.end);
+        Self {
+            mipmap: self.mipmap,
+            range: start..range.end.clamp(start, self.range.end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_matches_queries() {
+        let mipmap = MipMap1D::new((0..100).collect::<Vec<i32>>());
+        let view = mipmap.view(20..70);
+
+        assert_eq!(view.range(), 20..70);
+        assert_eq!(view.num_levels(), mipmap.num_levels());
+        for max_points in [1, 5, 13, 50, 100] {
+            let slice = view.query(&Viewport::new(0..1000, max_points));
+            assert_eq!(slice, mipmap.query(&Viewport::new(20..70, max_points)));
+            assert_eq!(slice.data, view.get_level(slice.level).unwrap());
+        }
+        assert_eq!(view.level_within(50), 0);
+        assert_eq!(view.get_level(mipmap.num_levels()), None);
+    }
+
+    #[test]
+    fn test_nested_and_clipped_views() {
+        let mipmap = MipMap1D::new(vec![1.0, 2.0, 3.0, 4.0]);
+
+        assert_eq!(mipmap.view(2..10).range(), 2..4);
+        assert_eq!(mipmap.view(1..4).view(0..2).range(), 1..2);
+        assert_eq!(mipmap.view(1..3).view(5..9).range(), 3..3);
+        assert_eq!(mipmap.view(3..3).get_level(1), Some(&[][..]));
+    }
+}
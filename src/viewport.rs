@@ -0,0 +1,285 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// A window of source indices which should be displayed with at most `max_points` points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewport {
+    pub range: Range<usize>,
+    pub max_points: usize,
+}
+
+/// The part of a level which covers a viewport.
+#[derive(Debug, PartialEq)]
+pub struct LevelSlice<'a, T> {
+    /// The level the data comes from.
+    pub level: usize,
+    /// Index of the first element of `data` within the level.
+    pub start: usize,
+    pub data: &'a [T],
+}
+
+impl<T> LevelSlice<'_, T> {
+    /// Returns the range of source indices covered by the `index`-th element of `data`.
+    /// The last bucket of a level may actually cover fewer source samples.
+    pub fn source_range(&self, index: usize) -> Range<usize> {
+        let bucket = self.start + index;
+        (bucket << self.level)..((bucket + 1) << self.level)
+    }
+}
+
+impl Viewport {
+    pub fn new(range: Range<usize>, max_points: usize) -> Self {
+        Self { range, max_points }
+    }
+
+    /// Returns the finest level at which the source `range` is covered by at most `max_points` buckets.
+    /// Levels are not limited by any particular mipmap, so the result might be coarser than its top level.
+    pub(crate) fn level_for(&self, range: &Range<usize>) -> usize {
+        (0..usize::BITS as usize)
+            .find(|&level| bucket_range(range, level).len() <= self.max_points)
+            .unwrap_or(usize::BITS as usize - 1)
+    }
+}
+
+/// Returns the buckets of given level which cover the source `range`.
+pub(crate) fn bucket_range(range: &Range<usize>, level: usize) -> Range<usize> {
+    if range.is_empty() {
+        return 0..0;
+    }
+
+    (range.start >> level)..((range.end - 1) >> level) + 1
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Returns the data of the finest level which displays the viewport with at most `viewport.max_points` points.
+    /// The part of the viewport beyond the source data is ignored.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Viewport};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// let slice = mipmap.query(&Viewport::new(1..5, 2));
+    /// assert_eq!(slice.level, 2);
+    /// assert_eq!(slice.data, [5, 9]);
+    /// ```
+    pub fn query(&self, viewport: &Viewport) -> LevelSlice<'_, T> {
+        let range = self.clip(&viewport.range);
+        let slice = self.slice_at(viewport.level_for(&range), &range);
+
+        #[cfg(feature = "trace-hook")]
+        crate::trace::emit(|| crate::trace::TraceEvent::Queried {
+            level: slice.level,
+            points: slice.data.len(),
+        });
+        slice
+    }
+
+    /// Restricts a source range to the source data.
+    fn clip(&self, range: &Range<usize>) -> Range<usize> {
+        let len = self.len();
+        range.start.min(len)..range.end.min(len)
+    }
+
+    /// Returns the part of given level covering the source `range`. Levels beyond the coarsest one are
+    /// replaced by it, and pruned levels by the finest remaining one.
+    fn slice_at(&self, level: usize, range: &Range<usize>) -> LevelSlice<'_, T> {
+        let level = level.min(self.num_levels() - 1).max(self.min_level());
+        let buckets = bucket_range(range, level);
+
+        LevelSlice {
+            level,
+            start: buckets.start,
+            data: &self.get_level(level).unwrap()[buckets],
+        }
+    }
+}
+
+/// Queries several series sharing the same source indices (e.g. sampled at the same rate) at a consistent level.
+/// The level is chosen so that neither series exceeds `viewport.max_points` points,
+/// which gives all traces comparable point densities and aligned bucket boundaries.
+/// Series too short to have that level use their coarsest one.
+/// Example:
+/// ```rust
+/// use mipmap_1d::{viewport, MipMap1D, Viewport};
+///
+/// let long = MipMap1D::new(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+/// let short = MipMap1D::new(vec![1, 2, 3]);
+/// let slices = viewport::query_synced([&long, &short], &Viewport::new(0..8, 4));
+/// assert_eq!(slices[0].level, 1);
+/// assert_eq!(slices[0].data, [1, 3, 5, 7]);
+/// assert_eq!(slices[1].level, 1);
+/// assert_eq!(slices[1].data, [1, 3]);
+/// ```
+pub fn query_synced<'a, T: Num + ToPrimitive + FromPrimitive + Clone + 'a>(
+    mipmaps: impl IntoIterator<Item = &'a MipMap1D<T>>,
+    viewport: &Viewport,
+) -> Vec<LevelSlice<'a, T>> {
+    let mipmaps: Vec<_> = mipmaps.into_iter().collect();
+    let level = mipmaps
+        .iter()
+        .map(|mipmap| viewport.level_for(&mipmap.clip(&viewport.range)))
+        .max()
+        .unwrap_or(0);
+
+    mipmaps
+        .into_iter()
+        .map(|mipmap| mipmap.slice_at(level, &mipmap.clip(&viewport.range)))
+        .collect()
+}
+
+/// Iterator over the parts of successively finer levels which cover a viewport, created by [`MipMap1D::refine`].
+pub struct Refinement<'a, T: Num + ToPrimitive + FromPrimitive> {
+    mipmap: &'a MipMap1D<T>,
+    range: Range<usize>,
+    /// The next level, or None if the finest one was returned.
+    level: Option<usize>,
+    finest: usize,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> MipMap1D<T> {
+    /// Returns the parts of the levels covering the viewport, from the coarsest level down to the one
+    /// [`MipMap1D::query`] returns, so a renderer can paint a rough curve at once and refine it while time allows.
+    /// Each level has about twice as many points as the one before, so the total work is about twice that of the finest level.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{MipMap1D, Viewport};
+    ///
+    /// let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+    /// let levels: Vec<_> = mipmap.refine(&Viewport::new(0..5, 3)).map(|slice| slice.data).collect();
+    /// assert_eq!(levels, [&[7][..], &[5, 9], &[3, 7, 9]]);
+    /// // Without a limit, the refinement goes down to the source.
+    /// assert_eq!(mipmap.refine(&Viewport::new(0..usize::MAX, usize::MAX)).count(), 4);
+    /// ```
+    pub fn refine(&self, viewport: &Viewport) -> Refinement<'_, T> {
+        let range = self.clip(&viewport.range);
+        Refinement {
+            mipmap: self,
+            finest: self.query(viewport).level,
+            level: Some(self.num_levels() - 1),
+            range,
+        }
+    }
+}
+
+impl<'a, T: Num + ToPrimitive + FromPrimitive + Clone> Iterator for Refinement<'a, T> {
+    type Item = LevelSlice<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let level = self.level?;
+        self.level = (level > self.finest).then(|| level - 1);
+        Some(self.mipmap.slice_at(level, &self.range))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.level.map_or(0, |level| level - self.finest + 1);
+        (len, Some(len))
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Clone> ExactSizeIterator for Refinement<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_range() {
+        assert_eq!(bucket_range(&(0..5), 0), 0..5);
+        assert_eq!(bucket_range(&(1..5), 1), 0..3);
+        assert_eq!(bucket_range(&(2..4), 1), 1..2);
+        assert_eq!(bucket_range(&(3..3), 1), 0..0);
+    }
+
+    #[test]
+    fn test_query_levels() {
+        let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+
+        let slice = mipmap.query(&Viewport::new(0..5, 5));
+        assert_eq!(
+            (slice.level, slice.start, slice.data),
+            (0, 0, &[2, 4, 6, 8, 9][..])
+        );
+
+        let slice = mipmap.query(&Viewport::new(2..5, 2));
+        assert_eq!((slice.level, slice.start, slice.data), (1, 1, &[7, 9][..]));
+        assert_eq!(slice.source_range(0), 2..4);
+
+        let slice = mipmap.query(&Viewport::new(0..100, 0));
+        assert_eq!((slice.level, slice.data), (3, &[7][..]));
+    }
+
+    #[test]
+    fn test_query_outside_of_data() {
+        let mipmap = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+        let slice = mipmap.query(&Viewport::new(10..20, 4));
+
+        assert!(slice.data.is_empty());
+    }
+
+    #[test]
+    fn test_query_after_pruning() {
+        let mut mipmap = MipMap1D::new((0..16).collect::<Vec<i32>>());
+        mipmap.prune_below(2);
+
+        let slice = mipmap.query(&Viewport::new(4..12, 100));
+        assert_eq!((slice.level, slice.start), (2, 1));
+        assert_eq!(slice.data, &mipmap.get_level(2).unwrap()[1..3]);
+        let coarse = mipmap.query(&Viewport::new(0..16, 2));
+        assert_eq!((coarse.level, coarse.data.len()), (3, 2));
+        assert_eq!(mipmap.refine(&Viewport::new(0..16, 100)).count(), 3);
+        assert_eq!(mipmap.view(4..12).level_within(100), 2);
+    }
+
+    #[test]
+    fn test_synced_levels_are_aligned() {
+        let a = MipMap1D::new((0..16).collect());
+        let b = MipMap1D::new((0..6).collect());
+        let slices = query_synced([&a, &b], &Viewport::new(2..16, 4));
+
+        assert_eq!(slices[0].level, 2);
+        assert_eq!(slices[0].start, 0);
+        assert_eq!(slices[0].data.len(), 4);
+        assert_eq!(slices[1].level, 2);
+        assert_eq!(slices[1].start, 0);
+        assert_eq!(slices[1].data.len(), 2);
+        assert!(query_synced::<i32>([], &Viewport::new(0..1, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_refinement_ends_at_query_level() {
+        let mipmap = MipMap1D::new((0..100).collect());
+        let viewport = Viewport::new(20..60, 10);
+        let slices: Vec<_> = mipmap.refine(&viewport).collect();
+
+        assert_eq!(slices.len(), 6);
+        assert_eq!(mipmap.refine(&viewport).len(), 6);
+        assert!(slices
+            .windows(2)
+            .all(|pair| pair[0].level == pair[1].level + 1));
+        assert_eq!(*slices.last().unwrap(), mipmap.query(&viewport));
+        assert!(mipmap
+            .refine(&Viewport::new(200..300, 10))
+            .all(|slice| slice.data.is_empty()));
+    }
+
+    #[test]
+    fn test_clone_only_samples() {
+        use crate::fixtures::CloneInt;
+
+        let mipmap = MipMap1D::new((0..16).map(CloneInt).collect());
+        let short = MipMap1D::new((0..6).map(CloneInt).collect());
+        let viewport = Viewport::new(4..12, 4);
+
+        let slice = mipmap.query(&viewport);
+        assert_eq!((slice.level, slice.start), (1, 2));
+        assert_eq!(slice.data, &mipmap.get_level(1).unwrap()[2..6]);
+        let slices = query_synced([&mipmap, &short], &viewport);
+        assert_eq!((slices[1].level, slices[1].data.len()), (1, 1));
+        assert_eq!(mipmap.refine(&viewport).last().unwrap(), slice);
+        assert_eq!(mipmap.view(4..12).query(&viewport), slice);
+    }
+}
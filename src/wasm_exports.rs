@@ -0,0 +1,184 @@
+//! Raw WebAssembly exports for JavaScript, on top of the C API of [`crate::ffi`].
+//! A WebAssembly module is built with
+//! `cargo rustc --release --target wasm32-unknown-unknown --features wasm-exports --crate-type cdylib`.
+//! The crate does not use wasm-bindgen, so there are no generated JavaScript classes or typed arrays:
+//! every export takes and returns plain numbers, so it can be called without any JavaScript glue.
+//! Samples are read through `Float64Array` views over the memory of the module, without copying:
+//! ```js
+//! const { memory, ...mipmap } = (await WebAssembly.instantiate(bytes)).instance.exports;
+//! const buffer = mipmap.mipmap_f64_alloc(samples.length);
+//! new Float64Array(memory.buffer, buffer, samples.length).set(samples);
+//! const handle = mipmap.mipmap_f64_from_buffer(buffer, samples.length);
+//!
+//! const level = mipmap.mipmap_f64_query_level(handle, start, end, width);
+//! const offset = mipmap.mipmap_f64_query_start(handle, start, end, width);
+//! const points = new Float64Array(
+//!     memory.buffer,
+//!     mipmap.mipmap_f64_level_ptr(handle, level) + offset * 8,
+//!     mipmap.mipmap_f64_query_len(handle, start, end, width),
+//! );
+//! ```
+//! Views become detached when the memory grows, e.g. after pushing samples, and must be created again.
+
+use std::ptr;
+
+use crate::ffi::MipMapF64;
+use crate::viewport::{LevelSlice, Viewport};
+use crate::MipMap1D;
+
+/// Allocates a buffer of `len` zeroed samples, which JavaScript fills before passing it to [`mipmap_f64_from_buffer`].
+#[no_mangle]
+pub extern "C" fn mipmap_f64_alloc(len: usize) -> *mut f64 {
+    Box::into_raw(vec![0.0; len].into_boxed_slice()).cast()
+}
+
+/// Releases a buffer which was not passed to [`mipmap_f64_from_buffer`].
+///
+/// # Safety
+///
+/// `data` must be a buffer of `len` samples returned by [`mipmap_f64_alloc`].
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_dealloc(data: *mut f64, len: usize) {
+    drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)) });
+}
+
+/// Creates a mipmap which takes over a buffer returned by [`mipmap_f64_alloc`].
+/// The handle must be released with [`crate::ffi::mipmap_f64_free`].
+///
+/// # Safety
+///
+/// `data` must be a buffer of `len` samples returned by [`mipmap_f64_alloc`], which is not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_from_buffer(data: *mut f64, len: usize) -> *mut MipMapF64 {
+    let source = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)) };
+    Box::into_raw(Box::new(MipMapF64(MipMap1D::new(source.into_vec()))))
+}
+
+/// Returns the address of the data on given level. If the level is out of bounds, returns null.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_level_ptr(
+    mipmap: *const MipMapF64,
+    level: usize,
+) -> *const f64 {
+    match unsafe { &*mipmap }.0.get_level(level) {
+        Some(data) => data.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// Returns the length of given level. If the level is out of bounds, returns `0`.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_level_len(mipmap: *const MipMapF64, level: usize) -> usize {
+    unsafe { &*mipmap }
+        .0
+        .get_level(level)
+        .map_or(0, |data| data.len())
+}
+
+/// Returns the level which displays the source samples `start..end` with at most `max_points` points.
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_query_level(
+    mipmap: *const MipMapF64,
+    start: usize,
+    end: usize,
+    max_points: usize,
+) -> usize {
+    unsafe { query(mipmap, start, end, max_points) }.level
+}
+
+/// Returns the index of the first point of the viewport within [`mipmap_f64_query_level`].
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_query_start(
+    mipmap: *const MipMapF64,
+    start: usize,
+    end: usize,
+    max_points: usize,
+) -> usize {
+    unsafe { query(mipmap, start, end, max_points) }.start
+}
+
+/// Returns the number of points of the viewport on [`mipmap_f64_query_level`].
+///
+/// # Safety
+///
+/// `mipmap` must be a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn mipmap_f64_query_len(
+    mipmap: *const MipMapF64,
+    start: usize,
+    end: usize,
+    max_points: usize,
+) -> usize {
+    unsafe { query(mipmap, start, end, max_points) }.data.len()
+}
+
+/// Queries the viewport of the source samples `start..end`.
+unsafe fn query<'a>(
+    mipmap: *const MipMapF64,
+    start: usize,
+    end: usize,
+    max_points: usize,
+) -> LevelSlice<'a, f64> {
+    unsafe { &*mipmap }
+        .0
+        .query(&Viewport::new(start..end, max_points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::mipmap_f64_free;
+
+    #[test]
+    fn test_buffer_handover() {
+        unsafe {
+            let buffer = mipmap_f64_alloc(5);
+            std::slice::from_raw_parts_mut(buffer, 5).copy_from_slice(&[2.0, 4.0, 6.0, 8.0, 9.0]);
+            let mipmap = mipmap_f64_from_buffer(buffer, 5);
+
+            let level = mipmap_f64_level_ptr(mipmap, 0);
+            assert_eq!(
+                std::slice::from_raw_parts(level, 5),
+                [2.0, 4.0, 6.0, 8.0, 9.0]
+            );
+            assert_eq!(mipmap_f64_level_len(mipmap, 1), 3);
+            assert!(mipmap_f64_level_ptr(mipmap, 4).is_null());
+            assert_eq!(mipmap_f64_level_len(mipmap, 4), 0);
+            mipmap_f64_free(mipmap);
+
+            mipmap_f64_dealloc(mipmap_f64_alloc(3), 3);
+        }
+    }
+
+    #[test]
+    fn test_query_view() {
+        let source = [2.0, 4.0, 6.0, 8.0, 9.0, 1.0, 3.0, 5.0];
+        unsafe {
+            let mipmap = crate::ffi::mipmap_f64_new(source.as_ptr(), source.len());
+            let level = mipmap_f64_query_level(mipmap, 2, 8, 3);
+            let start = mipmap_f64_query_start(mipmap, 2, 8, 3);
+            let len = mipmap_f64_query_len(mipmap, 2, 8, 3);
+            assert_eq!((level, start, len), (1, 1, 3));
+
+            let data = mipmap_f64_level_ptr(mipmap, level).add(start);
+            assert_eq!(std::slice::from_raw_parts(data, len), [7.0, 5.0, 4.0]);
+            mipmap_f64_free(mipmap);
+        }
+    }
+}
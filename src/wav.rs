@@ -0,0 +1,232 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use crate::StatsMipMap1D;
+
+/// Number of frames decoded at once.
+const BLOCK_FRAMES: usize = 4096;
+
+/// Sample encodings of the `fmt ` chunk.
+const PCM: u16 = 1;
+const IEEE_FLOAT: u16 = 3;
+const EXTENSIBLE: u16 = 0xFFFE;
+
+impl StatsMipMap1D<f32> {
+    /// Decodes one channel of a WAV file into a mipmap of min/max envelopes, e.g. for the waveform view of an audio editor.
+    /// Integer samples are scaled to `-1.0..1.0`. Supported are 8, 16, 24 and 32 bit PCM and 32 and 64 bit float samples.
+    /// Files which are not supported are reported as [`io::ErrorKind::InvalidData`],
+    /// and a channel beyond the channels of the file as [`io::ErrorKind::InvalidInput`].
+    /// Example:
+    /// ```rust,no_run
+    /// use mipmap_1d::StatsMipMap1D;
+    ///
+    /// let left = StatsMipMap1D::from_wav("song.wav", 0).unwrap();
+    /// let envelope = left.get_level(10).unwrap();
+    /// println!("{} buckets of 1024 samples", envelope.len());
+    /// ```
+    pub fn from_wav(path: impl AsRef<Path>, channel: usize) -> io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let mut channels = read_wav(file, |index| index == channel)?;
+        if channel >= channels.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("the file has no channel {channel}"),
+            ));
+        }
+        Ok(Self::new(channels.swap_remove(channel)))
+    }
+
+    /// Same as [`StatsMipMap1D::from_wav`], but decodes every channel into its own mipmap.
+    pub fn from_wav_channels(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let file = BufReader::new(File::open(path)?);
+        let channels = read_wav(file, |_| true)?;
+        Ok(channels.into_iter().map(Self::new).collect())
+    }
+}
+
+/// Decodes the samples of the channels for which `keep` is true, block by block.
+/// The other channels are returned empty.
+fn read_wav(mut reader: impl Read, keep: impl Fn(usize) -> bool) -> io::Result<Vec<Vec<f32>>> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Err(invalid("the file is not a WAV file"));
+    }
+
+    let mut format = None;
+    loop {
+        let mut chunk = [0u8; 8];
+        reader.read_exact(&mut chunk)?;
+        let len = u32::from_le_bytes(chunk[4..].try_into().unwrap()) as u64;
+        match &chunk[..4] {
+            b"fmt " => {
+                // The extensible format, the longest one, has 40 bytes.
+                match len {
+                    0..16 => return Err(invalid("the format chunk is truncated")),
+                    41.. => return Err(invalid("the format chunk is too long")),
+                    _ => {}
+                }
+                let mut fmt = vec![0u8; len as usize];
+                reader.read_exact(&mut fmt)?;
+                let field = |at: usize| u16::from_le_bytes([fmt[at], fmt[at + 1]]);
+                let tag = match field(0) {
+                    EXTENSIBLE if fmt.len() >= 26 => field(24),
+                    tag => tag,
+                };
+                format = Some((tag, field(2) as usize, field(14)));
+                if len % 2 == 1 {
+                    io::copy(&mut (&mut reader).take(1), &mut io::sink())?;
+                }
+            }
+            b"data" => {
+                let (tag, channels, bits) =
+                    format.ok_or_else(|| invalid("the data chunk precedes the format chunk"))?;
+                let decode = decoder(tag, bits)
+                    .ok_or_else(|| invalid("the sample format is not supported"))?;
+                if channels == 0 {
+                    return Err(invalid("the file has no channels"));
+                }
+                return read_samples(reader.take(len), channels, bits as usize / 8, decode, keep);
+            }
+            _ => {
+                io::copy(&mut (&mut reader).take(len + len % 2), &mut io::sink())?;
+            }
+        }
+    }
+}
+
+/// Returns the function converting the little-endian bytes of a sample to `-1.0..1.0`.
+fn decoder(tag: u16, bits: u16) -> Option<fn(&[u8]) -> f32> {
+    let decode: fn(&[u8]) -> f32 = match (tag, bits) {
+        (PCM, 8) => |bytes| (bytes[0] as f32 - 128.0) / 128.0,
+        (PCM, 16) => |bytes| i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+        (PCM, 24) => |bytes| {
+            (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8_388_608.0
+        },
+        (PCM, 32) => |bytes| i32::from_le_bytes(bytes.try_into().unwrap()) as f32 / 2_147_483_648.0,
+        (IEEE_FLOAT, 32) => |bytes| f32::from_le_bytes(bytes.try_into().unwrap()),
+        (IEEE_FLOAT, 64) => |bytes| f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        _ => return None,
+    };
+    Some(decode)
+}
+
+/// Decodes interleaved frames, keeping the samples of the selected channels.
+fn read_samples(
+    mut reader: impl Read,
+    channels: usize,
+    sample_len: usize,
+    decode: fn(&[u8]) -> f32,
+    keep: impl Fn(usize) -> bool,
+) -> io::Result<Vec<Vec<f32>>> {
+    let mut samples = vec![vec![]; channels];
+    let frame_len = channels * sample_len;
+    let mut block = vec![0u8; BLOCK_FRAMES * frame_len];
+    let mut filled = 0;
+    loop {
+        let read = reader.read(&mut block[filled..])?;
+        filled += read;
+        let frames = filled / frame_len;
+        for frame in block[..frames * frame_len].chunks_exact(frame_len) {
+            for (channel, sample) in frame.chunks_exact(sample_len).enumerate() {
+                if keep(channel) {
+                    samples[channel].push(decode(sample));
+                }
+            }
+        }
+        // Keeps an incomplete frame for the next read.
+        block.copy_within(frames * frame_len..filled, 0);
+        filled -= frames * frame_len;
+        if read == 0 {
+            return Ok(samples);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes interleaved samples as a WAV file.
+    fn wav(tag: u16, channels: u16, bits: u16, data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        // An unknown chunk of odd length, which is padded.
+        bytes.extend(b"LIST\x03\0\0\0abc\0");
+        bytes.extend(b"fmt \x10\0\0\0");
+        for field in [tag, channels, 0x44AC, 0, 0, 0, channels * bits / 8, bits] {
+            bytes.extend(field.to_le_bytes());
+        }
+        bytes.extend(b"data");
+        bytes.extend((data.len() as u32).to_le_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_formats() {
+        let pcm16: Vec<u8> = [0i16, 16384, -32768, 32767]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let channels = read_wav(&wav(PCM, 2, 16, &pcm16)[..], |_| true).unwrap();
+        assert_eq!(channels, [vec![0.0, -1.0], vec![0.5, 32767.0 / 32768.0]]);
+
+        let pcm24 = [0x00, 0x00, 0x80, 0xFF, 0xFF, 0x7F];
+        assert_eq!(
+            read_wav(&wav(PCM, 1, 24, &pcm24)[..], |_| true).unwrap(),
+            [vec![-1.0, 8_388_607.0 / 8_388_608.0]]
+        );
+        assert_eq!(
+            read_wav(&wav(PCM, 1, 8, &[0, 128, 192])[..], |_| true).unwrap(),
+            [vec![-1.0, 0.0, 0.5]]
+        );
+
+        let float: Vec<u8> = [0.25f32, -0.75]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        assert_eq!(
+            read_wav(&wav(IEEE_FLOAT, 2, 32, &float)[..], |channel| channel == 1).unwrap(),
+            [vec![], vec![-0.75]]
+        );
+    }
+
+    #[test]
+    fn test_envelopes_of_channels() {
+        let samples: Vec<i16> = (0..10_000).flat_map(|x| [x as i16, -(x as i16)]).collect();
+        let data: Vec<u8> = samples.iter().flat_map(|x| x.to_le_bytes()).collect();
+        let path = std::env::temp_dir().join(format!("mipmap-1d-wav-{}.wav", std::process::id()));
+        std::fs::write(&path, wav(PCM, 2, 16, &data)).unwrap();
+        let channels = StatsMipMap1D::from_wav_channels(&path).unwrap();
+        let right = StatsMipMap1D::from_wav(&path, 1).unwrap();
+        let missing = StatsMipMap1D::from_wav(&path, 2).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(channels.len(), 2);
+        let top = channels[0].get_level(channels[0].num_levels() - 1).unwrap()[0];
+        assert_eq!(
+            (top.min, top.max, top.count),
+            (0.0, 9999.0 / 32768.0, 10_000)
+        );
+        assert_eq!(right.get_level(3), channels[1].get_level(3));
+        assert_eq!(missing.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_unsupported_files() {
+        let error = |bytes: &[u8]| read_wav(bytes, |_| true).err().unwrap().to_string();
+        assert_eq!(error(b"RIFF\0\0\0\0AVI LIST"), "the file is not a WAV file");
+        assert_eq!(
+            error(&wav(PCM, 1, 12, &[0, 0])),
+            "the sample format is not supported"
+        );
+
+        let mut huge = wav(PCM, 1, 16, &[0, 0]);
+        huge[28..32].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(error(&huge), "the format chunk is too long");
+        huge[28..32].copy_from_slice(&8u32.to_le_bytes());
+        assert_eq!(error(&huge), "the format chunk is truncated");
+    }
+}
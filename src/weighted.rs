@@ -0,0 +1,247 @@
+use alloc::{vec, vec::Vec};
+
+use num_traits::float::FloatCore;
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::mipmap::{is_integral, rounds_up};
+use crate::resample::weighted_mean;
+use crate::Rounding;
+
+/// Creates several downsampled versions of given vector, where every sample carries a weight.
+/// Each coarse value is the weighted mean of the source samples in its bucket,
+/// and the total weight of the bucket is carried upward, so higher levels remain exact.
+/// Example:
+/// ```rust
+/// use mipmap_1d::WeightedMipMap1D;
+///
+/// let data = vec![2.0, 4.0, 6.0, 8.0];
+/// let weights = vec![3.0, 1.0, 1.0, 1.0];
+/// let mipmap = WeightedMipMap1D::new(data, weights);
+/// assert_eq!(mipmap.num_levels(), 3);
+/// assert_eq!(*mipmap.get_level(1).unwrap(), [2.5, 7.0]);
+/// assert_eq!(*mipmap.get_weights(1).unwrap(), [4.0, 2.0]);
+/// assert_eq!(*mipmap.get_level(2).unwrap(), [4.0]);
+/// assert_eq!(*mipmap.get_weights(2).unwrap(), [6.0]);
+/// ```
+pub struct WeightedMipMap1D<T: Num + ToPrimitive + FromPrimitive> {
+    data: Vec<Vec<T>>,
+    weights: Vec<Vec<f64>>,
+    rounding: Rounding,
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> WeightedMipMap1D<T> {
+    /// Constructs the levels from the samples and their weights.
+    ///
+    /// # Panics
+    /// Panics if `source` and `weights` have different lengths.
+    pub fn new(source: Vec<T>, weights: Vec<f64>) -> Self {
+        Self::with_rounding(source, weights, Rounding::default())
+    }
+
+    /// Same as [`WeightedMipMap1D::new`], but integer means are rounded according to `rounding`.
+    /// Buckets whose weights are whole numbers are averaged exactly, others through `f64`.
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::{Rounding, WeightedMipMap1D};
+    ///
+    /// let data = vec![1, 2, -4, -3];
+    /// let weights = vec![1.0, 3.0, 1.0, 1.0];
+    /// let mipmap = WeightedMipMap1D::with_rounding(data, weights, Rounding::HalfEven);
+    /// assert_eq!(*mipmap.get_level(1).unwrap(), [2, -4]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `source` and `weights` have different lengths.
+    pub fn with_rounding(source: Vec<T>, weights: Vec<f64>, rounding: Rounding) -> Self {
+        assert_eq!(
+            source.len(),
+            weights.len(),
+            "Every sample must have exactly one weight"
+        );
+
+        let mut mipmap = Self {
+            data: vec![source],
+            weights: vec![weights],
+            rounding,
+        };
+
+        while mipmap.data[mipmap.data.len() - 1].len() > 1 {
+            let (level, level_weights) = mipmap.downsample(
+                &mipmap.data[mipmap.data.len() - 1],
+                &mipmap.weights[mipmap.weights.len() - 1],
+            );
+            mipmap.data.push(level);
+            mipmap.weights.push(level_weights);
+        }
+
+        mipmap
+    }
+
+    /// Returns the total number of downsampled levels.
+    /// Equal to `ceil(log2(source.len())`
+    pub fn num_levels(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the data on given level.
+    /// Level `0` returns the source data.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<&Vec<T>> {
+        self.data.get(level)
+    }
+
+    /// Returns the total weight of every bucket on given level.
+    /// Level `0` returns the source weights.
+    /// If the level is out of bounds, returns None
+    pub fn get_weights(&self, level: usize) -> Option<&Vec<f64>> {
+        self.weights.get(level)
+    }
+
+    /// Returns the rounding of integer means.
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Downsamples a vector to `ceil(len / 2)` elements by taking weighted means of the pairs.
+    /// Pairs with zero total weight are averaged evenly.
+    fn downsample(&self, source: &[T], weights: &[f64]) -> (Vec<T>, Vec<f64>) {
+        source
+            .chunks(2)
+            .zip(weights.chunks(2))
+            .map(|(values, weights)| {
+                let total: f64 = weights.iter().sum();
+                let mean = if total == 0.0 {
+                    self.mean(values, &[1.0; 2][..values.len()])
+                } else {
+                    self.mean(values, weights)
+                };
+
+                (mean, total)
+            })
+            .unzip()
+    }
+
+    /// Computes the weighted mean of a bucket with positive total weight.
+    /// Integers with whole weights take the exact path of [`MipMap1D`](crate::MipMap1D) means,
+    /// other integer means are rounded from `f64` according to the rounding.
+    fn mean(&self, values: &[T], weights: &[f64]) -> T {
+        let whole: Option<Vec<_>> = weights
+            .iter()
+            .map(|&w| match FloatCore::fract(w) {
+                0.0 => w.to_usize(),
+                _ => None,
+            })
+            .collect();
+        if let (true, Some(whole)) = (is_integral::<T>(), whole) {
+            let elements: Vec<_> = values.iter().zip(whole).collect();
+            if let Some(mean) = weighted_mean(elements.iter(), self.rounding) {
+                return mean;
+            }
+        }
+
+        let total: f64 = weights.iter().sum();
+        let mean = values
+            .iter()
+            .zip(weights)
+            .map(|(x, w)| x.to_f64().unwrap() * w)
+            .sum::<f64>()
+            / total;
+        let mean = if is_integral::<T>() {
+            round(mean, self.rounding)
+        } else {
+            mean
+        };
+
+        // The mean lies between the values, but may be rounded past the largest integer of the type.
+        T::from_f64(mean).unwrap_or_else(|| {
+            let distance = |x: &&T| FloatCore::abs(x.to_f64().unwrap() - mean);
+            let closest = values
+                .iter()
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)));
+            *closest.unwrap()
+        })
+    }
+}
+
+/// Rounds a mean to an integer according to `rounding`, like the exact integer means.
+fn round(mean: f64, rounding: Rounding) -> f64 {
+    let floor = FloatCore::floor(mean);
+    let remainder = mean - floor;
+    let up = match rounding {
+        _ if remainder == 0.0 => false,
+        Rounding::HalfEven | Rounding::HalfAway if remainder != 0.5 => remainder > 0.5,
+        _ => rounds_up(rounding, floor < 0.0, floor % 2.0 != 0.0),
+    };
+    floor + f64::from(u8::from(up))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_weights_give_exact_mean() {
+        let data = vec![2.0, 4.0, 6.0, 8.0, 9.0];
+        let mipmap = WeightedMipMap1D::new(data, vec![1.0; 5]);
+        let target = [
+            vec![2.0, 4.0, 6.0, 8.0, 9.0],
+            vec![3.0, 7.0, 9.0],
+            vec![5.0, 9.0],
+            vec![5.8],
+        ];
+
+        assert_eq!(mipmap.data, target);
+    }
+
+    #[test]
+    fn test_weights_are_carried_upward() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let weights = vec![1.0, 1.0, 2.0, 0.0, 4.0];
+        let mipmap = WeightedMipMap1D::new(data, weights);
+
+        assert_eq!(mipmap.weights[1], [2.0, 2.0, 4.0]);
+        assert_eq!(mipmap.weights[2], [4.0, 4.0]);
+        assert_eq!(mipmap.weights[3], [8.0]);
+        // The top level is the weighted mean of all source samples.
+        assert_eq!(mipmap.data[3], [(1.0 + 2.0 + 6.0 + 20.0) / 8.0]);
+    }
+
+    #[test]
+    fn test_zero_weight_pair() {
+        let mipmap = WeightedMipMap1D::new(vec![3.0, 5.0], vec![0.0, 0.0]);
+
+        assert_eq!(*mipmap.get_level(1).unwrap(), [4.0]);
+        assert_eq!(*mipmap.get_weights(1).unwrap(), [0.0]);
+    }
+
+    #[test]
+    fn test_integer_means_follow_rounding() {
+        let data = vec![1, 2, 4, 5, -10, 0];
+        let weights = vec![1.0, 1.0, 1.0, 1.0, 1.5, 0.5];
+        let ceil = WeightedMipMap1D::with_rounding(data.clone(), weights.clone(), Rounding::Ceil);
+        assert_eq!(*ceil.get_level(1).unwrap(), [2, 5, -7]);
+        assert_eq!(*ceil.get_level(2).unwrap(), [4, -7]);
+
+        let floor = WeightedMipMap1D::with_rounding(data, weights, Rounding::Floor);
+        assert_eq!(*floor.get_level(1).unwrap(), [1, 4, -8]);
+        assert_eq!(*floor.get_level(2).unwrap(), [2, -8]);
+        assert_eq!(floor.rounding(), Rounding::Floor);
+    }
+
+    #[test]
+    fn test_extreme_integers() {
+        let data = vec![i64::MAX, i64::MAX - 1, i64::MIN, i64::MIN + 1];
+        let exact = WeightedMipMap1D::new(data.clone(), vec![1.0; 4]);
+        assert_eq!(*exact.get_level(1).unwrap(), [i64::MAX - 1, i64::MIN + 1]);
+
+        // The `f64` mean is rounded past `i64::MAX`, so the closest value is kept.
+        let rounded = WeightedMipMap1D::new(data, vec![0.5; 4]);
+        assert_eq!(*rounded.get_level(1).unwrap(), [i64::MAX, i64::MIN]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mismatched_weights() {
+        WeightedMipMap1D::new(vec![1, 2, 3], vec![1.0]);
+    }
+}
@@ -0,0 +1,89 @@
+use num_traits::{FromPrimitive, Num, ToPrimitive};
+
+use crate::MipMap1D;
+
+/// A joined view of two mipmaps built from series of equal length.
+/// Both mipmaps have the same levels, so they are always accessed in lockstep.
+/// Created by [`MipMap1D::zip`].
+pub struct ZippedMipMap<'a, A, B>
+where
+    A: Num + ToPrimitive + FromPrimitive,
+    B: Num + ToPrimitive + FromPrimitive,
+{
+    first: &'a MipMap1D<A>,
+    second: &'a MipMap1D<B>,
+}
+
+impl<'a, A, B> ZippedMipMap<'a, A, B>
+where
+    A: Num + ToPrimitive + FromPrimitive + Copy,
+    B: Num + ToPrimitive + FromPrimitive + Copy,
+{
+    /// Returns the total number of downsampled levels, which is the same for both mipmaps.
+    pub fn num_levels(&self) -> usize {
+        self.first.num_levels()
+    }
+
+    /// Returns the pairs of values on given level.
+    /// If the level is out of bounds, returns None
+    pub fn get_level(&self, level: usize) -> Option<impl Iterator<Item = (A, B)> + 'a> {
+        let first = self.first.get_level(level)?;
+        let second = self.second.get_level(level)?;
+
+        Some(first.iter().copied().zip(second.iter().copied()))
+    }
+}
+
+impl<T: Num + ToPrimitive + FromPrimitive + Copy> MipMap1D<T> {
+    /// Joins two mipmaps built from series of the same length, keeping their levels in lockstep.
+    /// Useful for plotting value-vs-value or computing per-level ratios.
+    /// If the source lengths differ, returns None
+    /// Example:
+    /// ```rust
+    /// use mipmap_1d::MipMap1D;
+    ///
+    /// let values = MipMap1D::new(vec![2.0, 4.0, 6.0, 8.0]);
+    /// let baseline = MipMap1D::new(vec![1.0, 1.0, 2.0, 2.0]);
+    /// let zipped = values.zip(&baseline).unwrap();
+    /// let ratios: Vec<f64> = zipped.get_level(1).unwrap().map(|(a, b)| a / b).collect();
+    /// assert_eq!(ratios, [3.0, 3.5]);
+    /// ```
+    pub fn zip<'a, U: Num + ToPrimitive + FromPrimitive + Copy>(
+        &'a self,
+        other: &'a MipMap1D<U>,
+    ) -> Option<ZippedMipMap<'a, T, U>> {
+        if self.get_level(0)?.len() != other.get_level(0)?.len() {
+            return None;
+        }
+
+        Some(ZippedMipMap {
+            first: self,
+            second: other,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zipped_levels() {
+        let a = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+        let b = MipMap1D::new(vec![1.0, 3.0, 5.0, 7.0, 9.0]);
+        let zipped = a.zip(&b).unwrap();
+
+        assert_eq!(zipped.num_levels(), 4);
+        let level: Vec<_> = zipped.get_level(1).unwrap().collect();
+        assert_eq!(level, [(3, 2.0), (7, 6.0), (9, 9.0)]);
+        assert!(zipped.get_level(4).is_none());
+    }
+
+    #[test]
+    fn test_zip_different_lengths() {
+        let a = MipMap1D::new(vec![2, 4, 6, 8, 9]);
+        let b = MipMap1D::new(vec![2, 4, 6, 8]);
+
+        assert!(a.zip(&b).is_none());
+    }
+}